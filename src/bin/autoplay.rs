@@ -0,0 +1,83 @@
+extern crate rpg;
+
+use rpg::bot::{autoplay, Outcome};
+use rpg::character::class::{Class, CLASSES};
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "Usage: autoplay [--games N] [--seed N] [--turns N] [--class Warrior|Rogue|Ranger]";
+
+/// Plays seeded games with the bot and prints one CSV line per game,
+/// fails if a game crashed or got stuck.
+fn main() {
+    let config = match parse_args(env::args().skip(1).collect()) {
+        Result::Ok(config) => config,
+        Result::Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        },
+    };
+
+    println!("seed,class,outcome,depth,turns,kills");
+
+    let mut failures = 0;
+    let mut deepest = 0;
+
+    for seed in config.seed..config.seed + config.games {
+        //Without a class every class gets its turn.
+        let class = config.class.unwrap_or(CLASSES[seed % CLASSES.len()]);
+        let report = autoplay(class, seed, config.turns);
+
+        println!("{},{},{},{},{},{}", report.seed, class.name(), report.outcome.name(), report.depth, report.turns, report.kills);
+
+        if let Outcome::Crashed(ref message) = report.outcome {
+            eprintln!("Seed {} crashed: {}", seed, message);
+        }
+        if matches!(report.outcome, Outcome::Crashed(..) | Outcome::Stuck) {
+            failures += 1;
+        }
+        deepest = deepest.max(report.depth);
+    }
+
+    eprintln!("{} games, {} crashed or stuck, deepest level {}", config.games, failures, deepest);
+
+    if failures > 0 {
+        process::exit(2);
+    }
+}
+
+struct Config {
+    games: usize,
+    seed: usize,
+    turns: u32,
+    class: Option<Class>,
+}
+
+fn parse_args(args: Vec<String>) -> Result<Config, String> {
+    let mut config = Config { games: 10, seed: 0, turns: 5000, class: Option::None };
+
+    let mut index = 0;
+    while index < args.len() {
+        let value = args.get(index + 1).ok_or(format!("Missing value for {}.", args[index]))?;
+
+        match args[index].as_str() {
+            "--games" => config.games = parse_number(value)?,
+            "--seed" => config.seed = parse_number(value)?,
+            "--turns" => config.turns = parse_number(value)?,
+            "--class" => {
+                config.class = Option::Some(*CLASSES.iter().find(|class| class.name().eq_ignore_ascii_case(value)).ok_or(format!("Unknown class {}.", value))?);
+            },
+            _ => return Result::Err(format!("Unknown argument {}.", args[index])),
+        }
+
+        index += 2;
+    }
+
+    Result::Ok(config)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid number {}.", value))
+}
@@ -0,0 +1,94 @@
+extern crate rpg;
+
+use rpg::character::class::{Class, CLASSES};
+use rpg::character::entity::{Entity, MonsterType, Difficulty};
+use rpg::character::item::StatsMod;
+use rpg::combat::fight::RndGenerator;
+use rpg::combat::simulation::simulate;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "Usage: balance [--duels N] [--class Warrior|Rogue|Ranger] [--vitality N] [--strength N] [--speed N] [--defense N] [--damage MIN-MAX]";
+
+/// Every monster the level generator can place.
+const MONSTERS: [(MonsterType, Difficulty); 10] = [
+    (MonsterType::Zombie, Difficulty::Easy), (MonsterType::Zombie, Difficulty::Normal), (MonsterType::Zombie, Difficulty::Hard),
+    (MonsterType::Crab, Difficulty::Easy), (MonsterType::Crab, Difficulty::Normal), (MonsterType::Crab, Difficulty::Hard),
+    (MonsterType::Goblin, Difficulty::Easy), (MonsterType::Goblin, Difficulty::Normal), (MonsterType::Goblin, Difficulty::Hard),
+    (MonsterType::Boss, Difficulty::Boss),
+];
+
+/// Simulates duels of a player against every monster and prints the averages as CSV.
+fn main() {
+    let (mut player, duels) = match parse_args(env::args().skip(1).collect()) {
+        Result::Ok(config) => config,
+        Result::Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        },
+    };
+
+    println!("monster,difficulty,duels,win_rate,turns_to_kill,damage_taken,loot");
+
+    for &(monster_type, difficulty) in MONSTERS.iter() {
+        let summary = simulate(RndGenerator, &mut player, monster_type, difficulty, duels);
+
+        println!("{:?},{:?},{},{:.3},{:.2},{:.2},{:.3}", summary.monster_type, summary.difficulty, summary.duels,
+            summary.win_rate, summary.turns_to_kill, summary.damage_taken, summary.loot);
+    }
+}
+
+/// Player with the class kit and stat overrides, and the number of duels per monster.
+fn parse_args(args: Vec<String>) -> Result<(Entity, u32), String> {
+    let mut player = Entity::new(0);
+    let mut class = Class::Warrior;
+    let mut duels = 1000;
+    let mut overrides = Vec::new();
+
+    let mut index = 0;
+    while index < args.len() {
+        let value = args.get(index + 1).ok_or(format!("Missing value for {}.", args[index]))?;
+
+        match args[index].as_str() {
+            "--duels" => duels = parse_number(value)?,
+            "--class" => {
+                class = *CLASSES.iter().find(|class| class.name().eq_ignore_ascii_case(value)).ok_or(format!("Unknown class {}.", value))?;
+            },
+            "--vitality" | "--strength" | "--speed" | "--defense" | "--damage" => overrides.push((args[index].as_str(), value.as_str())),
+            _ => return Result::Err(format!("Unknown argument {}.", args[index])),
+        }
+
+        index += 2;
+    }
+
+    class.equip(&mut player);
+
+    for (name, value) in overrides {
+        match name {
+            "--vitality" => player.base_stats.vitality = parse_number(value)?,
+            "--strength" => player.base_stats.strength = parse_number(value)?,
+            "--speed" => player.base_stats.speed = parse_number(value)?,
+            "--defense" => player.base_stats.defense = parse_number(value)?,
+            _ => {
+                let mut parts = value.split('-');
+                let damage = match (parts.next(), parts.next(), parts.next()) {
+                    (Option::Some(min), Option::Some(max), Option::None) => StatsMod::Damage { min: parse_number(min)?, max: parse_number(max)? },
+                    _ => return Result::Err(format!("Invalid damage {}.", value)),
+                };
+
+                player.weapon.modifications.retain(|modification| !matches!(*modification, StatsMod::Damage { .. }));
+                player.weapon.modifications.push(damage);
+            },
+        }
+    }
+
+    player.current_life = player.calculate_max_life();
+
+    Result::Ok((player, duels))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid number {}.", value))
+}
@@ -0,0 +1,16 @@
+extern crate rpg;
+
+use rpg::game::Game;
+use rpg::text::TextFrontend;
+
+use std::io;
+
+/// Plays on stdin and stdout with one command per line, type help for all commands.
+fn main() {
+    let stdin = io::stdin();
+    let mut frontend = TextFrontend::new(stdin.lock(), io::stdout());
+
+    let mut game = Game::new();
+    game.init(&mut frontend);
+    game.run(&mut frontend);
+}
@@ -0,0 +1,286 @@
+use super::character::class::Class;
+use super::character::entity::Entity;
+use super::character::item::{Item, Type, StatsMod};
+use super::character::stats::Stat;
+use super::effect::Special;
+use super::gym::*;
+use super::level::{Level, Tile};
+
+use std::panic;
+
+/// Below this percentage of life the bot heals.
+const LOW_LIFE_PERCENT: i32 = 40;
+
+/// Steps in a row without a turn passing, e.g. a command which keeps failing.
+pub const STUCK_STEPS: u32 = 100;
+
+/// Turns on one level before the bot counts as stuck.
+pub const STUCK_TURNS: u32 = 3000;
+
+/// Scripted player which explores, fights, loots, equips, heals and descends.
+pub struct Bot {
+    class: Class,
+}
+
+impl Bot {
+    pub fn new(class: Class) -> Bot {
+        Bot { class }
+    }
+
+    pub fn act(&self, environment: &Environment) -> Command {
+        let observation = environment.observe();
+        let player = &observation.player;
+        let level = environment.game().map();
+        let backpack = &environment.game().player().backpack;
+
+        let low_life = player.life * 100 < player.max_life * LOW_LIFE_PERCENT;
+
+        if low_life {
+            if let Option::Some(index) = observation.backpack.iter().position(|item_type| *item_type == Type::Potion) {
+                return Command::UseItem(index);
+            }
+        }
+
+        if let Option::Some(index) = better_item(environment.game().player()) {
+            return Command::UseItem(index);
+        }
+
+        let specials = self.class.specials();
+        if low_life {
+            if let Option::Some(slot) = specials.iter().position(|special| *special == Special::Mend) {
+                if observation.cool_downs[slot] == 0 && observation.energy >= Special::Mend.cost() {
+                    return Command::Special(slot, Direction::North);
+                }
+            }
+        }
+
+        let adjacent = DIRECTIONS.iter().cloned().find(|direction| {
+            let (row, col) = step(player.row, player.col, *direction);
+            observation.enemies.iter().any(|enemy| enemy.life > 0 && enemy.row == row && enemy.col == col)
+        });
+
+        if let Option::Some(direction) = adjacent {
+            //Dash and Mend do not hurt anyone.
+            let ready = (0..specials.len()).find(|slot| {
+                observation.cool_downs[*slot] == 0 && observation.energy >= specials[*slot].cost() &&
+                    specials[*slot] != Special::Dash && specials[*slot] != Special::Mend
+            });
+
+            return match ready {
+                Option::Some(slot) => Command::Special(slot, direction),
+                Option::None => Command::Attack(direction),
+            };
+        }
+
+        let has_space = backpack.has_space();
+        let loot_here = observation.enemies.iter().any(|enemy| enemy.loot > 0 && enemy.row == player.row && enemy.col == player.col);
+        if loot_here && has_space {
+            return Command::Use;
+        }
+
+        //Loot first, then the stairs, monsters only block the way.
+        let mut targets: Vec<(i32, i32)> = Vec::new();
+        if has_space {
+            targets.extend(observation.enemies.iter().filter(|enemy| enemy.life <= 0 && enemy.loot > 0).map(|enemy| (enemy.row, enemy.col)));
+        }
+        if targets.is_empty() {
+            if tile_at(&observation, player.row, player.col) == TILE_DOWN {
+                return Command::Use;
+            }
+
+            targets.extend(find_tiles(&observation, TILE_DOWN));
+        }
+        if targets.is_empty() {
+            targets.extend(observation.enemies.iter().filter(|enemy| enemy.life > 0).map(|enemy| (enemy.row, enemy.col)));
+        }
+
+        match next_step(level, &observation, &targets) {
+            Option::Some(direction) => Command::Move(direction),
+            Option::None => Command::Wait,
+        }
+    }
+}
+
+/// Index of a backpack item which is better than the equipped one.
+fn better_item(player: &Entity) -> Option<usize> {
+    player.backpack.items.iter().take(player.backpack.size()).position(|item| {
+        let equipped = match item.item_type {
+            Type::Head => &player.head_item,
+            Type::Chest => &player.chest_item,
+            Type::Legs => &player.leg_item,
+            Type::Weapon => &player.weapon,
+            Type::Potion | Type::Scroll | Type::Nothing => return false,
+        };
+
+        item_value(item) > item_value(equipped)
+    })
+}
+
+/// Sum of all bonuses, weapons count their average damage.
+fn item_value(item: &Item) -> i32 {
+    item.modifications.iter().map(|modification| match *modification {
+        StatsMod::Damage { min, max } => (min + max) / 2,
+        StatsMod::AttackSpeed(value) => value,
+        StatsMod::Add(Stat::Vitality(value)) | StatsMod::Add(Stat::Strength(value)) |
+        StatsMod::Add(Stat::Speed(value)) | StatsMod::Add(Stat::Defense(value)) |
+        StatsMod::Add(Stat::Intelligence(value)) => value,
+        StatsMod::Heal(..) | StatsMod::Teach(..) | StatsMod::Quest => 0,
+    }).sum()
+}
+
+fn tile_at(observation: &Observation, row: i32, col: i32) -> u8 {
+    if row < 0 || col < 0 || row as usize >= observation.rows || col as usize >= observation.cols {
+        return TILE_NOTHING;
+    }
+
+    observation.tiles[row as usize * observation.cols + col as usize]
+}
+
+fn find_tiles(observation: &Observation, tile: u8) -> Vec<(i32, i32)> {
+    observation.tiles.iter().enumerate()
+        .filter(|&(_, code)| *code == tile)
+        .map(|(index, _)| ((index / observation.cols) as i32, (index % observation.cols) as i32))
+        .collect()
+}
+
+fn step(row: i32, col: i32, direction: Direction) -> (i32, i32) {
+    match direction {
+        Direction::North => (row - 1, col),
+        Direction::East => (row, col + 1),
+        Direction::South => (row + 1, col),
+        Direction::West => (row, col - 1),
+    }
+}
+
+/// First step on a shortest way to the nearest target, around living monsters and friendly people.
+fn next_step(level: &Level, observation: &Observation, targets: &[(i32, i32)]) -> Option<Direction> {
+    //Friendly people never move, the way leads around them, dashes may end on them though.
+    let mut map = level.map.clone();
+    for npc in observation.npcs.iter().filter(|npc| npc.row != observation.player.row || npc.col != observation.player.col) {
+        map[npc.row as usize][npc.col as usize] = Tile::Wall;
+    }
+    let level = &Level { map, meta: level.meta.clone(), level: level.level, explored: Vec::new() };
+
+    let player = &observation.player;
+    let from_player = level.distance_map(player.row as usize, player.col as usize);
+
+    let &(row, col) = targets.iter()
+        .filter(|&&(row, col)| from_player[row as usize][col as usize].is_some())
+        .min_by_key(|&&(row, col)| from_player[row as usize][col as usize])?;
+
+    let to_target = level.distance_map(row as usize, col as usize);
+    let distance = |row: i32, col: i32| to_target[row as usize][col as usize];
+    let here = distance(player.row, player.col)?;
+
+    DIRECTIONS.iter().cloned().find(|direction| {
+        let (row, col) = step(player.row, player.col, *direction);
+        let blocked = observation.enemies.iter().any(|enemy| enemy.life > 0 && enemy.row == row && enemy.col == col);
+
+        !blocked && level.is_walkable(row as usize, col as usize) && distance(row, col).is_some_and(|next| next < here)
+    })
+}
+
+/// How a bot run ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Died,
+    /// No turn passed for many steps or the bot did not get deeper for many turns.
+    Stuck,
+    /// The game panicked with this message.
+    Crashed(String),
+    /// Still alive after all turns.
+    Survived,
+}
+
+impl Outcome {
+    pub fn name(&self) -> &str {
+        match *self {
+            Outcome::Died => "died",
+            Outcome::Stuck => "stuck",
+            Outcome::Crashed(..) => "crashed",
+            Outcome::Survived => "survived",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub seed: usize,
+    pub outcome: Outcome,
+    pub depth: i32,
+    pub turns: u32,
+    pub kills: u32,
+}
+
+/// Plays one whole game with the bot until it ends or max_turns passed.
+pub fn autoplay(class: Class, seed: usize, max_turns: u32) -> Report {
+    let mut report = Report { seed, outcome: Outcome::Survived, depth: 0, turns: 0, kills: 0 };
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let bot = Bot::new(class);
+        let mut environment = Environment::new(class);
+        environment.reset(seed);
+
+        let mut idle_steps = 0;
+        let mut level_turn = 0;
+
+        while report.turns < max_turns {
+            let result = environment.step(bot.act(&environment));
+            let observation = result.observation;
+
+            report.kills = environment.game().statistics().kills;
+
+            if observation.turn == report.turns {
+                idle_steps += 1;
+            } else {
+                idle_steps = 0;
+            }
+            if observation.depth != report.depth {
+                level_turn = observation.turn;
+            }
+
+            report.turns = observation.turn;
+            report.depth = observation.depth;
+
+            if result.done {
+                return Outcome::Died;
+            }
+            if idle_steps >= STUCK_STEPS || report.turns - level_turn >= STUCK_TURNS {
+                return Outcome::Stuck;
+            }
+        }
+
+        Outcome::Survived
+    }));
+
+    report.outcome = match result {
+        Result::Ok(outcome) => outcome,
+        Result::Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+
+            Outcome::Crashed(message)
+        },
+    };
+
+    report
+}
+
+#[test]
+fn test_autoplay() {
+    for seed in 0..6 {
+        let class = super::character::class::CLASSES[seed % 3];
+        let report = autoplay(class, seed, 2000);
+
+        assert!(report.outcome != Outcome::Stuck, "{:?}", report);
+        assert!(!matches!(report.outcome, Outcome::Crashed(..)), "{:?}", report);
+    }
+}
+
+#[test]
+fn test_autoplay_descends() {
+    let reports: Vec<Report> = (0..6).map(|seed| autoplay(Class::Warrior, seed, 2000)).collect();
+
+    assert!(reports.iter().any(|report| report.depth > 0), "{:?}", reports);
+}
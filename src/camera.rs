@@ -0,0 +1,67 @@
+use std::cmp;
+
+/// Visible part of a level, which scrolls around a focused position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    /// Level position shown in the top left corner.
+    pub row: i32,
+    pub col: i32,
+
+    pub height: i32,
+    pub width: i32,
+}
+
+impl Camera {
+    pub fn new(height: i32, width: i32) -> Camera {
+        Camera { row: 0, col: 0, height, width }
+    }
+
+    /// Centers on given position, but never scrolls beyond the level borders.
+    pub fn focus(&mut self, level_rows: usize, level_cols: usize, row: i32, col: i32) {
+        self.row = scroll(row, self.height, level_rows as i32);
+        self.col = scroll(col, self.width, level_cols as i32);
+    }
+
+    /// Screen position of given level position, None if it is out of sight.
+    pub fn to_screen(&self, row: i32, col: i32) -> Option<(i32, i32)> {
+        let screen_row = row - self.row;
+        let screen_col = col - self.col;
+
+        if screen_row < 0 || screen_col < 0 || screen_row >= self.height || screen_col >= self.width {
+            return Option::None;
+        }
+
+        Option::Some((screen_row, screen_col))
+    }
+}
+
+fn scroll(center: i32, view: i32, size: i32) -> i32 {
+    if size <= view {
+        return 0;
+    }
+
+    cmp::max(0, cmp::min(center - view / 2, size - view))
+}
+
+#[test]
+fn test_camera_follows_focus() {
+    let mut camera = Camera::new(10, 20);
+
+    camera.focus(40, 120, 20, 60);
+    assert_eq!((15, 50), (camera.row, camera.col));
+    assert_eq!(Option::Some((5, 10)), camera.to_screen(20, 60));
+    assert_eq!(Option::None, camera.to_screen(14, 60));
+
+    //Stops at the borders.
+    camera.focus(40, 120, 1, 119);
+    assert_eq!((0, 100), (camera.row, camera.col));
+}
+
+#[test]
+fn test_camera_small_level() {
+    let mut camera = Camera::new(18, 78);
+
+    camera.focus(9, 9, 5, 5);
+    assert_eq!((0, 0), (camera.row, camera.col));
+    assert_eq!(Option::Some((5, 5)), camera.to_screen(5, 5));
+}
@@ -157,10 +157,10 @@ mod tests {
     }
 
     fn create_special(name: String) -> Item {
-        Item { item_type: Type::Chest, name: name, modifications: Vec::new() }
+        Item { item_type: Type::Chest, name: name, modifications: Vec::new(), rarity: Rarity::Common }
     }
 
     fn create_shorts(n: usize) -> Item {
-        Item { item_type: Type::Legs, name: format!("{} Shorts", n), modifications: Vec::new() }
+        Item { item_type: Type::Legs, name: format!("{} Shorts", n), modifications: Vec::new(), rarity: Rarity::Common }
     }
 }
\ No newline at end of file
@@ -0,0 +1,109 @@
+use super::stats::*;
+use super::item::*;
+use super::entity::Entity;
+use super::super::effect::Special;
+
+/// What the player chooses to be, decides base stats, starting kit and specials.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Class {
+    Warrior,
+    Rogue,
+    Ranger,
+}
+
+pub const CLASSES: [Class; 3] = [Class::Warrior, Class::Rogue, Class::Ranger];
+
+impl Class {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Class::Warrior => "Warrior",
+            Class::Rogue => "Rogue",
+            Class::Ranger => "Ranger",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Class::Warrior => "Tough fighter, who charges into groups.",
+            Class::Rogue => "Fast and fragile, strikes with sure critical hits.",
+            Class::Ranger => "Keeps enemies at distance with arrows.",
+        }
+    }
+
+    pub fn base_stats(&self) -> Stats {
+        match *self {
+            Class::Warrior => Stats { vitality: 25, strength: 12, speed: 4, defense: 12, intelligence: 2 },
+            Class::Rogue => Stats { vitality: 18, strength: 10, speed: 8, defense: 8, intelligence: 4 },
+            Class::Ranger => Stats { vitality: 20, strength: 9, speed: 6, defense: 9, intelligence: 6 },
+        }
+    }
+
+    /// Abilities bound to SpecialOne, SpecialTwo and SpecialThree.
+    pub fn specials(&self) -> [Special; 3] {
+        match *self {
+            Class::Warrior => [Special::Storm, Special::RoundHouse, Special::Mend],
+            Class::Rogue => [Special::Backstab, Special::Dash, Special::RoundHouse],
+            Class::Ranger => [Special::PiercingShot, Special::Volley, Special::Dash],
+        }
+    }
+
+    /// Name of the pet which follows the player from the start.
+    pub fn pet(&self) -> &'static str {
+        match *self {
+            Class::Warrior => "Hound",
+            Class::Rogue => "Jackal",
+            Class::Ranger => "Wolf",
+        }
+    }
+
+    /// Gives the entity its base stats and starting kit, life is refilled.
+    pub fn equip(&self, entity: &mut Entity) {
+        entity.base_stats = self.base_stats();
+
+        let (equipment, potions) = match *self {
+            Class::Warrior => (vec![
+                new_item(Type::Weapon, "Rusty Sword", vec![StatsMod::Damage { min: 3, max: 7 }, StatsMod::AttackSpeed(1)]),
+                new_item(Type::Chest, "Leather Armor", vec![StatsMod::Add(Stat::Defense(2))]),
+            ], 1),
+            Class::Rogue => (vec![
+                new_item(Type::Weapon, "Dagger", vec![StatsMod::Damage { min: 2, max: 6 }, StatsMod::AttackSpeed(2)]),
+                new_item(Type::Legs, "Soft Boots", vec![StatsMod::Add(Stat::Speed(1))]),
+            ], 2),
+            Class::Ranger => (vec![
+                new_item(Type::Weapon, "Short Bow", vec![StatsMod::Damage { min: 2, max: 8 }, StatsMod::AttackSpeed(1)]),
+                new_item(Type::Head, "Hood", vec![StatsMod::Add(Stat::Defense(1))]),
+            ], 1),
+        };
+
+        for item in equipment {
+            entity.equip(item);
+        }
+
+        for _ in 0..potions {
+            entity.backpack.add_item(new_item(Type::Potion, "Healing Potion", vec![StatsMod::Heal(25)])).ok();
+        }
+
+        entity.current_life = entity.calculate_max_life();
+    }
+}
+
+fn new_item(item_type: Type, name: &str, modifications: Vec<StatsMod>) -> Item {
+    Item { item_type, name: name.to_string(), modifications, rarity: Rarity::Common }
+}
+
+#[test]
+fn test_equip_class() {
+    let mut entity = Entity::new(0);
+    Class::Warrior.equip(&mut entity);
+
+    assert_eq!("Rusty Sword", entity.weapon.name);
+    assert_eq!(14, entity.calculate_stats().defense);
+    assert_eq!(250, entity.current_life);
+    assert_eq!(1, entity.backpack.size());
+
+    for class in CLASSES.iter() {
+        for special in class.specials().iter() {
+            assert_eq!(special.effect_id(), special.create(0, super::super::effect::AttackDirection::North).effect_id());
+        }
+    }
+}
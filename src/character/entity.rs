@@ -2,6 +2,13 @@ use super::stats::*;
 use super::item::*;
 use super::backpack::*;
 
+use std::cmp;
+
+/// Energy of a rested entity.
+pub const MAX_ENERGY: i32 = 100;
+/// Energy which comes back every turn.
+pub const ENERGY_REGENERATION: i32 = 5;
+
 pub struct Entity {
     pub id: u32,
     pub name: String,
@@ -17,9 +24,16 @@ pub struct Entity {
     pub pos_row: i32,
     pub pos_col: i32,
     pub current_life: i32,
+    /// Spent by specials, refills every turn.
+    pub current_energy: i32,
 
     pub monster_type: MonsterType,
     pub monster_difficulty: Difficulty,
+
+    /// Side the entity fights for, the player and companions are allied.
+    pub faction: Faction,
+    /// What a companion was told to do, monsters ignore it.
+    pub order: Order,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -29,6 +43,42 @@ pub enum MonsterType {
     Zombie,
     Goblin,
     Boss,
+    /// Companion of the player.
+    Pet,
+    /// Friendly entity to talk to.
+    Npc,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Faction {
+    Hostile,
+    Allied,
+    /// Fights nobody and is left alone, until it turns hostile.
+    Neutral,
+}
+
+impl Faction {
+    /// Whether entities of both factions fight each other.
+    pub fn fights(&self, other: Faction) -> bool {
+        *self != other && *self != Faction::Neutral && other != Faction::Neutral
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Order {
+    /// Walks behind the player and fights what comes close, also to other levels.
+    Follow,
+    /// Keeps its place and only fights what stands next to it.
+    Stay,
+}
+
+impl Order {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Order::Follow => "follows you",
+            Order::Stay => "stays",
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -41,7 +91,7 @@ pub enum Difficulty {
 
 impl Entity {
     pub fn new(id: u32) -> Entity {
-        let base_stats = Stats { vitality: 20, strength: 10, speed: 5, defense: 10 };
+        let base_stats = Stats { vitality: 20, strength: 10, speed: 5, defense: 10, intelligence: 0 };
 
         let mut entity = Entity {
             id: id,
@@ -59,12 +109,16 @@ impl Entity {
 
             // Life.
             current_life: 0,
+            current_energy: MAX_ENERGY,
 
             // Backpack.
             backpack: Backpack::new(),
 
             monster_type : MonsterType::Unknown,
             monster_difficulty : Difficulty::Easy,
+
+            faction: Faction::Hostile,
+            order: Order::Follow,
         };
 
         //Set correct life.
@@ -96,6 +150,39 @@ impl Entity {
         self.current_life <= 0
     }
 
+    /// Rough life left, as seen by others.
+    pub fn condition(&self) -> &'static str {
+        if self.is_death() {
+            return "dead";
+        }
+
+        match self.current_life * 100 / cmp::max(1, self.calculate_max_life()) {
+            percent if percent < 25 => "almost dead",
+            percent if percent < 50 => "badly wounded",
+            percent if percent < 75 => "wounded",
+            percent if percent < 100 => "slightly wounded",
+            _ => "unhurt",
+        }
+    }
+
+    /// Living entities of the other faction are fought, neutral ones are not.
+    pub fn is_enemy_of(&self, other: &Entity) -> bool {
+        !other.is_death() && self.faction.fights(other.faction)
+    }
+
+    pub fn is_companion(&self) -> bool {
+        self.faction == Faction::Allied && !self.is_death()
+    }
+
+    /// Living entity the player can talk to.
+    pub fn is_friendly(&self) -> bool {
+        self.faction == Faction::Neutral && !self.is_death()
+    }
+
+    pub fn regenerate_energy(&mut self) {
+        self.current_energy = cmp::min(MAX_ENERGY, self.current_energy + ENERGY_REGENERATION);
+    }
+
     pub fn equip(&mut self, new_item: Item) -> Item {
         match &new_item.item_type {
             &Type::Head => {
@@ -114,7 +201,7 @@ impl Entity {
                 assert_item_is_weapon(&new_item);
                 change_item(&mut self.weapon, new_item)
             },
-            &Type::Nothing | &Type::Potion => {
+            &Type::Nothing | &Type::Potion | &Type::Scroll => {
                 new_item
             }
         }
@@ -136,12 +223,13 @@ fn add_item(mut base_stats: &mut Stats, item: &Item) {
     }
 }
 
-fn add_stat(base_stats: &mut Stats, stat: &Stat) {
+pub fn add_stat(base_stats: &mut Stats, stat: &Stat) {
     match stat {
         &Stat::Vitality(value) => base_stats.vitality += value,
         &Stat::Strength(value) => base_stats.strength += value,
         &Stat::Speed(value) => base_stats.speed += value,
         &Stat::Defense(value) => base_stats.defense += value,
+        &Stat::Intelligence(value) => base_stats.intelligence += value,
     };
 }
 
@@ -152,7 +240,7 @@ fn test_change_item() {
     let mut attributes: Vec<StatsMod> = Vec::new();
     attributes.push(StatsMod::Add(Stat::Strength(5)));
     attributes.push(StatsMod::Add(Stat::Defense(10)));
-    let new_item = Item { modifications: attributes, name: "Helm".to_string(), item_type: Type::Head };
+    let new_item = Item { modifications: attributes, name: "Helm".to_string(), item_type: Type::Head, rarity: Rarity::Common };
 
     assert_eq!("Free", player_entity.head_item.name);
     assert_eq!("Helm", new_item.name);
@@ -165,10 +253,25 @@ fn test_change_item() {
 
 #[test]
 fn test_add_stat() {
-    let mut stats = Stats { vitality: 1, strength: 2, speed: 3, defense: 5 };
+    let mut stats = Stats { vitality: 1, strength: 2, speed: 3, defense: 5, intelligence: 0 };
     let stat = Stat::Vitality(7);
 
     add_stat(&mut stats, &stat);
 
     assert_eq!(8, stats.vitality);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_condition() {
+    let mut entity = Entity::new(0);
+    assert_eq!("unhurt", entity.condition());
+
+    entity.current_life = entity.calculate_max_life() / 2;
+    assert_eq!("wounded", entity.condition());
+
+    entity.current_life = 1;
+    assert_eq!("almost dead", entity.condition());
+
+    entity.current_life = -3;
+    assert_eq!("dead", entity.condition());
+}
@@ -1,10 +1,12 @@
 use super::stats::Stat;
+use super::super::effect::Special;
 
 #[derive(Debug)]
 pub struct Item {
     pub item_type: Type,
     pub name: String,
     pub modifications: Vec<StatsMod>,
+    pub rarity: Rarity,
 }
 
 impl Item {
@@ -33,6 +35,28 @@ impl Item {
 
         panic!("Method Item::get_heal_percentage should only be called on healing-potions!")
     }
+
+    /// Name, rarity and all modifications, e.g. "Good Sword (Rare) Damage 3-7".
+    pub fn describe(&self) -> String {
+        if self.item_type == Type::Nothing {
+            return "-".to_string();
+        }
+
+        let modifications: Vec<String> = self.modifications.iter().map(|modification| match *modification {
+            StatsMod::Damage { min, max } => format!("Damage {}-{}", min, max),
+            StatsMod::AttackSpeed(value) => format!("Attack Speed {}", value),
+            StatsMod::Heal(value) => format!("Heal {}%", value),
+            StatsMod::Add(Stat::Vitality(value)) => format!("Vitality {}", value),
+            StatsMod::Add(Stat::Strength(value)) => format!("Strength {}", value),
+            StatsMod::Add(Stat::Speed(value)) => format!("Speed {}", value),
+            StatsMod::Add(Stat::Defense(value)) => format!("Defense {}", value),
+            StatsMod::Add(Stat::Intelligence(value)) => format!("Intelligence {}", value),
+            StatsMod::Teach(spell) => format!("Teaches {}", spell.name()),
+            StatsMod::Quest => "Starts a quest".to_string(),
+        }).collect();
+
+        format!("{} ({:?}) {}", self.name, self.rarity, modifications.join(", "))
+    }
 }
 
 impl Clone for Item {
@@ -43,7 +67,7 @@ impl Clone for Item {
             modifications.push(modification.clone());
         }
 
-        Item { item_type: self.item_type, name: self.name.clone(), modifications: modifications }
+        Item { item_type: self.item_type, name: self.name.clone(), modifications: modifications, rarity: self.rarity }
     }
 }
 
@@ -54,9 +78,20 @@ pub enum Type {
     Legs,
     Weapon,
     Potion,
+    /// Teaches spells when read, tomes also raise the intelligence.
+    Scroll,
     Nothing,
 }
 
+/// How good an item is, better monsters drop rarer items.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum StatsMod {
     Add(Stat),
@@ -66,11 +101,15 @@ pub enum StatsMod {
     },
     AttackSpeed(i32),
     Heal(i32),
+    /// Spell learned by reading the item.
+    Teach(Special),
+    /// Reading the item starts a quest.
+    Quest,
 }
 
 pub fn get_fist() -> Item {
     let modifications: Vec<StatsMod> = vec!(StatsMod::Damage { min: 1, max: 5 }, StatsMod::AttackSpeed(1));
-    Item { item_type: Type::Weapon, name: "Fists".to_string(), modifications: modifications }
+    Item { item_type: Type::Weapon, name: "Fists".to_string(), modifications: modifications, rarity: Rarity::Common }
 }
 
 pub fn get_free() -> Item {
@@ -78,7 +117,7 @@ pub fn get_free() -> Item {
 }
 
 fn new_plain_item(item_type: Type, name: String) -> Item {
-    Item { item_type: item_type, name: name, modifications: Vec::new() }
+    Item { item_type: item_type, name: name, modifications: Vec::new(), rarity: Rarity::Common }
 }
 
 pub fn assert_item_is_clothing(given_item: &Item) {
@@ -122,12 +161,12 @@ fn item_is_weapon(given_item: &Item) -> bool {
 
 #[test]
 fn test_clothing_check() {
-    let legs = Item { item_type: Type::Legs, name: "Legs".to_string(), modifications: Vec::new() };
+    let legs = Item { item_type: Type::Legs, name: "Legs".to_string(), modifications: Vec::new(), rarity: Rarity::Common };
 
     assert_eq!(true, item_is_clothing(&legs));
 
     let modifications: Vec<StatsMod> = vec!(StatsMod::Damage { min: 1, max: 5 }, StatsMod::AttackSpeed(1));
-    let weapon = Item { item_type: Type::Weapon, name: "Fists".to_string(), modifications: modifications };
+    let weapon = Item { item_type: Type::Weapon, name: "Fists".to_string(), modifications: modifications, rarity: Rarity::Common };
 
     assert_eq!(false, item_is_clothing(&weapon));
 }
@@ -135,11 +174,11 @@ fn test_clothing_check() {
 #[test]
 fn test_weapon_check() {
     let modifications: Vec<StatsMod> = vec!(StatsMod::Damage { min: 1, max: 5 }, StatsMod::AttackSpeed(1));
-    let fists = Item { item_type: Type::Weapon, name: "Fists".to_string(), modifications: modifications };
+    let fists = Item { item_type: Type::Weapon, name: "Fists".to_string(), modifications: modifications, rarity: Rarity::Common };
 
     assert_eq!(true, item_is_weapon(&fists));
 
-    let legs = Item { item_type: Type::Legs, name: "Legs".to_string(), modifications: Vec::new() };
+    let legs = Item { item_type: Type::Legs, name: "Legs".to_string(), modifications: Vec::new(), rarity: Rarity::Common };
 
     assert_eq!(false, item_is_weapon(&legs));
 }
@@ -147,7 +186,7 @@ fn test_weapon_check() {
 #[test]
 fn test_malformed_weapon() {
     let modifications = vec![StatsMod::Add(Stat::Strength(5)), StatsMod::Damage { min: 10, max: 20 }];
-    let error_weapon = Item { item_type: Type::Weapon, name: "ErrorWeapon".to_string(), modifications: modifications };
+    let error_weapon = Item { item_type: Type::Weapon, name: "ErrorWeapon".to_string(), modifications: modifications, rarity: Rarity::Common };
 
     assert_eq!(false, item_is_weapon(&error_weapon));
 }
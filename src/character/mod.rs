@@ -5,3 +5,5 @@ pub mod entity;
 pub mod item;
 
 pub mod backpack;
+
+pub mod class;
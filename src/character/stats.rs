@@ -4,6 +4,8 @@ pub struct Stats {
     pub strength: i32,
     pub speed: i32,
     pub defense: i32,
+    /// Power of spells.
+    pub intelligence: i32,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -12,4 +14,5 @@ pub enum Stat {
     Strength(i32),
     Speed(i32),
     Defense(i32),
+    Intelligence(i32),
 }
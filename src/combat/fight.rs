@@ -1,9 +1,6 @@
-extern crate rand;
-
-use rand::Rng;
-
+use super::super::rng;
 use super::super::character::entity::*;
-use super::super::log::*;
+use super::super::event::*;
 
 pub struct Fight;
 
@@ -11,7 +8,7 @@ impl Fight {
 
     ///calculates a weapon hit.
     /// crit_chance from 0 to 100.
-    pub fn weapon_hit<T: Generator>(log : &mut Log, generator: T, me: &Entity, enemy: &mut Entity, crit_chance: i32) {
+    pub fn weapon_hit<T: Generator>(events: &mut EventQueue, generator: T, me: &Entity, enemy: &mut Entity, crit_chance: i32) {
         if enemy.is_death() {
             return;
         }
@@ -36,31 +33,46 @@ impl Fight {
         if actual_damage > 0 {
             enemy.current_life -= actual_damage;
 
-            let crit_prefix = if crit {
-                "CRIT! "
+            if crit {
+                events.push(Event::Crit { attacker: Actor::of(me), target: Actor::of(enemy), damage: actual_damage });
             } else {
-                ""
-            }.to_string();
+                events.push(Event::Hit { attacker: Actor::of(me), target: Actor::of(enemy), damage: actual_damage });
+            }
 
             if enemy.is_death() {
-                log.add_message(crit_prefix + &format!("{} killed {}!", me.name, enemy.name));
-            } else {
-                log.add_message(crit_prefix + &format!("{} hit {} with {}!", me.name, enemy.name, actual_damage));
+                events.push(Event::Kill { attacker: Actor::of(me), target: Actor::of(enemy) });
             }
-
         } else {
-            log.add_message(format!("{} missed {}", me.name, enemy.name));
+            events.push(Event::Miss { attacker: Actor::of(me), target: Actor::of(enemy) });
+        }
+
+    }
+
+    /// Calculates a spell hit, spells ignore the defense and grow with the intelligence.
+    pub fn spell_hit<T: Generator>(events: &mut EventQueue, generator: T, me: &Entity, enemy: &mut Entity, power: i32) {
+        if enemy.is_death() {
+            return;
         }
 
+        let strength = power + me.calculate_stats().intelligence;
+        let damage = generator.generate(strength, strength * 2);
+
+        enemy.current_life -= damage;
+        events.push(Event::Hit { attacker: Actor::of(me), target: Actor::of(enemy), damage });
+
+        if enemy.is_death() {
+            events.push(Event::Kill { attacker: Actor::of(me), target: Actor::of(enemy) });
+        }
     }
 }
 
+#[derive(Clone, Copy)]
 pub struct RndGenerator;
 
 impl Generator for RndGenerator {
     fn generate(&self, min_inclusive: i32, max_inclusive: i32) -> i32 {
         //gen_range generates min_inclusive to max_exclusive.
-        rand::thread_rng().gen_range(min_inclusive, max_inclusive + 1)
+        rng::gen_range(min_inclusive, max_inclusive + 1)
     }
 }
 
@@ -70,20 +82,33 @@ pub trait Generator {
 
 #[test]
 fn test_damage() {
-    let mut log = Log {messages : Vec::new()};
+    let mut events = EventQueue::new();
     let me = Entity::new(0);
     let mut enemy = Entity::new(1);
 
     assert_eq!(enemy.calculate_max_life(), enemy.current_life);
 
-    Fight::weapon_hit(&mut log, RndGenerator, &me, &mut enemy, 0);
+    Fight::weapon_hit(&mut events, MaxGenerator, &me, &mut enemy, 0);
 
     assert!(enemy.current_life < enemy.calculate_max_life());
+
+    //Fists do 1-5 damage, no crit because the roll is 100.
+    assert_eq!(&[Event::Hit { attacker: Actor::of(&me), target: Actor::of(&enemy), damage: 5 }], events.events());
+}
+
+#[cfg(test)]
+struct MaxGenerator;
+
+#[cfg(test)]
+impl Generator for MaxGenerator {
+    fn generate(&self, _min_inclusive: i32, max_inclusive: i32) -> i32 {
+        max_inclusive
+    }
 }
 
 #[test]
 fn test_death() {
-    let mut log = Log {messages : Vec::new()};
+    let mut events = EventQueue::new();
     let me = Entity::new(0);
     let mut enemy = Entity::new(1);
 
@@ -91,7 +116,37 @@ fn test_death() {
 
     assert ! ( !enemy.is_death());
 
-    Fight::weapon_hit(&mut log, RndGenerator, &me, & mut enemy, 0);
+    Fight::weapon_hit(&mut events, RndGenerator, &me, & mut enemy, 0);
 
     assert ! (enemy.is_death());
+    assert_eq!(Some(&Event::Kill { attacker: Actor::of(&me), target: Actor::of(&enemy) }), events.events().last());
+}
+
+#[test]
+fn test_crit() {
+    let mut events = EventQueue::new();
+    let me = Entity::new(0);
+    let mut enemy = Entity::new(1);
+
+    Fight::weapon_hit(&mut events, RndGenerator, &me, &mut enemy, 100);
+
+    match events.events()[0] {
+        Event::Crit { damage, .. } => assert!(damage >= 2),
+        ref other => panic!("Expected crit, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_spell_hit() {
+    let mut events = EventQueue::new();
+    let mut me = Entity::new(0);
+    let mut enemy = Entity::new(1);
+
+    me.base_stats.intelligence = 4;
+    enemy.base_stats.defense = 100;
+
+    Fight::spell_hit(&mut events, MaxGenerator, &me, &mut enemy, 6);
+
+    //Defense does not help, (6 + 4) * 2 damage.
+    assert_eq!(&[Event::Hit { attacker: Actor::of(&me), target: Actor::of(&enemy), damage: 20 }], events.events());
 }
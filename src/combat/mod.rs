@@ -1 +1,3 @@
 pub mod fight;
+
+pub mod simulation;
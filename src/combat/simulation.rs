@@ -0,0 +1,145 @@
+use super::super::character::entity::*;
+use super::super::event::EventQueue;
+use super::super::gen::monster::create_monster;
+use super::fight::{Fight, Generator};
+
+/// Same crit chance as a normal weapon hit.
+const CRIT_CHANCE: i32 = 10;
+
+/// Duels which take longer are stopped and count as lost.
+pub const MAX_TURNS: u32 = 1000;
+
+/// Outcome of a single fight to the death.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Duel {
+    pub won: bool,
+    pub turns: u32,
+    pub damage_taken: i32,
+}
+
+/// Player and monster hit each other in turns until one dies, the player starts.
+/// The players life is restored afterwards.
+pub fn duel<T: Generator + Copy>(generator: T, player: &mut Entity, monster: &mut Entity) -> Duel {
+    let mut events = EventQueue::new();
+    let start_life = player.current_life;
+
+    let mut turns = 0;
+    while turns < MAX_TURNS && !player.is_death() && !monster.is_death() {
+        turns += 1;
+
+        Fight::weapon_hit(&mut events, generator, player, monster, CRIT_CHANCE);
+        if !monster.is_death() {
+            Fight::weapon_hit(&mut events, generator, monster, player, CRIT_CHANCE);
+        }
+    }
+
+    let damage_taken = start_life - player.current_life.max(0);
+    player.current_life = start_life;
+
+    Duel { won: monster.is_death(), turns, damage_taken }
+}
+
+/// Averages of many duels against one kind of monster.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Summary {
+    pub monster_type: MonsterType,
+    pub difficulty: Difficulty,
+    pub duels: u32,
+    pub win_rate: f32,
+    /// Only counts won duels.
+    pub turns_to_kill: f32,
+    pub damage_taken: f32,
+    pub loot: f32,
+}
+
+/// Creates a new monster for every duel, like the level generator does.
+pub fn simulate<T: Generator + Copy>(generator: T, player: &mut Entity, monster_type: MonsterType, difficulty: Difficulty, duels: u32) -> Summary {
+    let mut wins = 0;
+    let mut turns_to_kill = 0;
+    let mut damage_taken = 0;
+    let mut loot = 0;
+
+    for id in 0..duels {
+        let mut monster = Entity::new(id + 1);
+        create_monster(player, &mut monster, monster_type_id(monster_type), difficulty_id(difficulty));
+        loot += monster.backpack.size();
+
+        let duel = duel(generator, player, &mut monster);
+        if duel.won {
+            wins += 1;
+            turns_to_kill += duel.turns;
+        }
+        damage_taken += duel.damage_taken as i64;
+    }
+
+    let per_duel = |value: f32| if duels > 0 { value / duels as f32 } else { 0.0 };
+
+    Summary {
+        monster_type,
+        difficulty,
+        duels,
+        win_rate: per_duel(wins as f32),
+        turns_to_kill: if wins > 0 { turns_to_kill as f32 / wins as f32 } else { 0.0 },
+        damage_taken: per_duel(damage_taken as f32),
+        loot: per_duel(loot as f32),
+    }
+}
+
+/// Ids used by create_monster.
+fn monster_type_id(monster_type: MonsterType) -> u32 {
+    match monster_type {
+        MonsterType::Zombie => 1,
+        MonsterType::Crab => 2,
+        MonsterType::Goblin => 3,
+        MonsterType::Boss => 4,
+        MonsterType::Unknown | MonsterType::Pet | MonsterType::Npc => panic!("unknown monster_type."),
+    }
+}
+
+fn difficulty_id(difficulty: Difficulty) -> u32 {
+    match difficulty {
+        Difficulty::Easy => 1,
+        Difficulty::Normal => 2,
+        Difficulty::Hard => 3,
+        Difficulty::Boss => 4,
+    }
+}
+
+#[cfg(test)]
+#[derive(Clone, Copy)]
+struct MeanGenerator;
+
+#[cfg(test)]
+impl Generator for MeanGenerator {
+    fn generate(&self, min_inclusive: i32, max_inclusive: i32) -> i32 {
+        (min_inclusive + max_inclusive) / 2
+    }
+}
+
+#[test]
+fn test_duel() {
+    let mut player = Entity::new(0);
+    player.base_stats.strength = 20;
+
+    let mut monster = Entity::new(1);
+    monster.current_life = 20;
+
+    //Fists do 1-5, plus 10 strength bonus, so 13 per hit and no crits.
+    let duel = duel(MeanGenerator, &mut player, &mut monster);
+
+    //The monster does 3 per hit and dies before its second one.
+    assert!(duel.won);
+    assert_eq!(2, duel.turns);
+    assert_eq!(3, duel.damage_taken);
+    assert_eq!(player.calculate_max_life(), player.current_life);
+}
+
+#[test]
+fn test_simulate() {
+    let mut player = Entity::new(0);
+    let summary = simulate(MeanGenerator, &mut player, MonsterType::Crab, Difficulty::Easy, 20);
+
+    assert_eq!(20, summary.duels);
+    assert!(summary.win_rate >= 0.0 && summary.win_rate <= 1.0);
+    assert!(summary.loot <= 5.0);
+}
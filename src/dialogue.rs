@@ -0,0 +1,227 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+
+use super::quest::QuestKind;
+
+pub const DIALOGUE_FILE: &str = "dialogues.txt";
+
+/// Dialogues which are used without a dialogue file.
+const DEFAULT_DIALOGUES: &str = include_str!("../dialogues.txt");
+
+/// What happens when the player picks a choice.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Consequence {
+    /// Heals the player by given percentage of the max life.
+    Heal(i32),
+    GivePotion,
+    GiveScroll,
+    /// A random piece of gear.
+    GiveItem,
+    /// Takes a healing potion, the choice fails without one.
+    TakePotion,
+    /// Shows the whole floor.
+    RevealMap,
+    /// The speaker becomes an enemy.
+    Fight,
+    /// The speaker goes away for good.
+    Leave,
+    /// Gives a quest, the choice fails while the speaker has an open one.
+    Quest(QuestKind),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Choice {
+    pub text: String,
+    /// Node which comes next, the dialogue ends without one.
+    pub next: Option<String>,
+    pub consequences: Vec<Consequence>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub id: String,
+    pub text: Vec<String>,
+    pub choices: Vec<Choice>,
+}
+
+/// Everything one kind of friendly entity says, talking starts at the first node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dialogue {
+    /// Name of the entities which speak it.
+    pub name: String,
+    pub nodes: Vec<Node>,
+}
+
+impl Dialogue {
+    pub fn node(&self, id: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node.id == id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dialogues {
+    pub dialogues: Vec<Dialogue>,
+}
+
+impl Dialogues {
+    /// The dialogues which come with the game.
+    pub fn new() -> Dialogues {
+        Dialogues::parse(DEFAULT_DIALOGUES).expect("Invalid default dialogues.")
+    }
+
+    pub fn load(path: &str) -> io::Result<Dialogues> {
+        let mut content = String::new();
+        File::open(path)?.read_to_string(&mut content)?;
+
+        Dialogues::parse(&content)
+    }
+
+    /// Reads blocks of "npc", "node", "text" and "choice" lines, see the dialogue file.
+    pub fn parse(content: &str) -> io::Result<Dialogues> {
+        let mut dialogues: Vec<Dialogue> = Vec::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("Invalid dialogue line {}: {}", line_number + 1, line));
+            let (key, rest) = line.split_once(' ').map_or((line, ""), |(key, rest)| (key, rest.trim()));
+            if rest.is_empty() {
+                return Result::Err(invalid());
+            }
+
+            match key {
+                "npc" => {
+                    if dialogues.iter().any(|dialogue| dialogue.name == rest) {
+                        return Result::Err(invalid());
+                    }
+
+                    dialogues.push(Dialogue { name: rest.to_string(), nodes: Vec::new() });
+                },
+                "node" => {
+                    let dialogue = dialogues.last_mut().ok_or_else(invalid)?;
+                    if dialogue.node(rest).is_some() {
+                        return Result::Err(invalid());
+                    }
+
+                    dialogue.nodes.push(Node { id: rest.to_string(), text: Vec::new(), choices: Vec::new() });
+                },
+                "text" => {
+                    let node = dialogues.last_mut().and_then(|dialogue| dialogue.nodes.last_mut()).ok_or_else(invalid)?;
+                    node.text.push(rest.to_string());
+                },
+                "choice" => {
+                    let node = dialogues.last_mut().and_then(|dialogue| dialogue.nodes.last_mut()).ok_or_else(invalid)?;
+                    let values: Vec<&str> = rest.splitn(3, ' ').collect();
+                    if values.len() != 3 {
+                        return Result::Err(invalid());
+                    }
+
+                    let next = if values[0] == "end" { Option::None } else { Option::Some(values[0].to_string()) };
+                    let consequences = if values[1] == "-" {
+                        Vec::new()
+                    } else {
+                        values[1].split(',').map(parse_consequence).collect::<Option<Vec<Consequence>>>().ok_or_else(invalid)?
+                    };
+
+                    node.choices.push(Choice { text: values[2].trim().to_string(), next, consequences });
+                },
+                _ => return Result::Err(invalid()),
+            }
+        }
+
+        //Every node needs a way on and every way has to lead somewhere.
+        for dialogue in &dialogues {
+            if dialogue.nodes.is_empty() {
+                return Result::Err(io::Error::new(io::ErrorKind::InvalidData, format!("Dialogue of {} has no nodes.", dialogue.name)));
+            }
+
+            for node in &dialogue.nodes {
+                if node.choices.is_empty() {
+                    return Result::Err(io::Error::new(io::ErrorKind::InvalidData, format!("Node {} of {} has no choices.", node.id, dialogue.name)));
+                }
+
+                for next in node.choices.iter().filter_map(|choice| choice.next.as_ref()) {
+                    if dialogue.node(next).is_none() {
+                        return Result::Err(io::Error::new(io::ErrorKind::InvalidData, format!("Node {} of {} leads to the unknown node {}.", node.id, dialogue.name, next)));
+                    }
+                }
+            }
+        }
+
+        if dialogues.is_empty() {
+            return Result::Err(io::Error::new(io::ErrorKind::InvalidData, "There are no dialogues.".to_string()));
+        }
+
+        Result::Ok(Dialogues { dialogues })
+    }
+
+    /// Dialogue spoken by the entity of given name.
+    pub fn of(&self, name: &str) -> Option<&Dialogue> {
+        self.dialogues.iter().find(|dialogue| dialogue.name == name)
+    }
+
+    /// Picks a dialogue by any number, e.g. of a spawn.
+    pub fn pick(&self, number: u32) -> &Dialogue {
+        &self.dialogues[number as usize % self.dialogues.len()]
+    }
+}
+
+impl Default for Dialogues {
+    fn default() -> Dialogues {
+        Dialogues::new()
+    }
+}
+
+fn parse_consequence(value: &str) -> Option<Consequence> {
+    match value {
+        "potion" => Option::Some(Consequence::GivePotion),
+        "scroll" => Option::Some(Consequence::GiveScroll),
+        "item" => Option::Some(Consequence::GiveItem),
+        "trade" => Option::Some(Consequence::TakePotion),
+        "reveal" => Option::Some(Consequence::RevealMap),
+        "fight" => Option::Some(Consequence::Fight),
+        "leave" => Option::Some(Consequence::Leave),
+        "quest:kill" => Option::Some(Consequence::Quest(QuestKind::Kill)),
+        "quest:recover" => Option::Some(Consequence::Quest(QuestKind::Recover)),
+        "quest:vault" => Option::Some(Consequence::Quest(QuestKind::Vault)),
+        _ => {
+            let percentage = value.strip_prefix("heal:")?.parse::<i32>().ok()?;
+            if percentage > 0 { Option::Some(Consequence::Heal(percentage)) } else { Option::None }
+        },
+    }
+}
+
+#[test]
+fn test_parse_dialogues() {
+    let dialogues = Dialogues::parse("# Comment\n\nnpc Old Man\nnode start\ntext Hello.\ntext \"Really.\"\nchoice bye heal:20,potion Thanks.\nchoice end fight Die!\nnode bye\nchoice end quest:vault Bye.\n").unwrap();
+    let dialogue = dialogues.of("Old Man").unwrap();
+
+    assert_eq!(2, dialogue.nodes.len());
+    assert_eq!(vec!["Hello.".to_string(), "\"Really.\"".to_string()], dialogue.nodes[0].text);
+    assert_eq!(Choice { text: "Thanks.".to_string(), next: Option::Some("bye".to_string()), consequences: vec![Consequence::Heal(20), Consequence::GivePotion] }, dialogue.nodes[0].choices[0]);
+    assert_eq!(Option::None, dialogue.nodes[0].choices[1].next);
+    assert_eq!(Option::Some(1), dialogue.node("bye"));
+    assert_eq!(vec![Consequence::Quest(QuestKind::Vault)], dialogue.nodes[1].choices[0].consequences);
+    assert_eq!("Old Man", dialogues.pick(7).name);
+
+    assert!(Dialogues::parse("").is_err());
+    assert!(Dialogues::parse("node start").is_err());
+    assert!(Dialogues::parse("npc A\nnode start\nchoice nowhere - Go.").is_err());
+    assert!(Dialogues::parse("npc A\nnode start\nchoice end dance Go.").is_err());
+    assert!(Dialogues::parse("npc A\nnode start\nchoice end quest:dance Go.").is_err());
+    assert!(Dialogues::parse("npc A\nnode start\ntext Nothing to choose.").is_err());
+    assert!(Dialogues::parse("npc A\nnode start\nchoice end -").is_err());
+}
+
+#[test]
+fn test_default_dialogues() {
+    let dialogues = Dialogues::new();
+
+    assert!(dialogues.of("Wounded Adventurer").is_some());
+    assert!(dialogues.of("Hermit").is_some());
+    assert!(dialogues.of("Merchant").is_some());
+}
@@ -0,0 +1,66 @@
+use super::level::Level;
+use super::character::entity::Entity;
+
+/// A level together with everything living on it.
+pub struct Floor {
+    pub level: Level,
+    pub enemies: Vec<Entity>,
+}
+
+/// Floors the player has left, kept so they can be visited again.
+#[derive(Default)]
+pub struct Dungeon {
+    floors: Vec<Option<Floor>>,
+}
+
+impl Dungeon {
+    pub fn new() -> Dungeon {
+        Dungeon { floors: Vec::new() }
+    }
+
+    /// Keeps given floor until the player returns to its depth.
+    pub fn store(&mut self, floor: Floor) {
+        let depth = floor.level.level as usize;
+
+        while self.floors.len() <= depth {
+            self.floors.push(Option::None);
+        }
+
+        self.floors[depth] = Option::Some(floor);
+    }
+
+    /// Removes the floor of given depth, None if it was never visited.
+    pub fn take(&mut self, depth: i32) -> Option<Floor> {
+        if depth < 0 || depth as usize >= self.floors.len() {
+            return Option::None;
+        }
+
+        self.floors[depth as usize].take()
+    }
+
+    pub fn floors(&self) -> Vec<&Floor> {
+        self.floors.iter().filter_map(|floor| floor.as_ref()).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.floors.clear();
+    }
+}
+
+#[test]
+fn test_store_and_take() {
+    let mut dungeon = Dungeon::new();
+    let mut level = Level::new();
+    level.level = 3;
+
+    dungeon.store(Floor { level, enemies: vec![Entity::new(7)] });
+
+    assert!(dungeon.take(2).is_none());
+    assert_eq!(1, dungeon.floors().len());
+
+    let floor = dungeon.take(3).expect("Floor should be stored.");
+    assert_eq!(7, floor.enemies[0].id);
+
+    assert!(dungeon.take(3).is_none());
+    assert!(dungeon.take(-1).is_none());
+}
@@ -1,7 +1,9 @@
-use super::log::Log;
+use super::event::{Event, EventQueue, Actor};
 use super::character::entity::Entity;
 use super::combat::fight::{Fight, RndGenerator};
-use super::level::{Level, Tile};
+use super::level::{Level, Tile, SIGHT_RADIUS};
+
+use std::cmp;
 
 pub trait Effect {
     /// Checks if this effect is valid to add to game state.
@@ -14,11 +16,11 @@ pub trait Effect {
         }
     }
 
-    /// Executes given effect on other entity.
-    fn execute(&mut self, log: &mut Log, map: &mut Level, me: &mut Entity, other: &mut Entity);
+    /// Executes given effect on other entity, occupied are the positions of the other living entities.
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, other: &mut Entity, occupied: &[(i32, i32)]);
 
     /// Checks if given effect is done.
-    fn done(&mut self, me: &mut Entity, map: &mut Level) -> bool;
+    fn done(&mut self, events: &mut EventQueue, me: &mut Entity, map: &mut Level, occupied: &[(i32, i32)]) -> bool;
 
     /// the actors id.
     fn actor_id(&self) -> u32;
@@ -28,9 +30,142 @@ pub trait Effect {
 
     //To determine if direction is needed.
     fn needs_direction(&self) -> bool;
+
+    /// Turns until the effect is done, e.g. until a special can be used again.
+    fn cool_down(&self) -> u32 {
+        0
+    }
+
+    /// Tiles the effect is going to hit, seen from its actor, living entities stand on the occupied tiles.
+    fn area(&self, _me: &Entity, _map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        Vec::new()
+    }
+}
+
+/// Abilities which can be bound to the special slots.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Special {
+    WeaponHit,
+    Storm,
+    RoundHouse,
+    Backstab,
+    Dash,
+    PiercingShot,
+    Volley,
+    Mend,
+    Firebolt,
+    FrostNova,
+    Blink,
+    Heal,
+}
+
+/// Every special, e.g. to find the special of an effect.
+pub const SPECIALS: [Special; 12] = [
+    Special::WeaponHit, Special::Storm, Special::RoundHouse, Special::Backstab,
+    Special::Dash, Special::PiercingShot, Special::Volley, Special::Mend,
+    Special::Firebolt, Special::FrostNova, Special::Blink, Special::Heal,
+];
+
+/// Specials which are learned from scrolls and tomes.
+pub const SPELLS: [Special; 4] = [Special::Firebolt, Special::FrostNova, Special::Blink, Special::Heal];
+
+impl Special {
+    /// The special which creates effects with given id.
+    pub fn of_effect(effect_id: u32) -> Option<Special> {
+        SPECIALS.iter().cloned().find(|special| special.effect_id() == effect_id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Special::WeaponHit => "Hit",
+            Special::Storm => "Storm",
+            Special::RoundHouse => "Round-House",
+            Special::Backstab => "Backstab",
+            Special::Dash => "Dash",
+            Special::PiercingShot => "Piercing Shot",
+            Special::Volley => "Volley",
+            Special::Mend => "Mend",
+            Special::Firebolt => "Firebolt",
+            Special::FrostNova => "Frost Nova",
+            Special::Blink => "Blink",
+            Special::Heal => "Heal",
+        }
+    }
+
+    /// Creates the effect, direction is ignored by effects which do not need one.
+    pub fn create(&self, id: u32, direction: AttackDirection) -> Box<dyn Effect> {
+        match *self {
+            Special::WeaponHit => Box::new(WeaponHit::new(id, direction)),
+            Special::Storm => Box::new(Storm::new(id, direction)),
+            Special::RoundHouse => Box::new(RoundHouse::new(id)),
+            Special::Backstab => Box::new(Backstab::new(id, direction)),
+            Special::Dash => Box::new(Dash::new(id, direction)),
+            Special::PiercingShot => Box::new(PiercingShot::new(id, direction)),
+            Special::Volley => Box::new(Volley::new(id)),
+            Special::Mend => Box::new(Mend::new(id)),
+            Special::Firebolt => Box::new(Firebolt::new(id, direction)),
+            Special::FrostNova => Box::new(FrostNova::new(id)),
+            Special::Blink => Box::new(Blink::new(id, direction)),
+            Special::Heal => Box::new(Heal::new(id)),
+        }
+    }
+
+    pub fn needs_direction(&self) -> bool {
+        match *self {
+            Special::WeaponHit | Special::Storm | Special::Backstab | Special::Dash | Special::PiercingShot | Special::Firebolt | Special::Blink => true,
+            Special::RoundHouse | Special::Volley | Special::Mend | Special::FrostNova | Special::Heal => false,
+        }
+    }
+
+    /// Directions the special can be aimed at, moving specials only go straight.
+    pub fn directions(&self) -> &'static [AttackDirection] {
+        match *self {
+            Special::WeaponHit | Special::Backstab => &ATTACK_DIRECTIONS,
+            _ => &ATTACK_DIRECTIONS[..4],
+        }
+    }
+
+    pub fn effect_id(&self) -> u32 {
+        match *self {
+            Special::WeaponHit => 1,
+            Special::Storm => 2,
+            Special::RoundHouse => 3,
+            Special::Backstab => 4,
+            Special::Dash => 5,
+            Special::PiercingShot => 6,
+            Special::Volley => 7,
+            Special::Mend => 8,
+            Special::Firebolt => 9,
+            Special::FrostNova => 10,
+            Special::Blink => 11,
+            Special::Heal => 12,
+        }
+    }
+
+    /// Energy needed to use the special.
+    pub fn cost(&self) -> i32 {
+        match *self {
+            Special::WeaponHit => 0,
+            Special::Storm => 40,
+            Special::RoundHouse => 20,
+            Special::Backstab => 25,
+            Special::Dash => 15,
+            Special::PiercingShot => 10,
+            Special::Volley => 30,
+            Special::Mend => 40,
+            Special::Firebolt => 20,
+            Special::FrostNova => 35,
+            Special::Blink => 25,
+            Special::Heal => 30,
+        }
+    }
+
+    pub fn is_spell(&self) -> bool {
+        SPELLS.contains(self)
+    }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum AttackDirection {
     North,
     NorthEast,
@@ -43,6 +178,28 @@ pub enum AttackDirection {
     RoundHouseKick,
 }
 
+/// Straight directions first, then the diagonals.
+pub const ATTACK_DIRECTIONS: [AttackDirection; 8] = [
+    AttackDirection::North, AttackDirection::East, AttackDirection::South, AttackDirection::West,
+    AttackDirection::NorthEast, AttackDirection::SouthEast, AttackDirection::SouthWest, AttackDirection::NorthWest,
+];
+
+impl AttackDirection {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            AttackDirection::North => "north",
+            AttackDirection::NorthEast => "north-east",
+            AttackDirection::East => "east",
+            AttackDirection::SouthEast => "south-east",
+            AttackDirection::South => "south",
+            AttackDirection::SouthWest => "south-west",
+            AttackDirection::West => "west",
+            AttackDirection::NorthWest => "north-west",
+            AttackDirection::RoundHouseKick => "around",
+        }
+    }
+}
+
 pub struct WeaponHit {
     pub direction: AttackDirection,
     pub id: u32,
@@ -56,11 +213,11 @@ impl WeaponHit {
 
 
 impl Effect for WeaponHit {
-    fn execute(&mut self, log: &mut Log, map: &mut Level, me: &mut Entity, mut other: &mut Entity) {
-        simple_attack(&self.direction, log, me.pos_row, me.pos_col, me, other, 10);
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, mut other: &mut Entity, _occupied: &[(i32, i32)]) {
+        simple_attack(&self.direction, events, me.pos_row, me.pos_col, me, other, 10);
     }
 
-    fn done(&mut self, me: &mut Entity, map: &mut Level) -> bool {
+    fn done(&mut self, _events: &mut EventQueue, me: &mut Entity, map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
         true
     }
     fn actor_id(&self) -> u32 {
@@ -73,14 +230,18 @@ impl Effect for WeaponHit {
     fn needs_direction(&self) -> bool {
         true
     }
+
+    fn area(&self, me: &Entity, _map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        resolve_attack_area(&self.direction, me.pos_row, me.pos_col)
+    }
 }
 
-fn simple_attack(direction: &AttackDirection, log: &mut Log, pos_row: i32, pos_col: i32, me: &mut Entity, mut other: &mut Entity, crit_chance: i32) {
+fn simple_attack(direction: &AttackDirection, events: &mut EventQueue, pos_row: i32, pos_col: i32, me: &mut Entity, mut other: &mut Entity, crit_chance: i32) {
     let attack_area = resolve_attack_area(direction, pos_row, pos_col);
 
     for (row, col) in attack_area {
         if other.pos_row == row && other.pos_col == col {
-            Fight::weapon_hit(log, RndGenerator, me, &mut other, crit_chance);
+            Fight::weapon_hit(events, RndGenerator, me, &mut other, crit_chance);
         }
     }
 }
@@ -177,7 +338,7 @@ impl Storm {
 }
 
 impl Effect for Storm {
-    fn execute(&mut self, log: &mut Log, map: &mut Level, me: &mut Entity, other: &mut Entity) {
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, other: &mut Entity, _occupied: &[(i32, i32)]) {
         if !self.activated {
             let (row, col) = resolve_direction(&self.direction);
             let mut steps = 5;
@@ -188,7 +349,7 @@ impl Effect for Storm {
             while steps > 0 {
                 steps -= 1;
 
-                simple_attack(&self.direction, log, pos_row, pos_col, me, other, 100);
+                simple_attack(&self.direction, events, pos_row, pos_col, me, other, 100);
 
                 if map.map[(pos_row + row) as usize][(pos_col + col) as usize] == Tile::Floor {
                     pos_row += row;
@@ -196,11 +357,11 @@ impl Effect for Storm {
                 }
             }
 
-            simple_attack(&self.direction, log, pos_row, pos_col, me, other, 100);
+            simple_attack(&self.direction, events, pos_row, pos_col, me, other, 100);
         }
     }
 
-    fn done(&mut self, me: &mut Entity, map: &mut Level) -> bool {
+    fn done(&mut self, _events: &mut EventQueue, me: &mut Entity, map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
         if self.activated {
             self.cool_down -= 1;
 
@@ -233,6 +394,36 @@ impl Effect for Storm {
     fn needs_direction(&self) -> bool {
         true
     }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    /// Everything hit on the way of the storm.
+    fn area(&self, me: &Entity, map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut area = Vec::new();
+        if self.activated {
+            return area;
+        }
+
+        let (row, col) = resolve_direction(&self.direction);
+        let mut pos_row = me.pos_row;
+        let mut pos_col = me.pos_col;
+
+        for _ in 0..5 {
+            area.extend(resolve_attack_area(&self.direction, pos_row, pos_col));
+
+            if map.map[(pos_row + row) as usize][(pos_col + col) as usize] == Tile::Floor {
+                pos_row += row;
+                pos_col += col;
+            }
+        }
+        area.extend(resolve_attack_area(&self.direction, pos_row, pos_col));
+
+        area.sort();
+        area.dedup();
+        area
+    }
 }
 
 fn resolve_direction(direction: &AttackDirection) -> (i32, i32) {
@@ -259,13 +450,13 @@ impl RoundHouse {
 }
 
 impl Effect for RoundHouse {
-    fn execute(&mut self, log: &mut Log, map: &mut Level, me: &mut Entity, other: &mut Entity) {
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, other: &mut Entity, _occupied: &[(i32, i32)]) {
         if !self.activated {
-            simple_attack(&AttackDirection::RoundHouseKick, log, me.pos_row, me.pos_col, me, other, 100);
+            simple_attack(&AttackDirection::RoundHouseKick, events, me.pos_row, me.pos_col, me, other, 100);
         }
     }
 
-    fn done(&mut self, me: &mut Entity, map: &mut Level) -> bool {
+    fn done(&mut self, _events: &mut EventQueue, me: &mut Entity, map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
         if self.activated {
             self.cool_down -= 1;
 
@@ -286,4 +477,675 @@ impl Effect for RoundHouse {
     fn needs_direction(&self) -> bool {
         false
     }
-}
\ No newline at end of file
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    fn area(&self, me: &Entity, _map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        if self.activated {
+            return Vec::new();
+        }
+
+        resolve_attack_area(&AttackDirection::RoundHouseKick, me.pos_row, me.pos_col)
+    }
+}
+
+/// Directed hit, which is always critical.
+pub struct Backstab {
+    pub direction: AttackDirection,
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl Backstab {
+    pub fn new(id: u32, direction: AttackDirection) -> Backstab {
+        Backstab { id, direction, activated: false, cool_down: 4 }
+    }
+}
+
+impl Effect for Backstab {
+    fn execute(&mut self, events: &mut EventQueue, _map: &mut Level, me: &mut Entity, other: &mut Entity, _occupied: &[(i32, i32)]) {
+        if !self.activated {
+            simple_attack(&self.direction, events, me.pos_row, me.pos_col, me, other, 100);
+        }
+    }
+
+    fn done(&mut self, _events: &mut EventQueue, _me: &mut Entity, _map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            self.cool_down == 0
+        } else {
+            self.activated = true;
+            false
+        }
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        4
+    }
+
+    fn needs_direction(&self) -> bool {
+        true
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    fn area(&self, me: &Entity, _map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        if self.activated {
+            return Vec::new();
+        }
+
+        resolve_attack_area(&self.direction, me.pos_row, me.pos_col)
+    }
+}
+
+/// Quick move in a direction without attacking.
+pub struct Dash {
+    pub direction: AttackDirection,
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl Dash {
+    pub fn new(id: u32, direction: AttackDirection) -> Dash {
+        Dash { id, direction, activated: false, cool_down: 6 }
+    }
+}
+
+impl Effect for Dash {
+    fn execute(&mut self, _events: &mut EventQueue, _map: &mut Level, _me: &mut Entity, _other: &mut Entity, _occupied: &[(i32, i32)]) {}
+
+    fn done(&mut self, _events: &mut EventQueue, me: &mut Entity, map: &mut Level, occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            return self.cool_down == 0;
+        }
+
+        if let Option::Some(&(row, col)) = self.area(me, map, occupied).last() {
+            me.pos_row = row;
+            me.pos_col = col;
+        }
+
+        self.activated = true;
+        false
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        5
+    }
+
+    fn needs_direction(&self) -> bool {
+        true
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    /// Tiles the dash moves over.
+    fn area(&self, me: &Entity, map: &Level, occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut area = Vec::new();
+        if self.activated {
+            return area;
+        }
+
+        let (row, col) = resolve_direction(&self.direction);
+        let mut pos_row = me.pos_row;
+        let mut pos_col = me.pos_col;
+
+        //Stops in front of walls and living entities.
+        for _ in 0..4 {
+            if map.map[(pos_row + row) as usize][(pos_col + col) as usize] != Tile::Floor || occupied.contains(&(pos_row + row, pos_col + col)) {
+                break;
+            }
+
+            pos_row += row;
+            pos_col += col;
+            area.push((pos_row, pos_col));
+        }
+
+        area
+    }
+}
+
+/// Arrow which hits everyone in a straight line until it reaches a wall.
+pub struct PiercingShot {
+    pub direction: AttackDirection,
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl PiercingShot {
+    pub fn new(id: u32, direction: AttackDirection) -> PiercingShot {
+        PiercingShot { id, direction, activated: false, cool_down: 2 }
+    }
+}
+
+impl Effect for PiercingShot {
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, other: &mut Entity, _occupied: &[(i32, i32)]) {
+        if self.activated {
+            return;
+        }
+
+        let (row, col) = resolve_direction(&self.direction);
+        let mut pos_row = me.pos_row + row;
+        let mut pos_col = me.pos_col + col;
+
+        for _ in 0..6 {
+            if map.map[pos_row as usize][pos_col as usize] != Tile::Floor {
+                break;
+            }
+
+            if other.pos_row == pos_row && other.pos_col == pos_col {
+                Fight::weapon_hit(events, RndGenerator, me, other, 10);
+                break;
+            }
+
+            pos_row += row;
+            pos_col += col;
+        }
+    }
+
+    fn done(&mut self, _events: &mut EventQueue, _me: &mut Entity, _map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            self.cool_down == 0
+        } else {
+            self.activated = true;
+            false
+        }
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        6
+    }
+
+    fn needs_direction(&self) -> bool {
+        true
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    fn area(&self, me: &Entity, map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut area = Vec::new();
+        if self.activated {
+            return area;
+        }
+
+        let (row, col) = resolve_direction(&self.direction);
+        let mut pos_row = me.pos_row + row;
+        let mut pos_col = me.pos_col + col;
+
+        for _ in 0..6 {
+            if map.map[pos_row as usize][pos_col as usize] != Tile::Floor {
+                break;
+            }
+
+            area.push((pos_row, pos_col));
+            pos_row += row;
+            pos_col += col;
+        }
+
+        area
+    }
+}
+
+/// Hits everyone nearby.
+pub struct Volley {
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl Volley {
+    pub fn new(id: u32) -> Volley {
+        Volley { id, activated: false, cool_down: 8 }
+    }
+}
+
+impl Effect for Volley {
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, other: &mut Entity, occupied: &[(i32, i32)]) {
+        if !self.activated && self.area(me, map, occupied).contains(&(other.pos_row, other.pos_col)) {
+            Fight::weapon_hit(events, RndGenerator, me, other, 10);
+        }
+    }
+
+    fn done(&mut self, _events: &mut EventQueue, _me: &mut Entity, _map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            self.cool_down == 0
+        } else {
+            self.activated = true;
+            false
+        }
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        7
+    }
+
+    fn needs_direction(&self) -> bool {
+        false
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    /// Floor in sight around the archer, arrows do not fly through walls.
+    fn area(&self, me: &Entity, map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut area = Vec::new();
+        if self.activated {
+            return area;
+        }
+
+        for row in me.pos_row - 3..me.pos_row + 4 {
+            for col in me.pos_col - 3..me.pos_col + 4 {
+                let open = map.is_walkable(row as usize, col as usize) && map.in_sight(me.pos_row, me.pos_col, row, col);
+
+                if (row, col) != (me.pos_row, me.pos_col) && open {
+                    area.push((row, col));
+                }
+            }
+        }
+
+        area
+    }
+}
+
+/// Heals a quarter of the maximum life.
+pub struct Mend {
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl Mend {
+    pub fn new(id: u32) -> Mend {
+        Mend { id, activated: false, cool_down: 15 }
+    }
+}
+
+impl Effect for Mend {
+    fn execute(&mut self, _events: &mut EventQueue, _map: &mut Level, _me: &mut Entity, _other: &mut Entity, _occupied: &[(i32, i32)]) {}
+
+    fn done(&mut self, events: &mut EventQueue, me: &mut Entity, _map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            return self.cool_down == 0;
+        }
+
+        let max_life = me.calculate_max_life();
+        let amount = std::cmp::min(max_life / 4, max_life - me.current_life);
+        me.current_life += amount;
+
+        events.push(Event::Heal { target: Actor::of(me), amount });
+
+        self.activated = true;
+        false
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        8
+    }
+
+    fn needs_direction(&self) -> bool {
+        false
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+}
+
+/// Spell which burns the first one in a straight line, stronger with intelligence.
+pub struct Firebolt {
+    pub direction: AttackDirection,
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl Firebolt {
+    pub fn new(id: u32, direction: AttackDirection) -> Firebolt {
+        Firebolt { id, direction, activated: false, cool_down: 3 }
+    }
+}
+
+impl Effect for Firebolt {
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, other: &mut Entity, occupied: &[(i32, i32)]) {
+        if !self.activated && self.area(me, map, occupied).contains(&(other.pos_row, other.pos_col)) {
+            Fight::spell_hit(events, RndGenerator, me, other, 6);
+        }
+    }
+
+    fn done(&mut self, _events: &mut EventQueue, _me: &mut Entity, _map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            self.cool_down == 0
+        } else {
+            self.activated = true;
+            false
+        }
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        9
+    }
+
+    fn needs_direction(&self) -> bool {
+        true
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    /// Tiles the bolt flies over, up to the first living entity.
+    fn area(&self, me: &Entity, map: &Level, occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut area = Vec::new();
+        if self.activated {
+            return area;
+        }
+
+        let (row, col) = resolve_direction(&self.direction);
+        let mut pos_row = me.pos_row + row;
+        let mut pos_col = me.pos_col + col;
+
+        for _ in 0..8 {
+            if map.map[pos_row as usize][pos_col as usize] != Tile::Floor {
+                break;
+            }
+
+            area.push((pos_row, pos_col));
+            if occupied.contains(&(pos_row, pos_col)) {
+                break;
+            }
+
+            pos_row += row;
+            pos_col += col;
+        }
+
+        area
+    }
+}
+
+/// Spell which hurts everyone close by with frost.
+pub struct FrostNova {
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl FrostNova {
+    pub fn new(id: u32) -> FrostNova {
+        FrostNova { id, activated: false, cool_down: 6 }
+    }
+}
+
+impl Effect for FrostNova {
+    fn execute(&mut self, events: &mut EventQueue, map: &mut Level, me: &mut Entity, other: &mut Entity, occupied: &[(i32, i32)]) {
+        if !self.activated && self.area(me, map, occupied).contains(&(other.pos_row, other.pos_col)) {
+            Fight::spell_hit(events, RndGenerator, me, other, 3);
+        }
+    }
+
+    fn done(&mut self, _events: &mut EventQueue, _me: &mut Entity, _map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            self.cool_down == 0
+        } else {
+            self.activated = true;
+            false
+        }
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        10
+    }
+
+    fn needs_direction(&self) -> bool {
+        false
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    /// Floor in sight around the caster, the frost stops at walls.
+    fn area(&self, me: &Entity, map: &Level, _occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut area = Vec::new();
+        if self.activated {
+            return area;
+        }
+
+        for row in me.pos_row - 2..me.pos_row + 3 {
+            for col in me.pos_col - 2..me.pos_col + 3 {
+                let open = map.is_walkable(row as usize, col as usize) && map.in_sight(me.pos_row, me.pos_col, row, col);
+
+                if (row, col) != (me.pos_row, me.pos_col) && open {
+                    area.push((row, col));
+                }
+            }
+        }
+
+        area
+    }
+}
+
+/// Spell which teleports as far as the eye reaches in a direction, farther with intelligence.
+pub struct Blink {
+    pub direction: AttackDirection,
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl Blink {
+    pub fn new(id: u32, direction: AttackDirection) -> Blink {
+        Blink { id, direction, activated: false, cool_down: 8 }
+    }
+}
+
+impl Effect for Blink {
+    fn execute(&mut self, _events: &mut EventQueue, _map: &mut Level, _me: &mut Entity, _other: &mut Entity, _occupied: &[(i32, i32)]) {}
+
+    fn done(&mut self, _events: &mut EventQueue, me: &mut Entity, map: &mut Level, occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            return self.cool_down == 0;
+        }
+
+        if let Option::Some(&(row, col)) = self.area(me, map, occupied).last() {
+            me.pos_row = row;
+            me.pos_col = col;
+        }
+
+        self.activated = true;
+        false
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        11
+    }
+
+    fn needs_direction(&self) -> bool {
+        true
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+
+    /// Tile the blink ends on, it jumps over living entities but never lands on them.
+    fn area(&self, me: &Entity, map: &Level, occupied: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        if self.activated {
+            return Vec::new();
+        }
+
+        let range = cmp::min(SIGHT_RADIUS, 3 + me.calculate_stats().intelligence / 2);
+        let (row, col) = resolve_direction(&self.direction);
+        let mut target = Option::None;
+
+        for step in 1..range + 1 {
+            let (pos_row, pos_col) = (me.pos_row + row * step, me.pos_col + col * step);
+            if !map.is_walkable(pos_row as usize, pos_col as usize) || !map.in_sight(me.pos_row, me.pos_col, pos_row, pos_col) {
+                break;
+            }
+
+            if !occupied.contains(&(pos_row, pos_col)) {
+                target = Option::Some((pos_row, pos_col));
+            }
+        }
+
+        target.into_iter().collect()
+    }
+}
+
+/// Spell which heals, more with intelligence.
+pub struct Heal {
+    pub id: u32,
+
+    activated: bool,
+    cool_down: u32,
+}
+
+impl Heal {
+    pub fn new(id: u32) -> Heal {
+        Heal { id, activated: false, cool_down: 10 }
+    }
+}
+
+impl Effect for Heal {
+    fn execute(&mut self, _events: &mut EventQueue, _map: &mut Level, _me: &mut Entity, _other: &mut Entity, _occupied: &[(i32, i32)]) {}
+
+    fn done(&mut self, events: &mut EventQueue, me: &mut Entity, _map: &mut Level, _occupied: &[(i32, i32)]) -> bool {
+        if self.activated {
+            self.cool_down -= 1;
+
+            return self.cool_down == 0;
+        }
+
+        let amount = cmp::min(10 + 4 * me.calculate_stats().intelligence, me.calculate_max_life() - me.current_life);
+        me.current_life += amount;
+
+        events.push(Event::Heal { target: Actor::of(me), amount });
+
+        self.activated = true;
+        false
+    }
+
+    fn actor_id(&self) -> u32 {
+        self.id
+    }
+
+    fn effect_id(&self) -> u32 {
+        12
+    }
+
+    fn needs_direction(&self) -> bool {
+        false
+    }
+
+    fn cool_down(&self) -> u32 {
+        self.cool_down
+    }
+}
+
+#[test]
+fn test_special_matches_effect() {
+    for special in SPECIALS.iter() {
+        let effect = special.create(0, AttackDirection::North);
+
+        assert_eq!(special.effect_id(), effect.effect_id());
+        assert_eq!(special.needs_direction(), effect.needs_direction());
+    }
+}
+
+#[test]
+fn test_volley_area() {
+    let mut map = Level::new();
+    map.map[2][4] = Tile::Wall;
+    let mut me = Entity::new(0);
+    me.pos_row = 2;
+    me.pos_col = 2;
+
+    let area = Volley::new(0).area(&me, &map, &[]);
+
+    assert!(area.contains(&(5, 5)));
+    assert!(!area.contains(&(1, 2)));
+    assert!(!area.contains(&(2, 4)));
+    assert!(!area.contains(&(2, 5)));
+}
+
+#[test]
+fn test_frost_nova_area() {
+    let mut map = Level::new();
+    map.map[3][3] = Tile::Wall;
+    let mut me = Entity::new(0);
+    me.pos_row = 2;
+    me.pos_col = 2;
+
+    let area = FrostNova::new(0).area(&me, &map, &[]);
+
+    assert!(area.contains(&(2, 4)));
+    assert!(!area.contains(&(1, 1)));
+    assert!(!area.contains(&(3, 3)));
+    assert!(!area.contains(&(4, 4)));
+}
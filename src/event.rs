@@ -0,0 +1,239 @@
+use super::character::entity::{Entity, MonsterType};
+use super::effect::Special;
+
+/// Who took part in an event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Actor {
+    pub id: u32,
+    pub name: String,
+    pub monster_type: MonsterType,
+}
+
+impl Actor {
+    pub fn of(entity: &Entity) -> Actor {
+        Actor { id: entity.id, name: entity.name.clone(), monster_type: entity.monster_type }
+    }
+}
+
+/// Something that happened in the game.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Hit {
+        attacker: Actor,
+        target: Actor,
+        damage: i32
+    },
+    Miss {
+        attacker: Actor,
+        target: Actor
+    },
+    /// A hit with doubled damage.
+    Crit {
+        attacker: Actor,
+        target: Actor,
+        damage: i32
+    },
+    /// Follows the hit which killed the target.
+    Kill {
+        attacker: Actor,
+        target: Actor
+    },
+    Heal {
+        target: Actor,
+        amount: i32
+    },
+    ItemPickedUp {
+        actor: Actor,
+        item: String
+    },
+    LevelEntered {
+        actor: Actor,
+        depth: i32
+    },
+    EffectApplied {
+        actor: Actor,
+        effect_id: u32
+    },
+}
+
+/// Reacts on events, e.g. writes the log or counts kills.
+pub trait Subscriber {
+    fn notify(&mut self, event: &Event);
+}
+
+/// Events which are not yet delivered to subscribers.
+#[derive(Default)]
+pub struct EventQueue {
+    events: Vec<Event>,
+}
+
+impl EventQueue {
+    pub fn new() -> EventQueue {
+        EventQueue { events: Vec::new() }
+    }
+
+    pub fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Removes all events in the order they happened.
+    pub fn drain(&mut self) -> Vec<Event> {
+        self.events.drain(..).collect()
+    }
+}
+
+/// Numbers about the players run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    pub player_id: u32,
+
+    pub kills: u32,
+    pub crits: u32,
+    pub misses: u32,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub healed: i32,
+    pub items_picked_up: u32,
+    pub specials_used: u32,
+    pub deepest_level: i32,
+    /// Name of whoever killed the player.
+    pub killed_by: Option<String>,
+}
+
+impl Statistics {
+    pub fn new(player_id: u32) -> Statistics {
+        Statistics { player_id, kills: 0, crits: 0, misses: 0, damage_dealt: 0, damage_taken: 0, healed: 0, items_picked_up: 0, specials_used: 0, deepest_level: 0, killed_by: Option::None }
+    }
+
+    fn count_damage(&mut self, attacker: &Actor, target: &Actor, damage: i32) {
+        if attacker.id == self.player_id {
+            self.damage_dealt += damage;
+        } else if target.id == self.player_id {
+            self.damage_taken += damage;
+        }
+    }
+}
+
+impl Subscriber for Statistics {
+    fn notify(&mut self, event: &Event) {
+        match *event {
+            Event::Hit { ref attacker, ref target, damage } => {
+                self.count_damage(attacker, target, damage);
+            },
+            Event::Crit { ref attacker, ref target, damage } => {
+                if attacker.id == self.player_id {
+                    self.crits += 1;
+                }
+                self.count_damage(attacker, target, damage);
+            },
+            Event::Miss { ref attacker, .. } => {
+                if attacker.id == self.player_id {
+                    self.misses += 1;
+                }
+            },
+            Event::Kill { ref attacker, ref target } => {
+                if attacker.id == self.player_id {
+                    self.kills += 1;
+                } else if target.id == self.player_id {
+                    self.killed_by = Option::Some(attacker.name.clone());
+                }
+            },
+            Event::Heal { ref target, amount } => {
+                if target.id == self.player_id {
+                    self.healed += amount;
+                }
+            },
+            Event::ItemPickedUp { ref actor, .. } => {
+                if actor.id == self.player_id {
+                    self.items_picked_up += 1;
+                }
+            },
+            Event::LevelEntered { ref actor, depth } => {
+                if actor.id == self.player_id && depth > self.deepest_level {
+                    self.deepest_level = depth;
+                }
+            },
+            Event::EffectApplied { ref actor, effect_id } => {
+                //WeaponHit is no special.
+                if actor.id == self.player_id && effect_id != Special::WeaponHit.effect_id() {
+                    self.specials_used += 1;
+                }
+            },
+        }
+    }
+}
+
+/// Unlocks named achievements, new ones are collected until they are announced.
+pub struct Achievements {
+    pub unlocked: Vec<&'static str>,
+    pub announce: Vec<&'static str>,
+
+    statistics: Statistics,
+}
+
+impl Achievements {
+    pub fn new(player_id: u32) -> Achievements {
+        Achievements { unlocked: Vec::new(), announce: Vec::new(), statistics: Statistics::new(player_id) }
+    }
+
+    fn unlock(&mut self, name: &'static str, reached: bool) {
+        if reached && !self.unlocked.contains(&name) {
+            self.unlocked.push(name);
+            self.announce.push(name);
+        }
+    }
+}
+
+impl Subscriber for Achievements {
+    fn notify(&mut self, event: &Event) {
+        self.statistics.notify(event);
+
+        let kills = self.statistics.kills;
+        let crits = self.statistics.crits;
+        let deepest_level = self.statistics.deepest_level;
+
+        self.unlock("First Blood", kills >= 1);
+        self.unlock("Butcher", kills >= 50);
+        self.unlock("Critical Thinker", crits >= 10);
+        self.unlock("Deep Diver", deepest_level >= 5);
+        self.unlock("Boss Hunter", deepest_level >= 11);
+    }
+}
+
+#[test]
+fn test_statistics() {
+    let player = Actor { id: 0, name: "Player".to_string(), monster_type: MonsterType::Unknown };
+    let crab = Actor { id: 1, name: "Crab".to_string(), monster_type: MonsterType::Crab };
+
+    let mut queue = EventQueue::new();
+    queue.push(Event::Hit { attacker: player.clone(), target: crab.clone(), damage: 4 });
+    queue.push(Event::Crit { attacker: player.clone(), target: crab.clone(), damage: 10 });
+    queue.push(Event::Kill { attacker: player.clone(), target: crab.clone() });
+    queue.push(Event::Hit { attacker: crab.clone(), target: player.clone(), damage: 3 });
+    queue.push(Event::LevelEntered { actor: player.clone(), depth: 2 });
+    queue.push(Event::Kill { attacker: crab.clone(), target: player.clone() });
+
+    let mut statistics = Statistics::new(0);
+    let mut achievements = Achievements::new(0);
+    for event in queue.drain() {
+        statistics.notify(&event);
+        achievements.notify(&event);
+    }
+
+    assert!(queue.is_empty());
+    assert_eq!(1, statistics.kills);
+    assert_eq!(1, statistics.crits);
+    assert_eq!(14, statistics.damage_dealt);
+    assert_eq!(3, statistics.damage_taken);
+    assert_eq!(2, statistics.deepest_level);
+    assert_eq!(Option::Some("Crab".to_string()), statistics.killed_by);
+    assert_eq!(vec!["First Blood"], achievements.unlocked);
+}
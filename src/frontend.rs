@@ -0,0 +1,52 @@
+use super::character::backpack::Backpack;
+use super::character::class::Class;
+use super::character::entity::Entity;
+use super::character::item::Type;
+use super::event::Subscriber;
+use super::input::Input;
+use super::level::Level;
+use super::log::Log;
+use super::score::HighScore;
+use super::theme::Theme;
+
+/// Shows the game and reads the players input, e.g. the ncurses window.
+/// Gets every event after it happened, e.g. to flash hit entities.
+pub trait Frontend: Subscriber {
+    fn apply_theme(&mut self, theme: &Theme);
+
+    /// Waits for the next input in the game and menus.
+    fn get_input(&mut self) -> Input;
+    /// Waits for the next input while text is typed, letters are not mapped to actions.
+    fn get_text_input(&mut self) -> Input;
+
+    /// Lays everything out again after the screen changed its size.
+    fn resize(&mut self);
+    /// False if the screen is too small for the game.
+    fn fits(&self) -> bool;
+    fn draw_too_small(&mut self);
+
+    fn draw_title(&mut self, entries: &[(&str, bool)], index: usize, message: &str);
+    /// Field 0 is the name, 1 the class and 2 the seed.
+    fn draw_new_game(&mut self, name: &str, class: &Class, seed: &str, active_field: usize, message: &str);
+    fn draw_options(&mut self, level_size: &str);
+    fn draw_high_scores(&mut self, scores: &[HighScore]);
+
+    /// Specials are given by name, turns until they are ready and energy cost.
+    fn draw(&mut self, log: &Log, level: &Level, player: &Entity, enemies: &[Entity], specials: &[(&str, u32, i32)]);
+    fn draw_loot(&mut self, backpack: &Backpack, backpack_index: usize, active: bool, name: &str);
+    fn draw_entity(&mut self, player: &Entity, character_pointer: Type, active: bool);
+    fn draw_menu(&mut self);
+    fn draw_game_over(&mut self, summary: &[String]);
+    /// Offset is the number of messages scrolled back.
+    fn draw_history(&mut self, log: &Log, offset: usize);
+    /// Marks the looked at position on the map, which is drawn before.
+    fn draw_look(&mut self, row: usize, col: usize, description: &[String]);
+    /// Marks the area a special would hit in the aimed direction, the map is drawn before.
+    fn draw_target(&mut self, special: &str, direction: &str, area: &[(i32, i32)], targets: &[String]);
+    /// Abilities are given by name and energy cost, slots by the name of the bound ability.
+    fn draw_spellbook(&mut self, abilities: &[(&str, i32)], index: usize, slots: &[&str]);
+    /// Shows what the named entity says and the answers to choose from.
+    fn draw_dialogue(&mut self, name: &str, text: &[String], choices: &[&str], index: usize);
+    /// Quests are given by their description and whether they are completed.
+    fn draw_quests(&mut self, quests: &[(String, bool)]);
+}
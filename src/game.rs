@@ -3,10 +3,31 @@ use super::character::item::*;
 use super::level::*;
 use super::gen::level::*;
 use super::gen::monster::*;
-use super::ui::*;
-use super::effect::{AttackDirection, WeaponHit, Storm, RoundHouse, Effect};
+use super::input::Input;
+use super::frontend::Frontend;
+use super::effect::{AttackDirection, WeaponHit, Effect, Special};
+use super::character::class::{Class, CLASSES};
 use super::log::*;
 use super::ki::*;
+use super::dungeon::{Dungeon, Floor};
+use super::save::*;
+use super::event::*;
+use super::theme::{Theme, THEME_FILE};
+use super::score::{HighScore, HighScores, HIGH_SCORE_FILE};
+use super::morgue::Morgue;
+use super::dialogue::{Dialogues, Dialogue, Consequence, DIALOGUE_FILE};
+use super::gen::item::{generate_item, generate_potion, generate_scroll};
+use super::quest::{Quests, Quest, QuestKind, Objective, Reward, MAX_QUESTS, heirloom_name};
+use super::rng;
+
+extern crate rand;
+
+use std::cmp;
+use std::fs;
+use std::io;
+use std::iter;
+use std::mem;
+use std::path::Path;
 
 /*
     Was kann ich verbessern:
@@ -24,10 +45,16 @@ use super::ki::*;
 
 pub struct Game {
     log: Log,
+    events: EventQueue,
+    statistics: Statistics,
+    achievements: Achievements,
+    quests: Quests,
     map: Level,
     player: Entity,
+    dungeon: Dungeon,
 
     entity_count: u32,
+    /// Everyone on the floor but the player, monsters and companions.
     enemies: Vec<Entity>,
     effects: Vec<Box<Effect>>,
 
@@ -36,120 +63,587 @@ pub struct Game {
     inventory_pointer: InventoryPointer,
     character_pointer: Type,
     enemy_loot_index: usize,
-
-    player_special_one: bool,
-    player_special_two: bool,
-    player_special_three: bool,
+    history_offset: usize,
+    /// Row and column in look mode.
+    look_cursor: (usize, usize),
+
+    title_index: usize,
+    /// Shown on the title and new game screen, e.g. why a save could not be loaded.
+    title_message: String,
+    name_input: String,
+    seed_input: String,
+    new_game_field: NewGameField,
+    level_size: (usize, usize),
+
+    player_class: Class,
+    /// Specials bound to the three slots, the class specials at first.
+    specials: [Special; 3],
+    /// Spells learned from scrolls and tomes.
+    spells: Vec<Special>,
+    /// Selected entry of the spellbook.
+    spellbook_index: usize,
+    /// Special which is being aimed.
+    pending_special: Option<Special>,
+    /// Where the pending special is aimed at.
+    target_direction: AttackDirection,
+
+    /// What friendly entities say.
+    dialogues: Dialogues,
+    /// Id of the entity the player talks to.
+    talk_partner: u32,
+    /// Shown node of its dialogue and the selected choice.
+    dialogue_node: usize,
+    dialogue_index: usize,
+
+    high_scores: HighScores,
+    /// False if the high score file could not be read nor moved aside, it is not overwritten then.
+    high_scores_writable: bool,
+    /// Whether deaths touch the save, high score and morgue files.
+    persistent: bool,
+    /// What happened to the last player, shown on the game over screen.
+    game_over_summary: Vec<String>,
 
     level_generator: LevelGenerator,
-    window: Window,
 }
 
 impl Game {
     pub fn new() -> Game {
         Game {
             log: Log::new(),
+            events: EventQueue::new(),
+            statistics: Statistics::new(0),
+            achievements: Achievements::new(0),
+            quests: Quests::new(0),
             map: Level::new(),
             player: Entity::new(0),
+            dungeon: Dungeon::new(),
 
             entity_count: 1,
             enemies: Vec::new(),
             effects: Vec::new(),
 
-            game_state: Action::Game,
+            game_state: Action::Title,
             backpack_index: 0,
             inventory_pointer: InventoryPointer::Backpack,
             character_pointer: Type::Head,
             enemy_loot_index: 0,
-
-            player_special_one: false,
-            player_special_two: false,
-            player_special_three: false,
+            history_offset: 0,
+            look_cursor: (0, 0),
+
+            title_index: 0,
+            title_message: String::new(),
+            name_input: "TamaNu".to_string(),
+            seed_input: String::new(),
+            new_game_field: NewGameField::Name,
+            level_size: (DEFAULT_ROWS, DEFAULT_COLS),
+
+            player_class: Class::Warrior,
+            specials: Class::Warrior.specials(),
+            spells: Vec::new(),
+            spellbook_index: 0,
+            pending_special: Option::None,
+            target_direction: AttackDirection::North,
+            dialogues: Dialogues::new(),
+            talk_partner: 0,
+            dialogue_node: 0,
+            dialogue_index: 0,
+            high_scores: HighScores::new(),
+            high_scores_writable: true,
+            persistent: true,
+            game_over_summary: Vec::new(),
 
             level_generator: LevelGenerator::new(),
-            window: Window::new(),
         }
     }
 
-    pub fn init(&mut self) {
-        self.player.name = "TamaNu".to_string();
+    pub fn init(&mut self, frontend: &mut dyn Frontend) {
+        match Theme::load(THEME_FILE) {
+            Result::Ok(theme) => frontend.apply_theme(&theme),
+            //Without a theme file the default colours are used.
+            Result::Err(ref error) if error.kind() == io::ErrorKind::NotFound => {},
+            Result::Err(error) => self.title_message = format!("Could not load theme: {}", error),
+        }
 
-        self.map = self.level_generator.generate_level(0);
+        match Dialogues::load(DIALOGUE_FILE) {
+            Result::Ok(dialogues) => self.dialogues = dialogues,
+            //Without a dialogue file the built-in dialogues are used.
+            Result::Err(ref error) if error.kind() == io::ErrorKind::NotFound => {},
+            Result::Err(error) => self.title_message = format!("Could not load dialogues: {}", error),
+        }
+
+        match HighScores::load(HIGH_SCORE_FILE) {
+            Result::Ok(high_scores) => self.high_scores = high_scores,
+            //The unreadable file is kept aside, so the next death does not overwrite it.
+            Result::Err(error) => {
+                let backup = format!("{}.bak", HIGH_SCORE_FILE);
+
+                self.title_message = match fs::rename(HIGH_SCORE_FILE, &backup) {
+                    Result::Ok(()) => format!("Could not load high scores, moved them to {}: {}", backup, error),
+                    Result::Err(_) => {
+                        self.high_scores_writable = false;
+                        format!("Could not load high scores, they are not saved: {}", error)
+                    },
+                };
+            },
+        }
 
+        if Path::new(SAVE_FILE).exists() {
+            self.title_index = TITLE_CONTINUE;
+        }
+    }
+
+    /// Headless games, e.g. of bots, should not touch the players files.
+    pub fn set_persistent(&mut self, persistent: bool) {
+        self.persistent = persistent;
+    }
+
+    /// Size of all generated levels except the boss level.
+    pub fn set_level_size(&mut self, size_rows: usize, size_cols: usize) {
+        self.level_size = (size_rows, size_cols);
+        self.level_generator.set_level_size(size_rows, size_cols);
+    }
+
+    /// Forgets everything about the last game.
+    fn reset(&mut self) {
+        self.log = Log::new();
+        self.events = EventQueue::new();
+        self.statistics = Statistics::new(0);
+        self.achievements = Achievements::new(0);
+        self.player = Entity::new(0);
+        self.player.faction = Faction::Allied;
+        self.quests = Quests::new(self.player.id);
+        self.entity_count = 1;
+        self.enemies.clear();
+        self.effects.clear();
+        self.dungeon.clear();
+
+        self.pending_special = Option::None;
+        self.spells.clear();
+        self.spellbook_index = 0;
+    }
+
+    /// Starts a new game right away, the same seed creates the same dungeon.
+    pub fn new_game(&mut self, name: String, class: Class, seed: usize) {
+        self.reset();
+        self.game_state = Action::Game;
+        self.player.name = name;
+        self.player_class = class;
+        self.specials = class.specials();
+        class.equip(&mut self.player);
+
+        self.level_generator.reseed(seed);
+        rng::reseed(seed);
+        self.map = self.level_generator.generate_level(0);
         self.set_player_and_monsters();
+
+        let mut pet = Entity::new(self.entity_count);
+        self.entity_count += 1;
+        create_companion(&self.player, &mut pet, class.pet());
+        self.place_companion(pet);
+
+        self.map.explore(self.player.pos_row, self.player.pos_col);
+
+        self.log.add_message(format!("Welcome {}, the dungeon seed is {}.", self.player.name, seed));
     }
 
-    pub fn run(&mut self) {
-        self.window.draw(&mut self.log, &self.map, &self.player, &self.enemies, false, false);
+    fn continue_game(&mut self) -> io::Result<()> {
+        let save = read_save(SAVE_FILE)?;
+
+        self.reset();
+        self.player = save.player;
+        self.player_class = save.progress.class;
+        self.specials = save.progress.specials;
+        self.spells = save.progress.spells;
+        self.log.set_turn(save.progress.turn);
+        self.statistics = save.progress.statistics;
+        self.quests = Quests::with_quests(self.player.id, save.progress.quests);
+        self.entity_count = save.entity_count;
+        self.map = save.current.level;
+        self.enemies = save.current.enemies;
+        self.dungeon = save.dungeon;
+
+        self.log.add_message(format!("Welcome back {}.", self.player.name));
+
+        Result::Ok(())
+    }
 
-        loop {
-            let input = self.window.get_input();
+    pub fn run(&mut self, frontend: &mut dyn Frontend) {
+        self.draw(frontend);
 
-            let next_game_state = match self.game_state {
-                Action::Game => {
-                    self.handle_game_state(input)
-                },
-                Action::GameOver => {
-                    self.handle_game_over_state(input)
-                },
-                Action::Loot => {
-                    self.handle_loot_state(input)
-                },
-                Action::Inventory => {
-                    self.handle_inventory_state(input)
-                },
-                Action::Menu => {
-                    self.handle_menu_state(input)
-                },
-                Action::Quit => {
-                    break;
-                },
+        loop {
+            let input = if self.game_state == Action::NewGame {
+                frontend.get_text_input()
+            } else {
+                frontend.get_input()
             };
 
-            if self.game_state == Action::Game && next_game_state == Action::Loot {
-                self.backpack_index = 0;
+            if input == Input::Resize {
+                frontend.resize();
+                self.draw(frontend);
+                continue;
             }
 
-            if next_game_state == Action::Quit {
+            for event in self.step(input) {
+                frontend.notify(&event);
+            }
+
+            if self.game_state == Action::Quit {
                 break;
-            } else {
-                self.game_state = next_game_state;
             }
 
-            let storm_cooldown = self.effects.iter().position(|x| x.actor_id() == self.player.id && x.effect_id() == 2).is_some();
-            let kick_cooldown = self.effects.iter().position(|x| x.actor_id() == self.player.id && x.effect_id() == 3).is_some();
+            self.draw(frontend);
+        }
+    }
+
+    /// Handles one input in the current screen, returns all events which happened meanwhile.
+    pub fn step(&mut self, input: Input) -> Vec<Event> {
+        let next_game_state = match self.game_state {
+            Action::Title => {
+                self.handle_title_state(input)
+            },
+            Action::NewGame => {
+                self.handle_new_game_state(input)
+            },
+            Action::Options => {
+                self.handle_options_state(input)
+            },
+            Action::HighScores => {
+                self.handle_high_scores_state(input)
+            },
+            Action::Game => {
+                self.handle_game_state(input)
+            },
+            Action::GameOver => {
+                self.handle_game_over_state(input)
+            },
+            Action::Loot => {
+                self.handle_loot_state(input)
+            },
+            Action::Inventory => {
+                self.handle_inventory_state(input)
+            },
+            Action::Menu => {
+                self.handle_menu_state(input)
+            },
+            Action::History => {
+                self.handle_history_state(input)
+            },
+            Action::Look => {
+                self.handle_look_state(input)
+            },
+            Action::Target => {
+                self.handle_target_state(input)
+            },
+            Action::Spellbook => {
+                self.handle_spellbook_state(input)
+            },
+            Action::Dialogue => {
+                self.handle_dialogue_state(input)
+            },
+            Action::Quests => {
+                self.handle_quests_state(input)
+            },
+            Action::Quit => {
+                Action::Quit
+            },
+        };
+
+        let events = self.dispatch_events();
+
+        if self.game_state == Action::Game && next_game_state == Action::Loot {
+            self.backpack_index = 0;
+        }
+
+        self.game_state = next_game_state;
+
+        events
+    }
+
+    pub fn state(&self) -> Action {
+        self.game_state
+    }
+
+    pub fn map(&self) -> &Level {
+        &self.map
+    }
+
+    pub fn player(&self) -> &Entity {
+        &self.player
+    }
+
+    pub fn player_class(&self) -> Class {
+        self.player_class
+    }
+
+    /// Specials bound to the slots 1, 2 and 3.
+    pub fn specials(&self) -> [Special; 3] {
+        self.specials
+    }
+
+    pub fn enemies(&self) -> &[Entity] {
+        &self.enemies
+    }
+
+    /// Running effects of everyone, e.g. specials on cooldown.
+    pub fn effects(&self) -> &[Box<dyn Effect>] {
+        &self.effects
+    }
+
+    /// Turns until the player can use the special again, 0 if it is ready.
+    pub fn cool_down(&self, special: Special) -> u32 {
+        self.effects.iter()
+            .filter(|effect| effect.actor_id() == self.player.id && effect.effect_id() == special.effect_id())
+            .map(|effect| effect.cool_down().max(1))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn log(&self) -> &Log {
+        &self.log
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn draw(&self, frontend: &mut dyn Frontend) {
+        if !frontend.fits() {
+            frontend.draw_too_small();
+            return;
+        }
+
+        //Screens before the game hide the dungeon.
+        match self.game_state {
+            Action::Title => {
+                let can_continue = Path::new(SAVE_FILE).exists();
+                let entries = [("New Game", true), ("Continue", can_continue), ("Options", true), ("High Scores", true), ("Quit", true)];
+
+                frontend.draw_title(&entries, self.title_index, &self.title_message);
+                return;
+            },
+            Action::NewGame => {
+                frontend.draw_new_game(&self.name_input, &self.player_class, &self.seed_input, self.new_game_field as usize, &self.title_message);
+                return;
+            },
+            Action::Options => {
+                frontend.draw_options(&level_size_name(self.level_size));
+                return;
+            },
+            Action::HighScores => {
+                frontend.draw_high_scores(&self.high_scores.scores);
+                return;
+            },
+            _ => {},
+        }
+
+        let specials: Vec<(&str, u32, i32)> = self.specials.iter()
+            .map(|special| (special.name(), self.cool_down(*special), special.cost()))
+            .collect();
+
+        frontend.draw(&self.log, &self.map, &self.player, &self.enemies, &specials);
+
+        if self.game_state == Action::Loot {
+            let enemy = &self.enemies[self.enemy_loot_index];
+
+            frontend.draw_loot(&enemy.backpack, self.backpack_index, true, &enemy.name)
+        } else if self.game_state == Action::Inventory {
+            frontend.draw_loot(&self.player.backpack, self.backpack_index, self.inventory_pointer == InventoryPointer::Backpack, "");
+            frontend.draw_entity(&self.player, self.character_pointer, self.inventory_pointer == InventoryPointer::Character);
+        } else if self.game_state == Action::Menu {
+            frontend.draw_menu();
+        } else if self.game_state == Action::GameOver {
+            frontend.draw_game_over(&self.game_over_summary);
+        } else if self.game_state == Action::History {
+            frontend.draw_history(&self.log, self.history_offset);
+        } else if self.game_state == Action::Look {
+            let (row, col) = self.look_cursor;
+            frontend.draw_look(row, col, &self.describe(row, col));
+        } else if self.game_state == Action::Spellbook {
+            let abilities: Vec<(&str, i32)> = self.abilities().iter().map(|ability| (ability.name(), ability.cost())).collect();
+            let slots: Vec<&str> = self.specials.iter().map(|special| special.name()).collect();
+
+            frontend.draw_spellbook(&abilities, self.spellbook_index, &slots);
+        } else if self.game_state == Action::Quests {
+            let quests: Vec<(String, bool)> = self.quests.quests.iter().map(|quest| {
+                (format!("{} ({}/{}), from {}, reward {}", quest.describe(), quest.progress, quest.goal(), quest.giver, quest.reward.name()), quest.done)
+            }).collect();
+
+            frontend.draw_quests(&quests);
+        } else if let (Action::Dialogue, Option::Some((partner, dialogue))) = (self.game_state, self.talk()) {
+            let node = &dialogue.nodes[self.dialogue_node];
+            let choices: Vec<&str> = node.choices.iter().map(|choice| choice.text.as_str()).collect();
+
+            frontend.draw_dialogue(&partner.name, &node.text, &choices, self.dialogue_index);
+        } else if let (Action::Target, Option::Some(special)) = (self.game_state, self.pending_special) {
+            let area = special.create(self.player.id, self.target_direction).area(&self.player, &self.map, &self.occupied(self.player.id));
+            let targets: Vec<String> = self.targets(special, self.target_direction).iter().map(|enemy| enemy.name.clone()).collect();
+
+            frontend.draw_target(special.name(), self.target_direction.name(), &area, &targets);
+        }
+    }
+
+    /// Delivers all queued events to the subscribers, returns them for the frontend.
+    fn dispatch_events(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        //Rewards of completed quests are new events.
+        while !self.events.is_empty() {
+            let batch = self.events.drain();
+
+            for event in &batch {
+                self.log.notify(event);
+                self.statistics.notify(event);
+                self.achievements.notify(event);
+                self.quests.notify(event);
+            }
+
+            for name in self.achievements.announce.drain(..) {
+                self.log.add_message(format!("Achievement unlocked: {}", name));
+            }
+
+            self.reward_quests();
+            events.extend(batch);
+        }
 
-            self.window.draw(&mut self.log, &self.map, &self.player, &self.enemies, storm_cooldown, kick_cooldown);
+        events
+    }
 
-            if self.game_state == Action::Loot {
-                let enemy = &self.enemies[self.enemy_loot_index];
+    fn reward_quests(&mut self) {
+        let completed: Vec<usize> = self.quests.completed.drain(..).collect();
 
-                self.window.draw_loot(&enemy.backpack, self.backpack_index, true, &enemy.name)
-            } else if self.game_state == Action::Inventory {
-                self.window.draw_loot(&self.player.backpack, self.backpack_index, self.inventory_pointer == InventoryPointer::Backpack, &"".to_string());
-                self.window.draw_entity(&self.player, self.character_pointer, self.inventory_pointer == InventoryPointer::Character);
-            } else if self.game_state == Action::Menu {
-                self.window.draw_menu();
-            } else if self.game_state == Action::GameOver {
-                self.window.draw_game_over();
+        for index in completed {
+            let quest = self.quests.quests[index].clone();
+            self.log.add_message(format!("Quest completed: {}. {} receives {}.", quest.describe(), self.player.name, quest.reward.name()));
+
+            match quest.reward {
+                Reward::Potions => {
+                    self.receive(generate_potion(&Difficulty::Hard));
+                    self.receive(generate_potion(&Difficulty::Hard));
+                },
+                Reward::Tome => self.receive(generate_scroll(&Difficulty::Hard)),
+                Reward::Gear => {
+                    let item = generate_gear(&self.player, &Difficulty::Hard);
+                    self.receive(item);
+                },
             }
         }
     }
 
-    pub fn cleanup(&mut self) {
-        Window::clear();
+    /// Starts a quest of the kind, its places lie below every floor seen so far.
+    fn start_quest(&mut self, kind: QuestKind, giver: &str) -> bool {
+        if self.quests.open() >= MAX_QUESTS {
+            self.log.add_message(format!("{} has too many open quests.", self.player.name));
+            return false;
+        }
+
+        let depth = cmp::max(self.statistics.deepest_level, self.map.level) + rng::gen_range(1, 3);
+        let quest = Quest::new(kind, giver, depth);
+        self.log.add_message(format!("New quest from {}: {}.", giver, quest.describe()));
+        self.quests.quests.push(quest);
+
+        true
+    }
+
+    /// Hides the heirlooms and builds the vaults with their guardians on a newly generated floor.
+    fn prepare_quests(&mut self, depth: i32) {
+        let mut vaults = Vec::new();
+        let mut heirlooms = Vec::new();
+
+        for (index, quest) in self.quests.quests.iter().enumerate().filter(|(_, quest)| !quest.done) {
+            match quest.objective {
+                Objective::Vault { depth: vault_depth, .. } if vault_depth == depth => vaults.push(index),
+                Objective::Recover { item_type, depth: item_depth } if item_depth == depth => heirlooms.push(item_type),
+                _ => {},
+            }
+        }
+
+        for index in vaults {
+            let guardians = self.add_vault();
+
+            //Without room for the vault it is built on the next floor.
+            if guardians.is_empty() {
+                if let Objective::Vault { depth: ref mut vault_depth, .. } = self.quests.quests[index].objective {
+                    *vault_depth = depth + 1;
+                }
+                let quest = &self.quests.quests[index];
+                self.log.add_message(format!("The vault of {} lies deeper: {}.", quest.giver, quest.describe()));
+            } else if let Objective::Vault { guardians: ref mut vault_guardians, .. } = self.quests.quests[index].objective {
+                *vault_guardians = guardians;
+            }
+        }
+
+        for item_type in heirlooms {
+            let equipped = match item_type {
+                Type::Head => &self.player.head_item,
+                Type::Chest => &self.player.chest_item,
+                Type::Legs => &self.player.leg_item,
+                _ => &self.player.weapon,
+            };
+            let mut heirloom = generate_item(item_type, equipped, &Difficulty::Hard);
+            heirloom.name = heirloom_name(item_type).to_string();
+
+            self.hide(heirloom);
+        }
+    }
+
+    /// Carves a vault into the level and spawns its guardians, the strongest one in the middle.
+    fn add_vault(&mut self) -> Vec<u32> {
+        let places = self.level_generator.add_vault(&mut self.map);
+        let mut guardians = Vec::new();
+
+        for (index, &(row, col)) in places.iter().enumerate() {
+            let mut guardian = Entity::new(self.entity_count);
+            self.entity_count += 1;
+
+            let difficulty = if index == places.len() / 2 { 3 } else { 2 };
+            create_monster(&self.player, &mut guardian, rng::gen_range(1, 4), difficulty);
+            guardian.name = format!("{} Guardian", guardian.name);
+            guardian.pos_row = row as i32;
+            guardian.pos_col = col as i32;
+
+            guardians.push(guardian.id);
+            self.enemies.push(guardian);
+        }
+
+        guardians
+    }
+
+    /// Gives the item to the strongest monster with room, else it lies on a corpse at the stairs.
+    fn hide(&mut self, item: Item) {
+        let mut carriers: Vec<usize> = (0..self.enemies.len()).filter(|index| self.enemies[*index].faction == Faction::Hostile).collect();
+        carriers.sort_by_key(|index| cmp::Reverse(self.enemies[*index].calculate_max_life()));
+
+        for index in carriers {
+            if self.enemies[index].backpack.add_item(item.clone()).is_ok() {
+                return;
+            }
+        }
+
+        let mut corpse = Entity::new(self.entity_count);
+        self.entity_count += 1;
+        corpse.name = "Fallen Adventurer".to_string();
+        corpse.current_life = 0;
+
+        //Next to the stairs, on them it would be looted instead of taking them.
+        let stairs = self.map.find_meta(Tile::Next).or_else(|| self.map.find_meta(Tile::PlSpawn));
+        if let Option::Some((row, col)) = stairs.and_then(|(row, col)| self.free_tile_near(row, col)) {
+            corpse.pos_row = row as i32;
+            corpse.pos_col = col as i32;
+        }
+
+        //A fresh backpack always has room.
+        let _ = corpse.backpack.add_item(item);
+        self.enemies.push(corpse);
     }
 
     fn set_player_and_monsters(&mut self) {
+        self.set_player_on(Tile::PlSpawn);
+
         let mut row_index = 0;
         for meta_row in &self.map.meta {
             let mut col_index = 0;
             for meta_col in meta_row {
                 match meta_col {
-                    &Tile::PlSpawn => {
-                        self.player.pos_row = row_index;
-                        self.player.pos_col = col_index;
-                    },
                     &Tile::MnSpawn { mn_type, difficulty } => {
                         let mut monster = Entity::new(self.entity_count);
                         self.entity_count += 1;
@@ -161,6 +655,17 @@ impl Game {
 
                         self.enemies.push(monster);
                     },
+                    &Tile::NpcSpawn { npc } => {
+                        let mut friend = Entity::new(self.entity_count);
+                        self.entity_count += 1;
+
+                        create_npc(&self.player, &mut friend, &self.dialogues.pick(npc).name);
+
+                        friend.pos_row = row_index;
+                        friend.pos_col = col_index;
+
+                        self.enemies.push(friend);
+                    },
                     _ => (),
                 }
 
@@ -171,19 +676,136 @@ impl Game {
         }
     }
 
-    fn handle_game_over_state(&mut self, input: Input) -> Action {
-        match input {
-            Input::Use => {
-                let player_name = self.player.name.clone();
+    fn set_player_on(&mut self, tile: Tile) {
+        if let Option::Some((row, col)) = self.map.find_meta(tile) {
+            self.player.pos_row = row as i32;
+            self.player.pos_col = col as i32;
+        }
+    }
 
-                self.player = Entity::new(0);
-                self.player.name = player_name;
+    /// Puts the companion on the free tile nearest to the player.
+    fn place_companion(&mut self, mut companion: Entity) {
+        //Without any free tile it shares the tile with the player.
+        let nearest = self.free_tile_near(self.player.pos_row as usize, self.player.pos_col as usize);
+        let (row, col) = nearest.map_or((self.player.pos_row, self.player.pos_col), |(row, col)| (row as i32, col as i32));
+        companion.pos_row = row;
+        companion.pos_col = col;
 
-                self.enemies.clear();
-                self.map = self.level_generator.generate_level(0);
+        self.enemies.push(companion);
+    }
+
+    /// Nearest reachable floor around the position without stairs or anybody on it, the position itself excluded.
+    fn free_tile_near(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let distances = self.map.distance_map(row, col);
+        let mut nearest: Option<(u32, usize, usize)> = Option::None;
+
+        for (row, distance_row) in distances.iter().enumerate() {
+            for (col, distance) in distance_row.iter().enumerate() {
+                let stairs = matches!(self.map.meta[row][col], Tile::PlSpawn | Tile::Next);
+                let occupied = self.enemies.iter().any(|enemy| enemy.pos_row == row as i32 && enemy.pos_col == col as i32);
+
+                if let Option::Some(distance) = *distance {
+                    if distance > 0 && !stairs && !occupied && nearest.is_none_or(|(best, _, _)| distance < best) {
+                        nearest = Option::Some((distance, row, col));
+                    }
+                }
+            }
+        }
+
+        nearest.map(|(_, row, col)| (row, col))
+    }
+
+    /// Keeps the current floor and enters the floor of given depth,
+    /// which is generated on the first visit.
+    fn change_floor(&mut self, depth: i32) {
+        let going_down = depth > self.map.level;
+
+        //Companions who follow come along, the others wait on their floor.
+        let level = mem::replace(&mut self.map, Level::new());
+        let (companions, enemies): (Vec<Entity>, Vec<Entity>) = mem::take(&mut self.enemies).into_iter()
+            .partition(|entity| entity.is_companion() && entity.order == Order::Follow);
+        self.dungeon.store(Floor { level, enemies });
+
+        //Effects of enemies stay behind.
+        let player_id = self.player.id;
+        self.effects.retain(|effect| effect.actor_id() == player_id || companions.iter().any(|companion| companion.id == effect.actor_id()));
+
+        match self.dungeon.take(depth) {
+            Option::Some(floor) => {
+                self.map = floor.level;
+                self.enemies = floor.enemies;
+            },
+            Option::None => {
+                self.map = self.level_generator.generate_level(depth);
                 self.set_player_and_monsters();
+                self.prepare_quests(depth);
+            },
+        }
 
-                Action::Game
+        if going_down {
+            self.set_player_on(Tile::PlSpawn);
+        } else {
+            self.set_player_on(Tile::Next);
+        }
+
+        for companion in companions {
+            self.place_companion(companion);
+        }
+
+        self.map.explore(self.player.pos_row, self.player.pos_col);
+
+        self.events.push(Event::LevelEntered { actor: Actor::of(&self.player), depth });
+    }
+
+    /// Adds the high score and writes the morgue, the outcome is shown on the game over screen.
+    fn record_death(&mut self) {
+        let killer = self.statistics.killed_by.clone().unwrap_or_else(|| "something".to_string());
+        self.game_over_summary = vec![format!("Killed by {} on depth {}", killer, self.map.level)];
+
+        let score = HighScore {
+            name: self.player.name.clone(),
+            class: self.player_class.name().to_string(),
+            depth: cmp::max(self.statistics.deepest_level, self.map.level),
+            kills: self.statistics.kills,
+            turns: self.log.turn(),
+        };
+
+        match self.high_scores.add(score) {
+            Option::Some(rank) => {
+                self.game_over_summary.push(format!("New high score, rank {}", rank));
+
+                if !self.high_scores_writable {
+                    self.game_over_summary.push(format!("High scores are not saved, {} could not be read", HIGH_SCORE_FILE));
+                } else if let Result::Err(error) = self.high_scores.save(HIGH_SCORE_FILE) {
+                    self.game_over_summary.push(format!("Could not save high scores: {}", error));
+                }
+            },
+            Option::None => self.game_over_summary.push("No new high score".to_string()),
+        }
+
+        let morgue = Morgue {
+            player: &self.player,
+            class: self.player_class.name(),
+            statistics: &self.statistics,
+            log: &self.log,
+            level: &self.map,
+            enemies: &self.enemies,
+        };
+
+        let file_name = morgue.file_name();
+        match morgue.write(&file_name) {
+            Result::Ok(()) => self.game_over_summary.push(format!("Morgue written to {}", file_name)),
+            Result::Err(error) => self.game_over_summary.push(format!("Could not write morgue: {}", error)),
+        }
+    }
+
+    fn handle_game_over_state(&mut self, input: Input) -> Action {
+        match input {
+            Input::Use => {
+                self.title_index = TITLE_NEW_GAME;
+                self.title_message.clear();
+
+                Action::Title
             }
             Input::Quit => {
                 Action::Quit
@@ -192,10 +814,20 @@ impl Game {
         }
     }
 
-    fn handle_menu_state(&self, input: Input) -> Action {
+    fn handle_menu_state(&mut self, input: Input) -> Action {
         match input {
             Input::Use => {
-                Action::Quit
+                let progress = Progress { class: self.player_class, turn: self.log.turn(), statistics: self.statistics.clone(), specials: self.specials, spells: self.spells.clone(), quests: self.quests.quests.clone() };
+
+                if let Result::Err(error) = write_save(SAVE_FILE, &self.player, &progress, self.entity_count, &self.map, &self.enemies, &self.dungeon) {
+                    self.log.add_message(format!("Could not save: {}", error));
+                    return Action::Menu;
+                }
+
+                self.title_index = TITLE_CONTINUE;
+                self.title_message.clear();
+
+                Action::Title
             },
             Input::Quit => {
                 Action::Game
@@ -204,55 +836,536 @@ impl Game {
         }
     }
 
-    fn handle_inventory_state(&mut self, input: Input) -> Action {
-        match self.inventory_pointer {
-            InventoryPointer::Backpack => {
-                match input {
-                    Input::MoveUp => {
-                        if self.backpack_index > 0 {
-                            self.backpack_index -= 1;
+    fn handle_title_state(&mut self, input: Input) -> Action {
+        match input {
+            Input::MoveUp if self.title_index > 0 => {
+                self.title_index -= 1;
+            },
+            Input::MoveDown if self.title_index < TITLE_QUIT => {
+                self.title_index += 1;
+            },
+            Input::Use => {
+                self.title_message.clear();
+
+                match self.title_index {
+                    TITLE_NEW_GAME => {
+                        self.new_game_field = NewGameField::Name;
+
+                        return Action::NewGame;
+                    },
+                    TITLE_CONTINUE => {
+                        match self.continue_game() {
+                            Result::Ok(()) => return Action::Game,
+                            Result::Err(error) => self.title_message = format!("Could not continue: {}", error),
                         }
                     },
-                    Input::MoveDown => {
-                        if !self.player.backpack.empty_slot(self.backpack_index + 1) {
-                            self.backpack_index += 1;
+                    TITLE_OPTIONS => return Action::Options,
+                    TITLE_HIGH_SCORES => return Action::HighScores,
+                    _ => return Action::Quit,
+                }
+            },
+            Input::Quit => {
+                return Action::Quit;
+            },
+            _ => {},
+        }
+
+        Action::Title
+    }
+
+    fn handle_new_game_state(&mut self, input: Input) -> Action {
+        match input {
+            Input::Char(character) => {
+                match self.new_game_field {
+                    NewGameField::Name => {
+                        if self.name_input.len() < MAX_NAME_LENGTH && (character.is_ascii_alphanumeric() || character == ' ' || character == '-') {
+                            self.name_input.push(character);
                         }
                     },
-                    Input::Use => {
-                        let new_item: Item = self.player.backpack.items[self.backpack_index].clone();
+                    NewGameField::Seed => {
+                        if self.seed_input.len() < MAX_SEED_LENGTH && character.is_ascii_digit() {
+                            self.seed_input.push(character);
+                        }
+                    },
+                    NewGameField::Class => {},
+                }
+            },
+            Input::Backspace => {
+                match self.new_game_field {
+                    NewGameField::Name => self.name_input.pop(),
+                    NewGameField::Seed => self.seed_input.pop(),
+                    NewGameField::Class => Option::None,
+                };
+            },
+            Input::MoveLeft | Input::MoveRight if self.new_game_field == NewGameField::Class => {
+                let index = CLASSES.iter().position(|class| *class == self.player_class).unwrap();
 
-                        if new_item.item_type == Type::Potion {
-                            let max_life = self.player.calculate_max_life();
-                            let heal_percentage = new_item.get_heal_percentage() as f32;
-                            let actual_heal = ((max_life as f32) * (heal_percentage / 100.0f32)).round() as i32;
+                self.player_class = if input == Input::MoveRight {
+                    CLASSES[(index + 1) % CLASSES.len()]
+                } else {
+                    CLASSES[(index + CLASSES.len() - 1) % CLASSES.len()]
+                };
+            },
+            Input::MoveUp => {
+                self.new_game_field = match self.new_game_field {
+                    NewGameField::Seed => NewGameField::Class,
+                    _ => NewGameField::Name,
+                };
+            },
+            Input::MoveDown => {
+                self.new_game_field = match self.new_game_field {
+                    NewGameField::Name => NewGameField::Class,
+                    _ => NewGameField::Seed,
+                };
+            },
+            Input::Use => {
+                match self.new_game_field {
+                    NewGameField::Name => {
+                        self.new_game_field = NewGameField::Class;
+                        return Action::NewGame;
+                    },
+                    NewGameField::Class => {
+                        self.new_game_field = NewGameField::Seed;
+                        return Action::NewGame;
+                    },
+                    NewGameField::Seed => {},
+                }
 
-                            self.player.current_life = self.player.current_life + actual_heal;
-                            if self.player.current_life > max_life {
-                                self.player.current_life = max_life;
-                            }
+                let name = self.name_input.trim().to_string();
+                if name.is_empty() {
+                    self.title_message = "Please enter a name.".to_string();
+                    self.new_game_field = NewGameField::Name;
+                    return Action::NewGame;
+                }
 
-                            self.player.backpack.remove_item(self.backpack_index);
-                            self.log.add_message(format!("Player {} have been healed.", self.player.name));
-                        } else {
-                            let name_clone = new_item.name.clone();
-                            self.player.backpack.remove_item(self.backpack_index);
-                            let old_item = self.player.equip(new_item);
+                //Without a seed every game is different.
+                let seed = match self.seed_input.parse::<usize>() {
+                    Result::Ok(seed) => seed,
+                    Result::Err(..) => rand::random::<u32>() as usize,
+                };
 
-                            if old_item.item_type != Type::Nothing {
-                                self.player.backpack.insert_item(self.backpack_index, old_item);
-                            }
+                self.title_message.clear();
+                self.new_game(name, self.player_class, seed);
+
+                return Action::Game;
+            },
+            Input::Quit => {
+                self.title_message.clear();
+                return Action::Title;
+            },
+            _ => {},
+        }
+
+        Action::NewGame
+    }
+
+    fn handle_options_state(&mut self, input: Input) -> Action {
+        match input {
+            Input::MoveLeft | Input::MoveRight => {
+                let current = LEVEL_SIZES.iter().position(|size| *size == self.level_size);
+
+                let next = match (current, input) {
+                    (Option::Some(index), Input::MoveRight) => (index + 1) % LEVEL_SIZES.len(),
+                    (Option::Some(index), _) => (index + LEVEL_SIZES.len() - 1) % LEVEL_SIZES.len(),
+                    //A custom size from the command line starts over.
+                    (Option::None, _) => 0,
+                };
+
+                let (size_rows, size_cols) = LEVEL_SIZES[next];
+                self.set_level_size(size_rows, size_cols);
+            },
+            Input::Quit | Input::Use => {
+                return Action::Title;
+            },
+            _ => {},
+        }
+
+        Action::Options
+    }
 
-                            self.log.add_message(format!("Player {} equipped {}", self.player.name, name_clone));
+    fn handle_high_scores_state(&mut self, input: Input) -> Action {
+        match input {
+            Input::Quit | Input::Use => Action::Title,
+            _ => Action::HighScores,
+        }
+    }
+
+    fn handle_history_state(&mut self, input: Input) -> Action {
+        match input {
+            Input::MoveUp if self.history_offset + 1 < self.log.len() => self.history_offset += 1,
+            Input::MoveDown if self.history_offset > 0 => self.history_offset -= 1,
+            Input::Quit | Input::History => {
+                return Action::Game;
+            },
+            _ => {},
+        }
+
+        Action::History
+    }
+
+    fn handle_spellbook_state(&mut self, input: Input) -> Action {
+        let abilities = self.abilities();
+
+        match input {
+            Input::MoveUp if self.spellbook_index > 0 => self.spellbook_index -= 1,
+            Input::MoveDown if self.spellbook_index + 1 < abilities.len() => self.spellbook_index += 1,
+
+            //Binding a special which is in another slot swaps both.
+            Input::SpecialOne | Input::SpecialTwo | Input::SpecialThree => {
+                let slot = match input {
+                    Input::SpecialOne => 0,
+                    Input::SpecialTwo => 1,
+                    _ => 2,
+                };
+                let ability = abilities[self.spellbook_index];
+
+                if let Option::Some(other) = self.specials.iter().position(|special| *special == ability) {
+                    self.specials[other] = self.specials[slot];
+                }
+                self.specials[slot] = ability;
+            },
+            Input::Quit | Input::Spellbook => return Action::Game,
+            _ => {},
+        }
+
+        Action::Spellbook
+    }
+
+    fn handle_quests_state(&mut self, input: Input) -> Action {
+        match input {
+            Input::Quit | Input::Quests => Action::Game,
+            _ => Action::Quests,
+        }
+    }
+
+    /// Everything which can be bound to a slot, the class specials first.
+    fn abilities(&self) -> Vec<Special> {
+        let mut abilities = self.player_class.specials().to_vec();
+        abilities.extend(self.spells.iter().cloned());
+
+        abilities
+    }
+
+    /// Learns the spells of a scroll, tomes also raise the intelligence for good.
+    /// Notes start a quest instead.
+    fn read_scroll(&mut self, index: usize) {
+        let scroll = self.player.backpack.items[index].clone();
+        let mut learned = false;
+
+        if scroll.modifications.contains(&StatsMod::Quest) {
+            let kind = [QuestKind::Kill, QuestKind::Recover, QuestKind::Vault][rng::gen_range(0, 3)];
+
+            if self.start_quest(kind, &scroll.name) {
+                self.player.backpack.remove_item(index);
+            }
+            return;
+        }
+
+        for modification in &scroll.modifications {
+            if let StatsMod::Teach(spell) = *modification {
+                if !self.spells.contains(&spell) {
+                    self.spells.push(spell);
+                    self.log.add_message(format!("{} learned {}, bind it in the spellbook.", self.player.name, spell.name()));
+                    learned = true;
+                }
+            }
+        }
+
+        if !learned {
+            self.log.add_message(format!("{} knows everything of the {} already.", self.player.name, scroll.name));
+            return;
+        }
+
+        for modification in &scroll.modifications {
+            if let StatsMod::Add(stat) = *modification {
+                add_stat(&mut self.player.base_stats, &stat);
+            }
+        }
+        self.player.backpack.remove_item(index);
+    }
+
+    fn handle_dialogue_state(&mut self, input: Input) -> Action {
+        let (count, choice) = match self.talk() {
+            Option::Some((_, dialogue)) => {
+                let choices = &dialogue.nodes[self.dialogue_node].choices;
+                (choices.len(), choices[cmp::min(self.dialogue_index, choices.len() - 1)].clone())
+            },
+            Option::None => return Action::Game,
+        };
+
+        match input {
+            Input::MoveUp if self.dialogue_index > 0 => self.dialogue_index -= 1,
+            Input::MoveDown if self.dialogue_index + 1 < count => self.dialogue_index += 1,
+            Input::Use => {
+                if !self.answer(&choice.consequences) {
+                    return Action::Dialogue;
+                }
+
+                //Partners who fight or leave end the dialogue.
+                let next = choice.next.as_ref().and_then(|next| self.talk().and_then(|(_, dialogue)| dialogue.node(next)));
+                match next {
+                    Option::Some(node) => {
+                        self.dialogue_node = node;
+                        self.dialogue_index = 0;
+                    },
+                    Option::None => return Action::Game,
+                }
+            },
+            Input::Quit => return Action::Game,
+            _ => {},
+        }
+
+        Action::Dialogue
+    }
+
+    /// Friendly entity the player talks to and its dialogue.
+    fn talk(&self) -> Option<(&Entity, &Dialogue)> {
+        let partner = self.enemies.iter().find(|enemy| enemy.id == self.talk_partner && enemy.is_friendly())?;
+        let dialogue = self.dialogues.of(&partner.name).filter(|dialogue| self.dialogue_node < dialogue.nodes.len())?;
+
+        Option::Some((partner, dialogue))
+    }
+
+    /// Applies the consequences of a choice, false if it can not be made.
+    fn answer(&mut self, consequences: &[Consequence]) -> bool {
+        let index = match self.enemies.iter().position(|enemy| enemy.id == self.talk_partner) {
+            Option::Some(index) => index,
+            Option::None => return false,
+        };
+
+        //Nobody gives a second quest before the first is done.
+        let gives_quest = consequences.iter().any(|consequence| matches!(*consequence, Consequence::Quest(..)));
+        if gives_quest && self.quests.has_open(&self.enemies[index].name) {
+            self.log.add_message(format!("{} waits for the open quest to be done.", self.enemies[index].name));
+            return false;
+        }
+
+        //Nothing happens without the potion to trade.
+        if consequences.contains(&Consequence::TakePotion) {
+            let size = self.player.backpack.size();
+            match self.player.backpack.items.iter().take(size).position(|item| item.item_type == Type::Potion) {
+                Option::Some(potion) => {
+                    let name = self.player.backpack.items[potion].name.clone();
+                    self.player.backpack.remove_item(potion);
+                    self.log.add_message(format!("Player {} gave {} to {}.", self.player.name, name, self.enemies[index].name));
+                },
+                Option::None => {
+                    self.log.add_message(format!("{} has no healing potion.", self.player.name));
+                    return false;
+                },
+            }
+        }
+
+        for consequence in consequences {
+            match *consequence {
+                Consequence::Heal(percentage) => heal(&mut self.events, &mut self.player, percentage),
+                Consequence::GivePotion => self.receive(generate_potion(&Difficulty::Normal)),
+                Consequence::GiveScroll => self.receive(generate_scroll(&Difficulty::Normal)),
+                Consequence::GiveItem => {
+                    let item = generate_gear(&self.player, &Difficulty::Normal);
+                    self.receive(item);
+                },
+                Consequence::RevealMap => {
+                    self.map.explore_all();
+                    self.log.add_message("The whole floor is revealed.".to_string());
+                },
+                Consequence::Fight => {
+                    self.enemies[index].faction = Faction::Hostile;
+                    self.log.add_message(format!("{} attacks!", self.enemies[index].name));
+                },
+                Consequence::Quest(kind) => {
+                    let giver = self.enemies[index].name.clone();
+                    self.start_quest(kind, &giver);
+                },
+                Consequence::TakePotion | Consequence::Leave => {},
+            }
+        }
+
+        if consequences.contains(&Consequence::Leave) {
+            self.log.add_message(format!("{} leaves.", self.enemies[index].name));
+            self.enemies.remove(index);
+        }
+
+        true
+    }
+
+    /// Puts a gift into the backpack, it is lost if the backpack is full.
+    fn receive(&mut self, item: Item) {
+        let name = item.name.clone();
+
+        match self.player.backpack.add_item(item) {
+            Result::Ok(()) => self.events.push(Event::ItemPickedUp { actor: Actor::of(&self.player), item: name }),
+            Result::Err(..) => self.log.add_message(format!("{} does not fit into the backpack.", name)),
+        }
+    }
+
+    fn is_next_to_player(&self, entity: &Entity) -> bool {
+        (entity.pos_row - self.player.pos_row).abs() + (entity.pos_col - self.player.pos_col).abs() == 1
+    }
+
+    fn handle_look_state(&mut self, input: Input) -> Action {
+        let (row, col) = self.look_cursor;
+
+        match input {
+            Input::MoveUp if row > 0 => self.look_cursor.0 -= 1,
+            Input::MoveDown if row + 1 < self.map.map.len() => self.look_cursor.0 += 1,
+            Input::MoveLeft if col > 0 => self.look_cursor.1 -= 1,
+            Input::MoveRight if col + 1 < self.map.map[row].len() => self.look_cursor.1 += 1,
+
+            //Travels to the cursor.
+            Input::Travel | Input::Use => {
+                self.auto_move(Goal::Spot(row, col));
+                return Action::Game;
+            },
+            Input::Quit | Input::Look => return Action::Game,
+            _ => {},
+        }
+
+        Action::Look
+    }
+
+    fn handle_target_state(&mut self, input: Input) -> Action {
+        let special = match self.pending_special {
+            Option::Some(special) => special,
+            Option::None => return Action::Game,
+        };
+
+        match input {
+            Input::MoveUp | Input::MoveDown | Input::MoveLeft | Input::MoveRight => {
+                self.target_direction = input_direction(input).unwrap();
+            },
+            Input::NextTarget => {
+                self.target_direction = self.next_target(special, Option::Some(self.target_direction));
+            },
+
+            //Attack keys aim and use the special at once.
+            Input::AttackUp | Input::AttackDown | Input::AttackLeft | Input::AttackRight | Input::Use => {
+                if let Option::Some(direction) = input_direction(input) {
+                    self.target_direction = direction;
+                }
+
+                self.pending_special = Option::None;
+                self.use_special(special, self.target_direction);
+                self.end_turn();
+
+                return Action::Game;
+            },
+            Input::Quit => {
+                self.pending_special = Option::None;
+                return Action::Game;
+            },
+            _ => {},
+        }
+
+        Action::Target
+    }
+
+    /// Living monsters the special would hit in given direction.
+    fn targets(&self, special: Special, direction: AttackDirection) -> Vec<&Entity> {
+        let area = special.create(self.player.id, direction).area(&self.player, &self.map, &self.occupied(self.player.id));
+
+        self.enemies.iter().filter(|enemy| self.player.is_enemy_of(enemy) && area.contains(&(enemy.pos_row, enemy.pos_col))).collect()
+    }
+
+    /// Direction after the current one which hits a monster, any direction if none does.
+    fn next_target(&self, special: Special, current: Option<AttackDirection>) -> AttackDirection {
+        let directions = special.directions();
+        let mut choices: Vec<AttackDirection> = directions.iter().cloned().filter(|direction| !self.targets(special, *direction).is_empty()).collect();
+        if choices.is_empty() {
+            choices = directions.to_vec();
+        }
+
+        match current.and_then(|current| choices.iter().position(|direction| *direction == current)) {
+            Option::Some(index) => choices[(index + 1) % choices.len()],
+            Option::None => choices[0],
+        }
+    }
+
+    /// What the player knows about given position, one line for everything there.
+    fn describe(&self, row: usize, col: usize) -> Vec<String> {
+        if !self.map.is_explored(row, col) {
+            return vec!["You have not seen this place yet.".to_string()];
+        }
+
+        let tile = match (self.map.meta[row][col], self.map.map[row][col]) {
+            (Tile::PlSpawn, _) => "Stairs up",
+            (Tile::Next, _) => "Stairs down",
+            (_, Tile::Floor) => "Floor",
+            (_, Tile::Wall) => "Wall",
+            _ => "Nothing",
+        };
+        let mut lines = vec![tile.to_string()];
+
+        let (row, col) = (row as i32, col as i32);
+        if (self.player.pos_row, self.player.pos_col) == (row, col) {
+            lines.push(format!("You, {}", self.player.condition()));
+        }
+
+        for enemy in self.enemies.iter().filter(|enemy| covers(enemy, row, col)) {
+            let loot = enemy.backpack.size();
+
+            lines.push(match (enemy.is_death(), loot) {
+                (false, _) if enemy.is_companion() => format!("{}, {}, {}", enemy.name, enemy.condition(), enemy.order.name()),
+                (false, _) if enemy.is_friendly() => format!("{}, friendly", enemy.name),
+                (false, _) => format!("{}, {}", enemy.name, enemy.condition()),
+                (true, 0) => format!("Corpse of {}", enemy.name),
+                (true, 1) => format!("Corpse of {} with 1 item", enemy.name),
+                (true, _) => format!("Corpse of {} with {} items", enemy.name, loot),
+            });
+
+            let gear: Vec<&str> = [&enemy.head_item, &enemy.chest_item, &enemy.leg_item, &enemy.weapon].iter()
+                .filter(|item| item.item_type != Type::Nothing)
+                .map(|item| item.name.as_str())
+                .collect();
+            if enemy.is_companion() && !gear.is_empty() {
+                lines.push(format!("Wears {}", gear.join(", ")));
+            }
+        }
+
+        for effect in &self.effects {
+            let actor = if effect.actor_id() == self.player.id {
+                Option::Some(&self.player)
+            } else {
+                self.enemies.iter().find(|enemy| enemy.id == effect.actor_id())
+            };
+
+            if let Option::Some(actor) = actor {
+                if effect.area(actor, &self.map, &self.occupied(actor.id)).contains(&(row, col)) {
+                    let name = Special::of_effect(effect.effect_id()).map_or("Effect", |special| special.name());
+                    lines.push(format!("{} of {}", name, actor.name));
+                }
+            }
+        }
+
+        lines
+    }
+
+    fn handle_inventory_state(&mut self, input: Input) -> Action {
+        match self.inventory_pointer {
+            InventoryPointer::Backpack => {
+                match input {
+                    Input::MoveUp => {
+                        if self.backpack_index > 0 {
+                            self.backpack_index -= 1;
                         }
                     },
-                    Input::Drop => {
-                        let new_item: Item = self.player.backpack.items[self.backpack_index].clone();
-
-                        if new_item.item_type != Type::Nothing {
-                            self.player.backpack.remove_item(self.backpack_index);
-                            self.log.add_message(format!("Player {} dropped {}.", self.player.name, new_item.name));
+                    Input::MoveDown => {
+                        if !self.player.backpack.empty_slot(self.backpack_index + 1) {
+                            self.backpack_index += 1;
                         }
-                    }
+                    },
+                    Input::Use => {
+                        self.use_item(self.backpack_index);
+                    },
+                    Input::Drop => {
+                        self.drop_item(self.backpack_index);
+                    },
+                    Input::Give => {
+                        self.give_item(self.backpack_index);
+                    },
+                    Input::UseItem(index) => self.use_item(index),
+                    Input::DropItem(index) => self.drop_item(index),
+                    Input::GiveItem(index) => self.give_item(index),
 
                     Input::MoveLeft => {
                         self.inventory_pointer = InventoryPointer::Character;
@@ -306,6 +1419,86 @@ impl Game {
         Action::Inventory
     }
 
+    /// Hands a backpack item to the nearest companion in sight, which equips or drinks it.
+    /// Reads, drinks or equips the backpack item at given index.
+    fn use_item(&mut self, index: usize) {
+        if index >= self.player.backpack.size() {
+            return;
+        }
+
+        let new_item: Item = self.player.backpack.items[index].clone();
+
+        if new_item.item_type == Type::Scroll {
+            self.read_scroll(index);
+        } else if new_item.item_type == Type::Potion {
+            drink(&mut self.events, &mut self.player, &new_item);
+            self.player.backpack.remove_item(index);
+        } else {
+            let name_clone = new_item.name.clone();
+            self.player.backpack.remove_item(index);
+            let old_item = self.player.equip(new_item);
+
+            if old_item.item_type != Type::Nothing {
+                self.player.backpack.insert_item(index, old_item);
+            }
+
+            self.log.add_message(format!("Player {} equipped {}", self.player.name, name_clone));
+        }
+    }
+
+    fn drop_item(&mut self, index: usize) {
+        if index >= self.player.backpack.size() {
+            return;
+        }
+
+        let item = self.player.backpack.items[index].clone();
+        self.player.backpack.remove_item(index);
+        self.log.add_message(format!("Player {} dropped {}.", self.player.name, item.name));
+    }
+
+    fn give_item(&mut self, index: usize) {
+        if index >= self.player.backpack.size() {
+            return;
+        }
+
+        let item = self.player.backpack.items[index].clone();
+
+        let (row, col) = (self.player.pos_row, self.player.pos_col);
+        let map = &self.map;
+        let companion = self.enemies.iter_mut()
+            .filter(|enemy| enemy.is_companion() && map.in_sight(row, col, enemy.pos_row, enemy.pos_col))
+            .min_by_key(|enemy| (enemy.pos_row - row).abs() + (enemy.pos_col - col).abs());
+
+        let companion = match companion {
+            Option::Some(companion) => companion,
+            Option::None => {
+                self.log.add_message("There is no companion in sight.".to_string());
+                return;
+            },
+        };
+
+        match item.item_type {
+            Type::Scroll => {
+                self.log.add_message(format!("{} can not read.", companion.name));
+                return;
+            },
+            Type::Potion => {
+                self.player.backpack.remove_item(index);
+                drink(&mut self.events, companion, &item);
+            },
+            _ => {
+                self.player.backpack.remove_item(index);
+                let old_item = companion.equip(item.clone());
+
+                if old_item.item_type != Type::Nothing {
+                    self.player.backpack.insert_item(index, old_item);
+                }
+            },
+        }
+
+        self.log.add_message(format!("Player {} gave {} to {}.", self.player.name, item.name, companion.name));
+    }
+
     fn handle_loot_state(&mut self, input: Input) -> Action {
         match input {
             Input::MoveUp => {
@@ -322,7 +1515,7 @@ impl Game {
             Input::Use => {
                 if self.player.backpack.has_space() {
                     let item = self.enemies[self.enemy_loot_index].backpack.items[self.backpack_index].clone();
-                    self.log.add_message(format!("Item {} added to Backpack", item.name));
+                    self.events.push(Event::ItemPickedUp { actor: Actor::of(&self.player), item: item.name.clone() });
 
                     self.enemies[self.enemy_loot_index].backpack.remove_item(self.backpack_index);
                     match self.player.backpack.add_item(item) {
@@ -344,6 +1537,12 @@ impl Game {
 
     fn handle_game_state(&mut self, input: Input) -> Action {
         if self.player.is_death() {
+            //Death is permanent.
+            if self.persistent {
+                fs::remove_file(SAVE_FILE).ok();
+                self.record_death();
+            }
+
             return Action::GameOver;
         }
 
@@ -352,9 +1551,38 @@ impl Game {
                 self.handle_move(input);
             },
 
-            Input::AttackUp | Input::AttackDown | Input::AttackLeft | Input::AttackRight |
+            Input::AttackUp | Input::AttackDown | Input::AttackLeft | Input::AttackRight => {
+                let direction = input_direction(input).unwrap();
+                self.apply_effect(Box::new(WeaponHit::new(self.player.id, direction)));
+            },
+
             Input::SpecialOne | Input::SpecialTwo | Input::SpecialThree => {
-                self.handle_attack(input);
+                let slot = match input {
+                    Input::SpecialOne => 0,
+                    Input::SpecialTwo => 1,
+                    _ => 2,
+                };
+                let special = self.specials[slot];
+
+                //Using a special which cools down is no turn.
+                if !special.create(self.player.id, AttackDirection::North).valid(&self.effects) {
+                    self.log.add_message(format!("{} is not ready yet.", special.name()));
+                    return Action::Game;
+                }
+                if self.player.current_energy < special.cost() {
+                    self.log.add_message(format!("Not enough energy for {}.", special.name()));
+                    return Action::Game;
+                }
+
+                //Directed specials are aimed first.
+                if special.needs_direction() {
+                    self.pending_special = Option::Some(special);
+                    self.target_direction = self.next_target(special, Option::None);
+                    return Action::Target;
+                }
+
+                //AttackDirection will not be used.
+                self.use_special(special, AttackDirection::North);
             },
 
             Input::Use => {
@@ -382,32 +1610,252 @@ impl Game {
                     }
                 } else {
                     if self.map.meta[self.player.pos_row as usize][self.player.pos_col as usize] == Tile::Next {
-                        self.enemies.clear();
-                        self.map = self.level_generator.generate_level(self.map.level + 1);
-                        self.set_player_and_monsters();
+                        let depth = self.map.level + 1;
+                        self.change_floor(depth);
 
                         return Action::Game;
                     } else if self.map.meta[self.player.pos_row as usize][self.player.pos_col as usize] == Tile::PlSpawn {
-                        self.log.add_message("There is no way up.".to_string());
+                        if self.map.level == 0 {
+                            self.log.add_message("There is no way up.".to_string());
+                        } else {
+                            let depth = self.map.level - 1;
+                            self.change_floor(depth);
+                        }
 
                         return Action::Game;
                     }
 
+                    //Talking is no turn.
+                    if let Option::Some(partner) = self.enemies.iter().find(|enemy| enemy.is_friendly() && self.is_next_to_player(enemy)) {
+                        match self.dialogues.of(&partner.name) {
+                            Option::Some(..) => {
+                                self.talk_partner = partner.id;
+                                self.dialogue_node = 0;
+                                self.dialogue_index = 0;
+
+                                return Action::Dialogue;
+                            },
+                            Option::None => {
+                                self.log.add_message(format!("{} has nothing to say.", partner.name));
+                                return Action::Game;
+                            },
+                        }
+                    }
+
                     return Action::Inventory;
                 };
             },
 
             Input::Quit => { return Action::Menu },
 
-            Input::Nothing | Input::Drop => {},
+            Input::History => {
+                self.history_offset = 0;
+                return Action::History;
+            },
+
+            Input::Look => {
+                self.look_cursor = (self.player.pos_row as usize, self.player.pos_col as usize);
+                return Action::Look;
+            },
+
+            Input::Spellbook => {
+                self.spellbook_index = 0;
+                return Action::Spellbook;
+            },
+
+            Input::Quests => {
+                return Action::Quests;
+            },
+
+            //Giving orders is no turn.
+            Input::Order => {
+                let order = if self.enemies.iter().any(|enemy| enemy.is_companion() && enemy.order == Order::Follow) { Order::Stay } else { Order::Follow };
+                let mut any = false;
+
+                for companion in self.enemies.iter_mut().filter(|enemy| enemy.is_companion()) {
+                    companion.order = order;
+                    self.log.add_message(format!("{} {}.", companion.name, order.name()));
+                    any = true;
+                }
+
+                if !any {
+                    self.log.add_message("There is no companion to order.".to_string());
+                }
+
+                return Action::Game;
+            },
+
+            //Resizing is handled by run before any state sees it.
+            Input::Char(..) | Input::Backspace | Input::Resize => {},
+
+            Input::Explore => {
+                self.auto_move(Goal::Explore);
+                return Action::Game;
+            },
+            Input::Travel => {
+                self.auto_move(Goal::Stairs);
+                return Action::Game;
+            },
+            Input::TravelTo(row, col) => {
+                self.auto_move(Goal::Spot(row, col));
+                return Action::Game;
+            },
+
+            //Handling the backpack is no turn, like in the inventory.
+            Input::UseItem(index) => {
+                self.use_item(index);
+                return Action::Game;
+            },
+            Input::DropItem(index) => {
+                self.drop_item(index);
+                return Action::Game;
+            },
+            Input::GiveItem(index) => {
+                self.give_item(index);
+                return Action::Game;
+            },
+
+            Input::Nothing | Input::Drop | Input::NextTarget | Input::Give => {},
         }
 
+        self.end_turn();
+
+        Action::Game
+    }
+
+    /// Everyone else acts after the player.
+    fn end_turn(&mut self) {
+        self.log.next_turn();
+        self.player.regenerate_energy();
+
         handle_ki(&self.map, &mut self.player, &mut self.enemies, &mut self.effects);
 
         self.handle_player_effects();
         self.handle_enemy_effects();
 
-        Action::Game
+        self.map.explore(self.player.pos_row, self.player.pos_col);
+    }
+
+    /// Walks turn by turn towards the goal, until a monster comes into view,
+    /// the player gets hurt or new loot is seen.
+    fn auto_move(&mut self, goal: Goal) {
+        if let Option::Some(name) = self.monster_in_sight() {
+            self.log.add_message(format!("Not with {} in view.", name));
+            return;
+        }
+
+        let mut seen_loot = self.loot_in_sight();
+
+        for _ in 0..MAX_AUTO_STEPS {
+            let (row, col) = match self.auto_target(goal) {
+                Result::Ok(target) => target,
+                Result::Err(message) => {
+                    self.log.add_message(message.to_string());
+                    return;
+                },
+            };
+
+            if (row as i32, col as i32) == (self.player.pos_row, self.player.pos_col) {
+                return;
+            }
+
+            let direction = match self.step_towards(row, col) {
+                Option::Some(direction) => direction,
+                Option::None => {
+                    self.log.add_message("The way is blocked.".to_string());
+                    return;
+                },
+            };
+
+            let life = self.player.current_life;
+            self.handle_move(direction);
+            self.end_turn();
+
+            if self.player.current_life < life {
+                self.log.add_message("You got hurt.".to_string());
+                return;
+            }
+            if let Option::Some(name) = self.monster_in_sight() {
+                self.log.add_message(format!("{} comes into view.", name));
+                return;
+            }
+
+            let loot = self.loot_in_sight();
+            if loot.iter().any(|id| !seen_loot.contains(id)) {
+                self.log.add_message("You found loot.".to_string());
+                return;
+            }
+            seen_loot = loot;
+        }
+    }
+
+    fn auto_target(&self, goal: Goal) -> Result<(usize, usize), &'static str> {
+        let distances = self.map.distance_map(self.player.pos_row as usize, self.player.pos_col as usize);
+
+        match goal {
+            Goal::Explore => {
+                let mut nearest: Option<(u32, usize, usize)> = Option::None;
+
+                for (row, distance_row) in distances.iter().enumerate() {
+                    for (col, distance) in distance_row.iter().enumerate() {
+                        if let Option::Some(distance) = *distance {
+                            if !self.map.is_explored(row, col) && nearest.is_none_or(|(best, _, _)| distance < best) {
+                                nearest = Option::Some((distance, row, col));
+                            }
+                        }
+                    }
+                }
+
+                nearest.map(|(_, row, col)| (row, col)).ok_or("Nothing left to explore.")
+            },
+            Goal::Stairs => {
+                match self.map.find_meta(Tile::Next) {
+                    Option::Some((row, col)) if self.map.is_explored(row, col) => Result::Ok((row, col)),
+                    _ => Result::Err("You have not found the stairs yet."),
+                }
+            },
+            Goal::Spot(row, col) => {
+                let reachable = row < distances.len() && col < distances[row].len() && distances[row][col].is_some();
+
+                if reachable && self.map.is_explored(row, col) {
+                    Result::Ok((row, col))
+                } else {
+                    Result::Err("You do not know a way there.")
+                }
+            },
+        }
+    }
+
+    /// First step on a shortest way to given position, around living monsters and friendly people, companions make way.
+    fn step_towards(&self, row: usize, col: usize) -> Option<Input> {
+        let distances = self.map.distance_map(row, col);
+        let distance = |row: i32, col: i32| distances.get(row as usize).and_then(|distance_row| distance_row.get(col as usize)).cloned().unwrap_or(Option::None);
+        let here = distance(self.player.pos_row, self.player.pos_col)?;
+
+        let steps = [(Input::MoveUp, -1, 0), (Input::MoveDown, 1, 0), (Input::MoveLeft, 0, -1), (Input::MoveRight, 0, 1)];
+
+        steps.iter().find(|&&(_, row_diff, col_diff)| {
+            let next_row = self.player.pos_row + row_diff;
+            let next_col = self.player.pos_col + col_diff;
+            let blocked = self.enemies.iter().any(|enemy| !enemy.is_death() && !enemy.is_companion() && enemy.pos_row == next_row && enemy.pos_col == next_col);
+
+            !blocked && distance(next_row, next_col).is_some_and(|next| next < here)
+        }).map(|&(input, _, _)| input)
+    }
+
+    /// Name of a living monster the player can see.
+    fn monster_in_sight(&self) -> Option<String> {
+        self.enemies.iter()
+            .find(|enemy| self.player.is_enemy_of(enemy) && self.map.in_sight(self.player.pos_row, self.player.pos_col, enemy.pos_row, enemy.pos_col))
+            .map(|enemy| enemy.name.clone())
+    }
+
+    /// Ids of the corpses with loot the player can see.
+    fn loot_in_sight(&self) -> Vec<u32> {
+        self.enemies.iter()
+            .filter(|enemy| enemy.is_death() && enemy.backpack.size() > 0 && self.map.in_sight(self.player.pos_row, self.player.pos_col, enemy.pos_row, enemy.pos_col))
+            .map(|enemy| enemy.id)
+            .collect()
     }
 
     fn handle_player_effects(&mut self) {
@@ -422,15 +1870,20 @@ impl Game {
             index += 1;
         }
 
+        let occupied = self.occupied(self.player.id);
+
+        //Companions and friendly people are spared.
         for effect_index in player_effects.iter() {
             for enemy_index in 0..self.enemies.len() {
-                self.effects[*effect_index].execute(&mut self.log, &mut self.map, &mut self.player, &mut self.enemies[enemy_index]);
+                if self.player.faction.fights(self.enemies[enemy_index].faction) {
+                    self.effects[*effect_index].execute(&mut self.events, &mut self.map, &mut self.player, &mut self.enemies[enemy_index], &occupied);
+                }
             }
         }
 
         player_effects.reverse();
         for effect_index in player_effects.iter() {
-            if self.effects[*effect_index].done(&mut self.player, &mut self.map) {
+            if self.effects[*effect_index].done(&mut self.events, &mut self.player, &mut self.map, &occupied) {
                 self.effects.remove(*effect_index);
             }
         }
@@ -448,72 +1901,55 @@ impl Game {
             index += 1;
         }
 
+        //Monsters hit the player and the companions, companions hit the monsters.
         for effect_index in enemy_effects.iter() {
             let enemy_index = self.enemies.iter().position(|enemy| enemy.id == self.effects[*effect_index].actor_id()).unwrap();
+            let occupied = self.occupied(self.enemies[enemy_index].id);
+
+            if self.enemies[enemy_index].faction.fights(self.player.faction) {
+                self.effects[*effect_index].execute(&mut self.events, &mut self.map, &mut self.enemies[enemy_index], &mut self.player, &occupied);
+            }
 
-            self.effects[*effect_index].execute(&mut self.log, &mut self.map, &mut self.enemies[enemy_index], &mut self.player);
+            for other_index in 0..self.enemies.len() {
+                if self.enemies[other_index].faction.fights(self.enemies[enemy_index].faction) {
+                    let (me, other) = pair_mut(&mut self.enemies, enemy_index, other_index);
+                    self.effects[*effect_index].execute(&mut self.events, &mut self.map, me, other, &occupied);
+                }
+            }
         }
 
         enemy_effects.reverse();
         for effect_index in enemy_effects.iter() {
             let enemy_index = self.enemies.iter().position(|enemy| enemy.id == self.effects[*effect_index].actor_id()).unwrap();
+            let occupied = self.occupied(self.enemies[enemy_index].id);
 
-            if self.effects[*effect_index].done(&mut self.enemies[enemy_index], &mut self.map) {
+            if self.effects[*effect_index].done(&mut self.events, &mut self.enemies[enemy_index], &mut self.map, &occupied) {
                 self.effects.remove(*effect_index);
             }
         }
     }
 
-    fn handle_attack(&mut self, direction: Input) {
-        //AttackDirection will not be used.
-        //effect is direction_less.
-        let effect: Box<Effect> = match direction {
-            Input::SpecialOne => Box::new(Storm::new(self.player.id, AttackDirection::North)),
-            Input::SpecialTwo => Box::new(RoundHouse::new(self.player.id)),
-            Input::SpecialThree => Box::new(WeaponHit::new(self.player.id, AttackDirection::North)),
-            _ => Box::new(WeaponHit::new(self.player.id, AttackDirection::North)),
-        };
+    /// Positions of the living entities besides the actor, moving effects stop in front of them.
+    fn occupied(&self, actor_id: u32) -> Vec<(i32, i32)> {
+        iter::once(&self.player).chain(self.enemies.iter())
+            .filter(|entity| entity.id != actor_id && !entity.is_death())
+            .map(|entity| (entity.pos_row, entity.pos_col))
+            .collect()
+    }
 
-        if effect.needs_direction() {
-            match direction {
-                Input::SpecialOne => self.player_special_one = true,
-                Input::SpecialTwo => self.player_special_two = true,
-                Input::SpecialThree => self.player_special_three = true,
-
-                _ => {
-                    let attack_direction = match direction {
-                        Input::AttackUp => AttackDirection::North,
-                        Input::AttackDown => AttackDirection::South,
-                        Input::AttackLeft => AttackDirection::West,
-                        Input::AttackRight => AttackDirection::East,
-
-                        _ => unreachable!(),
-                    };
-
-                    //Here are the correct AttackDirections.
-                    let hit: Box<Effect> = if self.player_special_one {
-                        Box::new(Storm::new(self.player.id, attack_direction))
-                    } else if self.player_special_two {
-                        Box::new(RoundHouse::new(self.player.id))
-                    } else if self.player_special_three {
-                        Box::new(WeaponHit::new(self.player.id, attack_direction))
-                    } else {
-                        Box::new(WeaponHit::new(self.player.id, attack_direction))
-                    };
-
-                    if hit.valid(&self.effects) {
-                        self.effects.push(hit);
-                    }
+    fn use_special(&mut self, special: Special, direction: AttackDirection) {
+        let effect = special.create(self.player.id, direction);
 
-                    self.player_special_one = false;
-                    self.player_special_two = false;
-                    self.player_special_three = false;
-                }
-            }
-        } else {
-            if effect.valid(&self.effects) {
-                self.effects.push(effect);
-            }
+        if effect.valid(&self.effects) {
+            self.player.current_energy -= special.cost();
+            self.apply_effect(effect);
+        }
+    }
+
+    fn apply_effect(&mut self, effect: Box<dyn Effect>) {
+        if effect.valid(&self.effects) {
+            self.events.push(Event::EffectApplied { actor: Actor::of(&self.player), effect_id: effect.effect_id() });
+            self.effects.push(effect);
         }
     }
 
@@ -535,10 +1971,15 @@ impl Game {
             return;
         }
 
-        //Collision with alive entity uncool.
-        for enemy in &self.enemies {
+        //Collision with alive entity uncool, companions swap places.
+        for enemy in &mut self.enemies {
             if !enemy.is_death() && row_diff == enemy.pos_row && col_diff == enemy.pos_col {
-                return;
+                if !enemy.is_companion() {
+                    return;
+                }
+
+                enemy.pos_row = self.player.pos_row;
+                enemy.pos_col = self.player.pos_col;
             }
         }
 
@@ -547,18 +1988,632 @@ impl Game {
     }
 }
 
+/// Heals the entity by the percentage of the potion.
+fn drink(events: &mut EventQueue, entity: &mut Entity, potion: &Item) {
+    heal(events, entity, potion.get_heal_percentage());
+}
+
+/// Heals the entity by given percentage of its max life.
+fn heal(events: &mut EventQueue, entity: &mut Entity, percentage: i32) {
+    let max_life = entity.calculate_max_life();
+    let heal_percentage = percentage as f32;
+    let actual_heal = ((max_life as f32) * (heal_percentage / 100.0f32)).round() as i32;
+    let old_life = entity.current_life;
+
+    entity.current_life = cmp::min(max_life, entity.current_life + actual_heal);
+
+    events.push(Event::Heal { target: Actor::of(entity), amount: entity.current_life - old_life });
+}
+
+/// Two different entities of the same list, both mutable.
+fn pair_mut(entities: &mut [Entity], first: usize, second: usize) -> (&mut Entity, &mut Entity) {
+    if first < second {
+        let (left, right) = entities.split_at_mut(second);
+        (&mut left[first], &mut right[0])
+    } else {
+        let (left, right) = entities.split_at_mut(first);
+        (&mut right[0], &mut left[second])
+    }
+}
+
+/// Straight direction of a move or attack key.
+fn input_direction(input: Input) -> Option<AttackDirection> {
+    match input {
+        Input::MoveUp | Input::AttackUp => Option::Some(AttackDirection::North),
+        Input::MoveDown | Input::AttackDown => Option::Some(AttackDirection::South),
+        Input::MoveLeft | Input::AttackLeft => Option::Some(AttackDirection::West),
+        Input::MoveRight | Input::AttackRight => Option::Some(AttackDirection::East),
+        _ => Option::None,
+    }
+}
+
+/// Checks if given position shows the entity, the living boss is bigger than one tile.
+fn covers(entity: &Entity, row: i32, col: i32) -> bool {
+    let shape: &[(i32, i32)] = if entity.monster_type == MonsterType::Boss && !entity.is_death() {
+        &[(0, 0), (-1, 0), (0, -1), (0, 1), (1, -1), (1, 1)]
+    } else {
+        &[(0, 0)]
+    };
+
+    shape.iter().any(|&(row_diff, col_diff)| (entity.pos_row + row_diff, entity.pos_col + col_diff) == (row, col))
+}
+
+/// Auto movement stops after this many turns.
+const MAX_AUTO_STEPS: u32 = 1000;
+
+/// Where auto movement goes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Goal {
+    /// The nearest tile which was never seen.
+    Explore,
+    /// The known stairs down.
+    Stairs,
+    Spot(usize, usize),
+}
+
+const TITLE_NEW_GAME: usize = 0;
+const TITLE_CONTINUE: usize = 1;
+const TITLE_OPTIONS: usize = 2;
+const TITLE_HIGH_SCORES: usize = 3;
+const TITLE_QUIT: usize = 4;
+
+const MAX_NAME_LENGTH: usize = 16;
+const MAX_SEED_LENGTH: usize = 9;
+
+/// Level sizes to choose from in the options.
+const LEVEL_SIZES: [(usize, usize); 3] = [(DEFAULT_ROWS, DEFAULT_COLS), (30, 100), (40, 120)];
+
+fn level_size_name(level_size: (usize, usize)) -> String {
+    let name = match LEVEL_SIZES.iter().position(|size| *size == level_size) {
+        Option::Some(0) => "Normal",
+        Option::Some(1) => "Large",
+        Option::Some(_) => "Huge",
+        Option::None => "Custom",
+    };
+
+    format!("{} {}x{}", name, level_size.0, level_size.1)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum NewGameField {
+    Name,
+    Class,
+    Seed,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum InventoryPointer {
     Backpack,
     Character
 }
 
+/// Screen the game is in, every input is handled by the current one.
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum Action {
+pub enum Action {
+    Title,
+    NewGame,
+    Options,
+    HighScores,
     Game,
     GameOver,
     Loot,
     Inventory,
     Menu,
+    History,
+    /// A cursor moves over the map and describes what is there.
+    Look,
+    /// A directed special is aimed before it is used.
+    Target,
+    /// Binds specials and learned spells to the slots.
+    Spellbook,
+    /// Talking to a friendly entity.
+    Dialogue,
+    /// Lists the open and completed quests.
+    Quests,
     Quit,
-}
\ No newline at end of file
+}
+
+/// New game of given class with a fixed seed.
+#[cfg(test)]
+fn test_game(class: Class) -> Game {
+    let mut game = Game::new();
+    game.new_game("Tama Nu".to_string(), class, 42);
+    game
+}
+
+#[test]
+fn test_step() {
+    let mut game = test_game(Class::Rogue);
+
+    assert_eq!(Action::Game, game.state());
+    assert_eq!(Class::Rogue, game.player_class());
+    assert!(!game.enemies().is_empty());
+
+    game.step(Input::Nothing);
+    assert_eq!(1, game.log().turn());
+
+    //Specials wait for a direction, the dash only starts with it.
+    game.step(Input::SpecialTwo);
+    assert_eq!(Action::Target, game.state());
+    assert!(game.effects().is_empty());
+    game.step(Input::AttackUp);
+    assert!(game.effects().iter().any(|effect| effect.effect_id() == Special::Dash.effect_id()));
+
+    game.step(Input::Quit);
+    assert_eq!(Action::Menu, game.state());
+    game.step(Input::Quit);
+    assert_eq!(Action::Game, game.state());
+
+    //Same seed, same dungeon.
+    let mut other = Game::new();
+    other.new_game("Nu".to_string(), Class::Rogue, 42);
+    assert_eq!(game.map().map, other.map().map);
+}
+
+#[test]
+fn test_auto_move() {
+    let mut game = test_game(Class::Warrior);
+
+    //Monsters in view stop auto movement right away.
+    game.enemies[0].pos_row = game.player.pos_row;
+    game.enemies[0].pos_col = game.player.pos_col;
+    let turn = game.log().turn();
+    game.step(Input::Explore);
+    assert_eq!(turn, game.log().turn());
+
+    game.enemies.clear();
+    game.step(Input::Travel);
+    assert_eq!(turn, game.log().turn());
+
+    for _ in 0..100 {
+        game.step(Input::Explore);
+    }
+
+    let distances = game.map.distance_map(game.player.pos_row as usize, game.player.pos_col as usize);
+    for (row, distance_row) in distances.iter().enumerate() {
+        for (col, distance) in distance_row.iter().enumerate() {
+            assert!(distance.is_none() || game.map.is_explored(row, col), "{} {} not explored", row, col);
+        }
+    }
+
+    game.step(Input::Travel);
+    assert_eq!(game.map.find_meta(Tile::Next), Option::Some((game.player.pos_row as usize, game.player.pos_col as usize)));
+}
+
+#[test]
+fn test_look() {
+    let mut game = test_game(Class::Warrior);
+
+    let (row, col) = (game.player.pos_row as usize, game.player.pos_col as usize);
+    game.enemies.truncate(1);
+    game.enemies[0].pos_row = game.player.pos_row;
+    game.enemies[0].pos_col = game.player.pos_col;
+    let name = game.enemies[0].name.clone();
+
+    game.step(Input::Look);
+    assert!(game.game_state == Action::Look);
+    assert_eq!((row, col), game.look_cursor);
+    assert_eq!(vec!["Stairs up".to_string(), "You, unhurt".to_string(), format!("{}, unhurt", name)], game.describe(row, col));
+
+    game.enemies[0].current_life = 0;
+    game.enemies[0].backpack = super::character::backpack::Backpack::new();
+    assert_eq!(format!("Corpse of {}", name), game.describe(row, col)[2]);
+
+    let (last_row, last_col) = (game.map.map.len() - 1, game.map.map[0].len() - 1);
+    assert_eq!(vec!["You have not seen this place yet.".to_string()], game.describe(last_row, last_col));
+
+    //The cursor stays on the map.
+    for _ in 0..last_row + 5 {
+        game.step(Input::MoveDown);
+    }
+    assert_eq!((last_row, col), game.look_cursor);
+
+    game.step(Input::Quit);
+    assert!(game.game_state == Action::Game);
+
+    //Travels to the cursor.
+    game.enemies.clear();
+    let (next_row, next_col) = neighbours(row, col).into_iter().find(|&(next_row, next_col)| game.map.is_walkable(next_row, next_col)).unwrap();
+    game.step(Input::Look);
+    game.look_cursor = (next_row, next_col);
+    game.step(Input::Travel);
+    assert!(game.game_state == Action::Game);
+    assert_eq!((next_row as i32, next_col as i32), (game.player.pos_row, game.player.pos_col));
+    assert_eq!("Floor", game.describe(next_row, next_col)[0]);
+}
+
+#[test]
+fn test_target() {
+    let mut game = test_game(Class::Warrior);
+
+    game.enemies.truncate(1);
+    game.enemies[0].pos_row = game.player.pos_row;
+    game.enemies[0].pos_col = game.player.pos_col + 1;
+    let turn = game.log().turn();
+
+    //Aims at the monster right away.
+    game.step(Input::SpecialOne);
+    assert_eq!(Action::Target, game.state());
+    assert_eq!(AttackDirection::East, game.target_direction);
+    assert_eq!(1, game.targets(Special::Storm, AttackDirection::East).len());
+    assert!(game.targets(Special::Storm, AttackDirection::West).is_empty());
+
+    game.step(Input::NextTarget);
+    assert_eq!(AttackDirection::East, game.target_direction);
+    game.step(Input::MoveUp);
+    assert_eq!(AttackDirection::North, game.target_direction);
+
+    game.step(Input::Quit);
+    assert_eq!(Action::Game, game.state());
+    assert!(game.effects().is_empty());
+    assert_eq!(turn, game.log().turn());
+
+    game.step(Input::SpecialOne);
+    game.step(Input::Use);
+    assert_eq!(Action::Game, game.state());
+    assert_eq!(turn + 1, game.log().turn());
+    assert!(game.effects().iter().any(|effect| effect.effect_id() == Special::Storm.effect_id()));
+
+    //Cooling down, nothing to aim.
+    game.step(Input::SpecialOne);
+    assert_eq!(Action::Game, game.state());
+    assert_eq!(turn + 1, game.log().turn());
+}
+
+#[test]
+fn test_dash_blocked() {
+    let mut game = test_game(Class::Rogue);
+
+    game.player.pos_row = 5;
+    game.player.pos_col = 5;
+    for col in 5..10 {
+        game.map.map[5][col] = Tile::Floor;
+    }
+    game.enemies.truncate(1);
+    game.enemies[0].pos_row = 5;
+    game.enemies[0].pos_col = 8;
+
+    //Stops in front of the monster.
+    let area = Special::Dash.create(game.player.id, AttackDirection::East).area(&game.player, &game.map, &game.occupied(game.player.id));
+    assert_eq!(vec![(5, 6), (5, 7)], area);
+
+    game.use_special(Special::Dash, AttackDirection::East);
+    game.handle_player_effects();
+    assert_eq!((5, 7), (game.player.pos_row, game.player.pos_col));
+}
+
+#[test]
+fn test_spells_blocked() {
+    let mut game = test_game(Class::Rogue);
+
+    game.player.pos_row = 5;
+    game.player.pos_col = 5;
+    for col in 5..10 {
+        game.map.map[5][col] = Tile::Floor;
+    }
+    game.map.map[5][10] = Tile::Wall;
+    game.enemies.truncate(2);
+    for (enemy, col) in game.enemies.iter_mut().zip(vec![7, 9]) {
+        enemy.pos_row = 5;
+        enemy.pos_col = col;
+    }
+    let occupied = game.occupied(game.player.id);
+
+    //The bolt stops at the first monster.
+    let area = Special::Firebolt.create(game.player.id, AttackDirection::East).area(&game.player, &game.map, &occupied);
+    assert_eq!(vec![(5, 6), (5, 7)], area);
+
+    //The blink jumps over the monster, but not onto the other.
+    let area = Special::Blink.create(game.player.id, AttackDirection::East).area(&game.player, &game.map, &occupied);
+    assert_eq!(vec![(5, 8)], area);
+}
+
+#[test]
+fn test_energy() {
+    let mut game = test_game(Class::Warrior);
+    game.enemies.clear();
+    assert_eq!(MAX_ENERGY, game.player.current_energy);
+
+    //Not enough energy is no turn.
+    game.player.current_energy = Special::RoundHouse.cost() - 1;
+    game.step(Input::SpecialTwo);
+    assert_eq!(0, game.log().turn());
+    assert_eq!(0, game.cool_down(Special::RoundHouse));
+
+    game.step(Input::Nothing);
+    assert_eq!(Special::RoundHouse.cost() - 1 + ENERGY_REGENERATION, game.player.current_energy);
+
+    game.step(Input::SpecialTwo);
+    assert_eq!(ENERGY_REGENERATION * 2 - 1, game.player.current_energy);
+    assert!(game.cool_down(Special::RoundHouse) > 0);
+
+    for _ in 0..50 {
+        game.step(Input::Nothing);
+    }
+    assert_eq!(MAX_ENERGY, game.player.current_energy);
+    assert_eq!(0, game.cool_down(Special::RoundHouse));
+}
+
+#[test]
+fn test_spellbook() {
+    let mut game = test_game(Class::Warrior);
+
+    game.map = Level::new();
+    game.player.pos_row = 2;
+    game.player.pos_col = 2;
+    game.enemies.truncate(1);
+    game.enemies[0].pos_row = 2;
+    game.enemies[0].pos_col = 5;
+    game.enemies[0].current_life = 1000;
+
+    let tome = Item { item_type: Type::Scroll, name: "Tome of Firebolt".to_string(), modifications: vec![StatsMod::Teach(Special::Firebolt), StatsMod::Add(super::character::stats::Stat::Intelligence(2))], rarity: Rarity::Rare };
+    game.player.backpack = super::character::backpack::Backpack::new();
+    game.player.backpack.add_item(tome.clone()).unwrap();
+    game.player.backpack.add_item(tome).unwrap();
+    let intelligence = game.player.calculate_stats().intelligence;
+
+    //The second tome teaches nothing new and is kept.
+    game.read_scroll(0);
+    game.read_scroll(0);
+    assert_eq!(vec![Special::Firebolt], game.spells);
+    assert_eq!(intelligence + 2, game.player.calculate_stats().intelligence);
+    assert_eq!(1, game.player.backpack.size());
+
+    game.step(Input::Spellbook);
+    assert_eq!(Action::Spellbook, game.state());
+    for _ in 0..10 {
+        game.step(Input::MoveDown);
+    }
+    assert_eq!(3, game.spellbook_index);
+    game.step(Input::SpecialOne);
+    assert_eq!([Special::Firebolt, Special::RoundHouse, Special::Mend], game.specials());
+
+    //Binding a bound special swaps the slots.
+    game.step(Input::MoveUp);
+    game.step(Input::SpecialOne);
+    assert_eq!([Special::Mend, Special::RoundHouse, Special::Firebolt], game.specials());
+    game.step(Input::Quit);
+    assert_eq!(Action::Game, game.state());
+    assert_eq!(0, game.log().turn());
+
+    //The bolt flies over the free tiles to the monster.
+    game.step(Input::SpecialThree);
+    assert_eq!(Action::Target, game.state());
+    assert_eq!(AttackDirection::East, game.target_direction);
+    game.step(Input::Use);
+    assert!(game.enemies[0].current_life < 1000);
+    assert!(game.cool_down(Special::Firebolt) > 0);
+}
+
+#[test]
+fn test_companion() {
+    let mut game = test_game(Class::Warrior);
+
+    let pet_index = game.enemies.iter().position(|enemy| enemy.is_companion()).unwrap();
+    let mut pet = game.enemies.remove(pet_index);
+    assert_eq!("Hound", pet.name);
+    assert_eq!(1, (pet.pos_row - game.player.pos_row).abs() + (pet.pos_col - game.player.pos_col).abs());
+
+    //Specials of the player spare the pet.
+    game.enemies.clear();
+    pet.pos_row = game.player.pos_row;
+    pet.pos_col = game.player.pos_col;
+    game.player.pos_col -= 1;
+    game.enemies.push(pet);
+    game.step(Input::SpecialTwo);
+    assert_eq!(game.enemies[0].calculate_max_life(), game.enemies[0].current_life);
+
+    //Walking into the pet swaps places.
+    let (row, col) = (game.player.pos_row, game.player.pos_col);
+    game.step(Input::MoveRight);
+    assert_eq!((row, col + 1), (game.player.pos_row, game.player.pos_col));
+    assert_eq!((row, col), (game.enemies[0].pos_row, game.enemies[0].pos_col));
+
+    let helm = Item { item_type: Type::Head, name: "Lesser Helm".to_string(), modifications: Vec::new(), rarity: Rarity::Common };
+    game.player.backpack = super::character::backpack::Backpack::new();
+    game.player.backpack.add_item(helm).unwrap();
+    game.give_item(0);
+    assert_eq!("Lesser Helm", game.enemies[0].head_item.name);
+    assert_eq!(0, game.player.backpack.size());
+    assert_eq!("Wears Lesser Helm", game.describe(row as usize, col as usize).last().unwrap());
+
+    //Ordering is no turn, a staying pet waits on its floor.
+    let turn = game.log().turn();
+    game.step(Input::Order);
+    assert_eq!(Order::Stay, game.enemies[0].order);
+    assert_eq!(turn, game.log().turn());
+
+    game.change_floor(1);
+    assert!(!game.enemies.iter().any(|enemy| enemy.is_companion()));
+    game.change_floor(0);
+    assert_eq!(1, game.enemies.iter().filter(|enemy| enemy.is_companion()).count());
+
+    //A following pet comes along.
+    game.step(Input::Order);
+    game.change_floor(1);
+    assert_eq!(1, game.enemies.iter().filter(|enemy| enemy.is_companion()).count());
+}
+
+#[test]
+fn test_dialogue() {
+    let mut game = test_game(Class::Warrior);
+    game.map = Level::new();
+    game.enemies.clear();
+    game.player.backpack = super::character::backpack::Backpack::new();
+    game.player.pos_row = 3;
+    game.player.pos_col = 3;
+
+    let mut merchant = Entity::new(10);
+    create_npc(&game.player, &mut merchant, "Merchant");
+    merchant.pos_row = 3;
+    merchant.pos_col = 4;
+    game.enemies.push(merchant);
+    game.map.explore(3, 3);
+    assert_eq!("Merchant, friendly", game.describe(3, 4)[1]);
+
+    //Talking is no turn, trading fails without a potion.
+    let turn = game.log().turn();
+    game.step(Input::Use);
+    assert!(game.game_state == Action::Dialogue);
+    game.step(Input::Use);
+    assert!(game.game_state == Action::Dialogue);
+    assert_eq!(0, game.dialogue_node);
+
+    game.player.backpack.add_item(generate_potion(&Difficulty::Easy)).unwrap();
+    game.step(Input::Use);
+    assert_eq!(Option::Some(game.dialogue_node), game.dialogues.of("Merchant").unwrap().node("deal"));
+    assert_eq!(0, game.player.backpack.size());
+
+    game.step(Input::Use);
+    assert_eq!(1, game.player.backpack.size());
+    assert_eq!(0, game.dialogue_node);
+    assert_eq!(turn, game.log().turn());
+
+    //Robbing makes an enemy.
+    game.step(Input::MoveDown);
+    game.step(Input::Use);
+    assert!(game.game_state == Action::Game);
+    assert!(game.player.is_enemy_of(&game.enemies[0]));
+
+    //Resting heals, afterwards the hermit leaves.
+    let mut hermit = Entity::new(11);
+    create_npc(&game.player, &mut hermit, "Hermit");
+    hermit.pos_row = 4;
+    hermit.pos_col = 3;
+    game.enemies = vec![hermit];
+    game.player.current_life = 10;
+
+    game.step(Input::Use);
+    game.step(Input::Use);
+    assert!(game.player.current_life > 10);
+    game.step(Input::Use);
+    assert!(game.game_state == Action::Game);
+    assert!(game.enemies.is_empty());
+}
+
+#[test]
+fn test_quests() {
+    let mut game = test_game(Class::Warrior);
+    game.map = Level::new();
+    game.enemies.clear();
+    game.player.backpack = super::character::backpack::Backpack::new();
+    game.player.pos_row = 3;
+    game.player.pos_col = 3;
+
+    let mut merchant = Entity::new(10);
+    create_npc(&game.player, &mut merchant, "Merchant");
+    merchant.pos_row = 3;
+    merchant.pos_col = 4;
+    game.enemies.push(merchant);
+
+    //The merchant gives one quest at a time.
+    let work = game.dialogues.of("Merchant").unwrap().node("work");
+    game.step(Input::Use);
+    game.step(Input::MoveDown);
+    game.step(Input::MoveDown);
+    game.step(Input::Use);
+    assert_eq!(work, Option::Some(game.dialogue_node));
+    game.step(Input::Use);
+    assert_eq!(0, game.dialogue_node);
+    game.step(Input::MoveDown);
+    game.step(Input::MoveDown);
+    game.step(Input::Use);
+    game.step(Input::Use);
+    assert_eq!(work, Option::Some(game.dialogue_node));
+    assert_eq!(1, game.quests.quests.len());
+    assert_eq!("Merchant", game.quests.quests[0].giver);
+    game.step(Input::Quit);
+
+    //Completed quests are rewarded right away.
+    game.quests.quests[0].objective = Objective::Kill { monster_type: MonsterType::Goblin, count: 1 };
+    let goblin = Actor { id: 20, name: "Goblin".to_string(), monster_type: MonsterType::Goblin };
+    game.events.push(Event::Kill { attacker: Actor::of(&game.player), target: goblin });
+    game.step(Input::Quests);
+    assert!(game.game_state == Action::Quests);
+    assert!(game.quests.quests[0].done);
+    assert!(game.player.backpack.size() > 0);
+    game.step(Input::Quit);
+    assert!(game.game_state == Action::Game);
+
+    //Notes start quests too.
+    game.player.backpack = super::character::backpack::Backpack::new();
+    game.player.backpack.add_item(super::gen::item::generate_note()).unwrap();
+    game.read_scroll(0);
+    assert_eq!(0, game.player.backpack.size());
+    assert_eq!("Torn Note", game.quests.quests[1].giver);
+
+    //Places of quests are prepared when their floor is generated.
+    game.quests.quests.push(Quest { giver: "Hermit".to_string(), objective: Objective::Vault { depth: 1, guardians: Vec::new() }, progress: 0, reward: Reward::Tome, done: false });
+    game.quests.quests.push(Quest { giver: "Wounded Adventurer".to_string(), objective: Objective::Recover { item_type: Type::Legs, depth: 1 }, progress: 0, reward: Reward::Gear, done: false });
+    game.map = game.level_generator.generate_level(0);
+    game.change_floor(1);
+
+    //Only the guardians spawned in the vault count, not the other monsters.
+    let guardians: Vec<u32> = game.enemies.iter().filter(|enemy| enemy.name.ends_with("Guardian")).map(|enemy| enemy.id).collect();
+    assert_eq!(3, guardians.len());
+    assert!(game.enemies.iter().any(|enemy| enemy.faction == Faction::Hostile && !guardians.contains(&enemy.id)));
+    assert_eq!(Objective::Vault { depth: 1, guardians }, game.quests.quests[2].objective);
+    assert!(game.enemies.iter().any(|enemy| enemy.backpack.items.iter().any(|item| item.name == "Heirloom Trousers")));
+}
+
+#[test]
+fn test_use_item() {
+    let mut game = test_game(Class::Warrior);
+    game.enemies.clear();
+
+    //Items are used even where the use key takes the stairs.
+    let (row, col) = game.map.find_meta(Tile::PlSpawn).unwrap();
+    game.player.pos_row = row as i32;
+    game.player.pos_col = col as i32;
+    game.player.current_life = 1;
+    let potion = Item { item_type: Type::Potion, name: "Potion".to_string(), modifications: vec![StatsMod::Heal(50)], rarity: Rarity::Common };
+    game.player.backpack.add_item(potion).unwrap();
+    let index = game.player.backpack.size() - 1;
+
+    game.step(Input::UseItem(index));
+    assert_eq!(Action::Game, game.state());
+    assert_eq!(0, game.log().turn());
+    assert!(game.player.current_life > 1);
+    assert_eq!(index, game.player.backpack.size());
+
+    game.step(Input::UseItem(super::character::backpack::BACKPACK_SIZE));
+    assert_eq!(Action::Game, game.state());
+}
+
+#[test]
+fn test_heirloom_corpse() {
+    let mut game = test_game(Class::Warrior);
+    let stone = Item { item_type: Type::Legs, name: "Stone".to_string(), modifications: Vec::new(), rarity: Rarity::Common };
+    for enemy in game.enemies.iter_mut().filter(|enemy| enemy.faction == Faction::Hostile) {
+        while enemy.backpack.add_item(stone.clone()).is_ok() {}
+    }
+
+    //Without room in any backpack the heirloom lies next to the stairs, not on them.
+    let heirloom = Item { name: "Heirloom Trousers".to_string(), ..stone.clone() };
+    game.hide(heirloom);
+    let corpse = game.enemies.last().unwrap();
+    assert_eq!("Fallen Adventurer", corpse.name);
+    assert_eq!("Heirloom Trousers", corpse.backpack.items[0].name);
+    assert!(game.map.is_walkable(corpse.pos_row as usize, corpse.pos_col as usize));
+    assert!(!matches!(game.map.meta[corpse.pos_row as usize][corpse.pos_col as usize], Tile::PlSpawn | Tile::Next));
+
+    let (row, col) = game.map.find_meta(Tile::Next).unwrap();
+    game.player.pos_row = row as i32;
+    game.player.pos_col = col as i32;
+    game.step(Input::Use);
+    assert_eq!(1, game.map.level);
+}
+
+#[test]
+fn test_vault_without_room() {
+    let mut game = test_game(Class::Warrior);
+    game.quests.quests.push(Quest { giver: "Hermit".to_string(), objective: Objective::Vault { depth: 1, guardians: Vec::new() }, progress: 0, reward: Reward::Tome, done: false });
+
+    //A single small room has no place for a vault, it moves to the next floor.
+    game.map = Level::new();
+    game.map.level = 1;
+    game.prepare_quests(1);
+    assert_eq!(Objective::Vault { depth: 2, guardians: Vec::new() }, game.quests.quests[0].objective);
+    assert!(!game.enemies.iter().any(|enemy| enemy.name.ends_with("Guardian")));
+    assert!(!game.quests.quests[0].done);
+}
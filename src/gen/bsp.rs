@@ -0,0 +1,428 @@
+extern crate rand;
+
+use rand::{Rng, StdRng};
+
+use super::super::level::*;
+use super::level::{LevelAlgorithm, add_monster, assure_walls_everywhere};
+
+/// How rooms of two partitions are connected.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CorridorStyle {
+    /// One bend.
+    LShaped,
+    /// A single line if the rooms face each other, else two bends.
+    Straight,
+    /// Random walk towards the other room.
+    Winding,
+}
+
+/// Floor area of a room or a partition, row and col are the upper left corner.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Rect {
+    row: usize,
+    col: usize,
+    height: usize,
+    width: usize,
+}
+
+impl Rect {
+    fn center(&self) -> (usize, usize) {
+        (self.row + self.height / 2, self.col + self.width / 2)
+    }
+
+    fn area(&self) -> usize {
+        self.height * self.width
+    }
+
+    #[cfg(test)]
+    fn intersect(&self, other: &Rect) -> bool {
+        self.row < other.row + other.height && other.row < self.row + self.height &&
+            self.col < other.col + other.width && other.col < self.col + self.width
+    }
+}
+
+struct Partition {
+    rect: Rect,
+    children: Option<(usize, usize)>,
+    room: Option<Rect>,
+}
+
+/// Splits the map recursively and puts a room into every partition.
+/// Rooms never overlap, because every partition holds exactly one room and its walls.
+pub struct BspAlgorithm {
+    size_rows: usize,
+    size_cols: usize,
+
+    room_count: usize,
+    min_room_size: usize,
+    max_room_size: usize,
+
+    corridor_style: CorridorStyle,
+}
+
+impl BspAlgorithm {
+    /// Places room_count rooms, or as many as fit into the map.
+    pub fn new(size_rows: usize, size_cols: usize, room_count: usize, min_room_size: usize, max_room_size: usize, corridor_style: CorridorStyle) -> BspAlgorithm {
+        //Spawn and stairs need a room each, or two tiles of the only room.
+        assert!(room_count >= 2, "At least two rooms are needed.");
+        assert!(min_room_size >= 2 && min_room_size <= max_room_size, "Invalid room sizes.");
+        assert!(size_rows >= min_room_size + 2 && size_cols >= min_room_size + 2, "Map is too small for a single room.");
+
+        BspAlgorithm { size_rows, size_cols, room_count, min_room_size, max_room_size, corridor_style }
+    }
+
+    fn partition(&self, rng: &mut StdRng) -> Vec<Partition> {
+        //Outer border of the map stays empty, every partition keeps a border for walls.
+        let root = Rect { row: 0, col: 0, height: self.size_rows, width: self.size_cols };
+        let mut partitions = vec![Partition { rect: root, children: Option::None, room: Option::None }];
+        let mut leaf_count = 1;
+
+        while leaf_count < self.room_count {
+            let leaf = partitions.iter().enumerate()
+                .filter(|&(_, partition)| partition.children.is_none() && self.can_split(&partition.rect))
+                .max_by_key(|&(_, partition)| partition.rect.area())
+                .map(|(index, _)| index);
+
+            let leaf = match leaf {
+                Option::Some(value) => value,
+                Option::None => break,
+            };
+
+            let (first, second) = self.split(rng, &partitions[leaf].rect);
+            partitions.push(Partition { rect: first, children: Option::None, room: Option::None });
+            partitions.push(Partition { rect: second, children: Option::None, room: Option::None });
+            partitions[leaf].children = Option::Some((partitions.len() - 2, partitions.len() - 1));
+
+            leaf_count += 1;
+        }
+
+        partitions
+    }
+
+    fn min_partition_size(&self) -> usize {
+        self.min_room_size + 2
+    }
+
+    fn can_split(&self, rect: &Rect) -> bool {
+        rect.height >= 2 * self.min_partition_size() || rect.width >= 2 * self.min_partition_size()
+    }
+
+    fn split(&self, rng: &mut StdRng, rect: &Rect) -> (Rect, Rect) {
+        let min_size = self.min_partition_size();
+        let can_split_rows = rect.height >= 2 * min_size;
+        let can_split_cols = rect.width >= 2 * min_size;
+
+        let split_rows = if can_split_rows && can_split_cols {
+            //Prefer cutting the long side, console cells are twice as high as wide.
+            if rect.height * 2 > rect.width {
+                true
+            } else if rect.width > rect.height * 4 {
+                false
+            } else {
+                rng.gen_range(0, 2) == 0
+            }
+        } else {
+            can_split_rows
+        };
+
+        if split_rows {
+            let height = rng.gen_range(min_size, rect.height - min_size + 1);
+
+            (Rect { height, ..*rect }, Rect { row: rect.row + height, height: rect.height - height, ..*rect })
+        } else {
+            let width = rng.gen_range(min_size, rect.width - min_size + 1);
+
+            (Rect { width, ..*rect }, Rect { col: rect.col + width, width: rect.width - width, ..*rect })
+        }
+    }
+
+    fn place_room(&self, rng: &mut StdRng, rect: &Rect) -> Rect {
+        //Keep one tile for walls on every side.
+        let max_height = std::cmp::min(self.max_room_size, rect.height - 2);
+        let max_width = std::cmp::min(self.max_room_size, rect.width - 2);
+
+        let height = rng.gen_range(self.min_room_size, max_height + 1);
+        let width = rng.gen_range(self.min_room_size, max_width + 1);
+
+        Rect {
+            row: rng.gen_range(rect.row + 1, rect.row + rect.height - height),
+            col: rng.gen_range(rect.col + 1, rect.col + rect.width - width),
+            height,
+            width,
+        }
+    }
+
+    fn connect(&self, rng: &mut StdRng, level: &mut Level, room_a: &Rect, room_b: &Rect) {
+        let from = room_a.center();
+        let to = room_b.center();
+
+        match self.corridor_style {
+            CorridorStyle::LShaped => {
+                if rng.gen_range(0, 2) == 0 {
+                    carve_horizontal(level, from.0, from.1, to.1);
+                    carve_vertical(level, to.1, from.0, to.0);
+                } else {
+                    carve_vertical(level, from.1, from.0, to.0);
+                    carve_horizontal(level, to.0, from.1, to.1);
+                }
+            },
+            CorridorStyle::Straight => {
+                let first_row = std::cmp::max(room_a.row, room_b.row);
+                let last_row = std::cmp::min(room_a.row + room_a.height, room_b.row + room_b.height);
+                let first_col = std::cmp::max(room_a.col, room_b.col);
+                let last_col = std::cmp::min(room_a.col + room_a.width, room_b.col + room_b.width);
+
+                if first_row < last_row {
+                    let row = rng.gen_range(first_row, last_row);
+                    carve_horizontal(level, row, from.1, to.1);
+                } else if first_col < last_col {
+                    let col = rng.gen_range(first_col, last_col);
+                    carve_vertical(level, col, from.0, to.0);
+                } else {
+                    let middle_col = (from.1 + to.1) / 2;
+
+                    carve_horizontal(level, from.0, from.1, middle_col);
+                    carve_vertical(level, middle_col, from.0, to.0);
+                    carve_horizontal(level, to.0, middle_col, to.1);
+                }
+            },
+            CorridorStyle::Winding => {
+                let (mut row, mut col) = from;
+                level.map[row][col] = Tile::Floor;
+
+                while (row, col) != to {
+                    let towards_target = rng.gen_range(0, 100) < 70;
+                    let move_row = if row == to.0 {
+                        !towards_target
+                    } else if col == to.1 {
+                        towards_target
+                    } else {
+                        rng.gen_range(0, 2) == 0
+                    };
+
+                    if move_row {
+                        let step_down = if towards_target && row != to.0 { row < to.0 } else { rng.gen_range(0, 2) == 0 };
+                        row = clamp_step(row, step_down, self.size_rows);
+                    } else {
+                        let step_right = if towards_target && col != to.1 { col < to.1 } else { rng.gen_range(0, 2) == 0 };
+                        col = clamp_step(col, step_right, self.size_cols);
+                    }
+
+                    level.map[row][col] = Tile::Floor;
+                }
+            },
+        }
+    }
+}
+
+impl LevelAlgorithm for BspAlgorithm {
+    fn generate(&mut self, rng: &mut StdRng, level: i32) -> Option<Level> {
+        let mut partitions = self.partition(rng);
+
+        for partition in partitions.iter_mut() {
+            if partition.children.is_none() {
+                partition.room = Option::Some(self.place_room(rng, &partition.rect));
+            }
+        }
+
+        let mut new_level = Level {
+            map: vec![vec![Tile::Nothing; self.size_cols]; self.size_rows],
+            meta: vec![vec![Tile::Nothing; self.size_cols]; self.size_rows],
+            level,
+            explored: Vec::new(),
+        };
+
+        let rooms: Vec<Rect> = partitions.iter().filter_map(|partition| partition.room).collect();
+        for room in &rooms {
+            for row in room.row..room.row + room.height {
+                for col in room.col..room.col + room.width {
+                    new_level.map[row][col] = Tile::Floor;
+                }
+            }
+        }
+
+        //Connect the nearest rooms of both halves of every split.
+        for partition in &partitions {
+            if let Option::Some((first, second)) = partition.children {
+                let (room_a, room_b) = nearest_rooms(&rooms_of(&partitions, first), &rooms_of(&partitions, second));
+                self.connect(rng, &mut new_level, &room_a, &room_b);
+            }
+        }
+
+        assure_walls_everywhere(&mut new_level);
+        add_meta_information(rng, &rooms, &mut new_level);
+
+        Option::Some(new_level)
+    }
+}
+
+fn rooms_of(partitions: &[Partition], index: usize) -> Vec<Rect> {
+    match partitions[index].children {
+        Option::Some((first, second)) => {
+            let mut rooms = rooms_of(partitions, first);
+            rooms.extend(rooms_of(partitions, second));
+            rooms
+        },
+        Option::None => partitions[index].room.into_iter().collect(),
+    }
+}
+
+fn nearest_rooms(first_rooms: &[Rect], second_rooms: &[Rect]) -> (Rect, Rect) {
+    let mut nearest = (first_rooms[0], second_rooms[0]);
+    let mut min_distance = usize::MAX;
+
+    for first in first_rooms {
+        for second in second_rooms {
+            let (first_row, first_col) = first.center();
+            let (second_row, second_col) = second.center();
+            let distance = first_row.abs_diff(second_row) + first_col.abs_diff(second_col);
+
+            if distance < min_distance {
+                nearest = (*first, *second);
+                min_distance = distance;
+            }
+        }
+    }
+
+    nearest
+}
+
+/// Moves one step, but never onto the outer border of the map.
+fn clamp_step(value: usize, increase: bool, size: usize) -> usize {
+    if increase && value + 2 < size {
+        value + 1
+    } else if !increase && value > 1 {
+        value - 1
+    } else {
+        value
+    }
+}
+
+fn carve_horizontal(level: &mut Level, row: usize, from_col: usize, to_col: usize) {
+    for col in std::cmp::min(from_col, to_col)..std::cmp::max(from_col, to_col) + 1 {
+        level.map[row][col] = Tile::Floor;
+    }
+}
+
+fn carve_vertical(level: &mut Level, col: usize, from_row: usize, to_row: usize) {
+    for row in std::cmp::min(from_row, to_row)..std::cmp::max(from_row, to_row) + 1 {
+        level.map[row][col] = Tile::Floor;
+    }
+}
+
+/// Spawn in the first room, stairs in the room farthest away, monsters in between.
+fn add_meta_information(rng: &mut StdRng, rooms: &[Rect], level: &mut Level) {
+    let spawn = rooms[0].center();
+    let distances = level.distance_map(spawn.0, spawn.1);
+
+    let mut stairs_room = 0;
+    let mut max_distance = 0;
+    for (index, room) in rooms.iter().enumerate() {
+        let (row, col) = room.center();
+        let distance = distances[row][col].unwrap_or(0);
+
+        if distance > max_distance {
+            stairs_room = index;
+            max_distance = distance;
+        }
+    }
+
+    for (index, room) in rooms.iter().enumerate() {
+        if index == 0 || index == stairs_room {
+            continue;
+        }
+
+        if rng.gen_range(1, 11) <= 9 {
+            let (row, col) = room.center();
+            add_monster(rng, row, col, level);
+        }
+    }
+
+    //If no other room fits, the stairs go to the floor farthest from the spawn.
+    let mut stairs = rooms[stairs_room].center();
+    if stairs_room == 0 {
+        for (row, distance_row) in distances.iter().enumerate() {
+            for (col, distance) in distance_row.iter().enumerate() {
+                if let Option::Some(distance) = *distance {
+                    if distance > max_distance {
+                        stairs = (row, col);
+                        max_distance = distance;
+                    }
+                }
+            }
+        }
+    }
+
+    level.meta[spawn.0][spawn.1] = Tile::PlSpawn;
+    level.meta[stairs.0][stairs.1] = Tile::Next;
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, StdRng};
+
+    use super::*;
+    use super::super::super::level::Tile;
+
+    fn rng(seed: usize) -> StdRng {
+        let seed: &[usize] = &[seed];
+        SeedableRng::from_seed(seed)
+    }
+
+    #[test]
+    fn test_rooms_do_not_overlap() {
+        let algorithm = BspAlgorithm::new(18, 78, 8, 3, 8, CorridorStyle::LShaped);
+
+        for seed in 0..200 {
+            let mut rng = rng(seed);
+            let partitions = algorithm.partition(&mut rng);
+
+            let rooms: Vec<Rect> = partitions.iter().filter(|partition| partition.children.is_none())
+                .map(|partition| algorithm.place_room(&mut rng, &partition.rect)).collect();
+
+            assert_eq!(8, rooms.len());
+
+            for (index, room) in rooms.iter().enumerate() {
+                assert!(room.height >= 3 && room.height <= 8 && room.width >= 3 && room.width <= 8);
+
+                for other in rooms.iter().skip(index + 1) {
+                    assert!(!room.intersect(other), "seed {}: {:?} overlaps {:?}", seed, room, other);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_levels_are_valid_for_all_sizes() {
+        let styles = [CorridorStyle::LShaped, CorridorStyle::Straight, CorridorStyle::Winding];
+        let sizes = [(18, 78, 8), (12, 30, 3), (40, 120, 20)];
+
+        for style in styles.iter() {
+            for &(rows, cols, room_count) in sizes.iter() {
+                let mut algorithm = BspAlgorithm::new(rows, cols, room_count, 3, 7, *style);
+
+                for seed in 0..100 {
+                    let level = algorithm.generate(&mut rng(seed), 1).unwrap();
+
+                    assert_eq!(rows, level.map.len());
+                    assert_eq!(cols, level.map[0].len());
+                    assert_eq!(Result::Ok(()), level.validate(), "seed {} style {:?} size {}x{}", seed, style, rows, cols);
+                    assert!(level.find_meta(Tile::Next).is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_room_keeps_spawn() {
+        //No partition can be split, so only one room fits.
+        let mut algorithm = BspAlgorithm::new(12, 18, 4, 8, 9, CorridorStyle::Straight);
+
+        for seed in 0..20 {
+            let level = algorithm.generate(&mut rng(seed), 1).unwrap();
+
+            assert_eq!(Result::Ok(()), level.validate(), "seed {}", seed);
+            assert_ne!(level.find_meta(Tile::PlSpawn), level.find_meta(Tile::Next));
+        }
+    }
+}
@@ -0,0 +1,240 @@
+extern crate rand;
+
+use rand::{Rng, StdRng};
+
+use super::super::level::*;
+use super::level::{LevelAlgorithm, MAX_GENERATION_ATTEMPTS, add_monster, assure_walls_everywhere};
+
+/// Organic caves grown with a cellular automaton.
+pub struct CaveAlgorithm {
+    size_rows: usize,
+    size_cols: usize,
+
+    /// Chance in percent that a cell starts as wall.
+    fill_percentage: u32,
+    iterations: u32,
+
+    /// The cave is thrown away, if less floor than this percentage survives.
+    min_floor_percentage: usize,
+}
+
+impl CaveAlgorithm {
+    pub fn new(size_rows: usize, size_cols: usize) -> CaveAlgorithm {
+        CaveAlgorithm { size_rows, size_cols, fill_percentage: 45, iterations: 5, min_floor_percentage: 30 }
+    }
+
+    fn random_fill(&self, rng: &mut StdRng) -> Vec<Vec<bool>> {
+        let mut walls = Vec::new();
+
+        for row in 0..self.size_rows {
+            let mut wall_row = Vec::new();
+
+            for col in 0..self.size_cols {
+                let border = row == 0 || col == 0 || row == self.size_rows - 1 || col == self.size_cols - 1;
+
+                wall_row.push(border || rng.gen_range(0, 100) < self.fill_percentage);
+            }
+
+            walls.push(wall_row);
+        }
+
+        walls
+    }
+
+    /// A cell becomes wall if most of its neighbours are walls.
+    /// Big open areas get a pillar in the first iterations.
+    fn smooth(&self, walls: &[Vec<bool>], add_pillars: bool) -> Vec<Vec<bool>> {
+        let mut result = walls.to_vec();
+
+        for (row, result_row) in result.iter_mut().enumerate().take(self.size_rows - 1).skip(1) {
+            for (col, cell) in result_row.iter_mut().enumerate().take(self.size_cols - 1).skip(1) {
+                let near_walls = count_walls(walls, row, col, 1);
+
+                *cell = if near_walls >= 5 {
+                    true
+                } else {
+                    add_pillars && count_walls(walls, row, col, 2) <= 2
+                };
+            }
+        }
+
+        result
+    }
+}
+
+impl LevelAlgorithm for CaveAlgorithm {
+    fn generate(&mut self, rng: &mut StdRng, level: i32) -> Option<Level> {
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let mut walls = self.random_fill(rng);
+
+            for iteration in 0..self.iterations {
+                walls = self.smooth(&walls, iteration < self.iterations / 2);
+            }
+
+            let mut new_level = Level { map: Vec::new(), meta: Vec::new(), level, explored: Vec::new() };
+            for wall_row in &walls {
+                new_level.map.push(wall_row.iter().map(|&wall| if wall { Tile::Nothing } else { Tile::Floor }).collect());
+                new_level.meta.push(vec![Tile::Nothing; wall_row.len()]);
+            }
+
+            let floor_count = keep_largest_cave(&mut new_level);
+            if floor_count * 100 < self.size_rows * self.size_cols * self.min_floor_percentage {
+                continue;
+            }
+
+            assure_walls_everywhere(&mut new_level);
+            add_meta_information(rng, &mut new_level, floor_count);
+
+            return Option::Some(new_level);
+        }
+
+        //Too little floor survives every time, the generator falls back to rooms.
+        Option::None
+    }
+}
+
+fn count_walls(walls: &[Vec<bool>], row: usize, col: usize, radius: usize) -> usize {
+    let mut count = 0;
+
+    for other_row in row.saturating_sub(radius)..row + radius + 1 {
+        for other_col in col.saturating_sub(radius)..col + radius + 1 {
+            let outside = other_row >= walls.len() || other_col >= walls[other_row].len();
+
+            if outside || walls[other_row][other_col] {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+/// Removes every pocket except the biggest one, returns its size.
+fn keep_largest_cave(level: &mut Level) -> usize {
+    let mut largest: Vec<(usize, usize)> = Vec::new();
+    let mut visited: Vec<Vec<bool>> = level.map.iter().map(|map_row| vec![false; map_row.len()]).collect();
+
+    for row in 0..level.map.len() {
+        for col in 0..level.map[row].len() {
+            if visited[row][col] || !level.is_walkable(row, col) {
+                continue;
+            }
+
+            let pocket = flood_fill(level, &mut visited, row, col);
+            if pocket.len() > largest.len() {
+                largest = pocket;
+            }
+        }
+    }
+
+    for map_row in level.map.iter_mut() {
+        for tile in map_row.iter_mut() {
+            *tile = Tile::Nothing;
+        }
+    }
+
+    for &(row, col) in &largest {
+        level.map[row][col] = Tile::Floor;
+    }
+
+    largest.len()
+}
+
+fn flood_fill(level: &Level, visited: &mut [Vec<bool>], row: usize, col: usize) -> Vec<(usize, usize)> {
+    let mut pocket = vec![(row, col)];
+    visited[row][col] = true;
+
+    let mut index = 0;
+    while index < pocket.len() {
+        let (current_row, current_col) = pocket[index];
+        index += 1;
+
+        for (next_row, next_col) in neighbours(current_row, current_col) {
+            if level.is_walkable(next_row, next_col) && !visited[next_row][next_col] {
+                visited[next_row][next_col] = true;
+                pocket.push((next_row, next_col));
+            }
+        }
+    }
+
+    pocket
+}
+
+/// Spawn on a random floor, stairs on the farthest floor from it.
+/// Monsters keep some distance to the spawn.
+fn add_meta_information(rng: &mut StdRng, level: &mut Level, floor_count: usize) {
+    let spawn = random_floor(rng, level);
+    let distances = level.distance_map(spawn.0, spawn.1);
+
+    let mut stairs = spawn;
+    let mut max_distance = 0;
+    for (row, distance_row) in distances.iter().enumerate() {
+        for (col, distance) in distance_row.iter().enumerate() {
+            if let Option::Some(value) = *distance {
+                if value > max_distance {
+                    stairs = (row, col);
+                    max_distance = value;
+                }
+            }
+        }
+    }
+
+    let min_monster_distance = max_distance / 4;
+    let monster_groups = floor_count / 100;
+
+    for _ in 0..monster_groups {
+        let (row, col) = random_floor(rng, level);
+        let distance = distances[row][col].unwrap_or(0);
+
+        if distance > min_monster_distance && level.meta[row][col] == Tile::Nothing {
+            add_monster(rng, row, col, level);
+        }
+    }
+
+    level.meta[spawn.0][spawn.1] = Tile::PlSpawn;
+    level.meta[stairs.0][stairs.1] = Tile::Next;
+}
+
+fn random_floor(rng: &mut StdRng, level: &Level) -> (usize, usize) {
+    loop {
+        let row = rng.gen_range(0, level.map.len());
+        let col = rng.gen_range(0, level.map[row].len());
+
+        if level.is_walkable(row, col) {
+            return (row, col);
+        }
+    }
+}
+
+#[test]
+fn test_cave_gives_up() {
+    use rand::SeedableRng;
+    use super::level::{LevelGenerator, DEFAULT_ROWS, DEFAULT_COLS};
+
+    //No cave has more floor than tiles.
+    let mut cave = CaveAlgorithm::new(DEFAULT_ROWS, DEFAULT_COLS);
+    cave.min_floor_percentage = 101;
+
+    let seed: &[usize] = &[7];
+    assert!(cave.generate(&mut SeedableRng::from_seed(seed), 4).is_none());
+
+    //The generator builds rooms instead.
+    let mut generator = LevelGenerator::with_seed(7);
+    generator.try_first(Box::new(cave));
+    let level = generator.generate_level(4);
+    assert_eq!(Result::Ok(()), level.validate());
+}
+
+#[test]
+fn test_keep_largest_cave() {
+    let mut level = Level::new();
+
+    //Small pocket in the corner of the room.
+    for row in 2..7 {
+        level.map[row][3] = Tile::Wall;
+    }
+
+    assert_eq!(15, keep_largest_cave(&mut level));
+    assert_eq!(Tile::Nothing, level.map[4][2]);
+    assert_eq!(Tile::Floor, level.map[4][4]);
+}
@@ -1,18 +1,62 @@
-extern crate rand;
-
-use rand::Rng;
+use super::super::rng;
 use super::super::character::item::*;
 use super::super::character::stats::*;
 use super::super::character::entity::*;
+use super::super::effect::SPELLS;
 
 pub fn generate_item(target_type: Type, player_item: &Item, monster_difficulty: &Difficulty) -> Item {
-    let mut item = Item { item_type: target_type, name: generate_random_weapon_name(&target_type, &monster_difficulty), modifications: Vec::new() };
+    let mut item = Item { item_type: target_type, name: generate_random_weapon_name(&target_type, &monster_difficulty), modifications: Vec::new(), rarity: resolve_rarity(monster_difficulty) };
 
     generate_item_attributes(&mut item, &player_item, &monster_difficulty);
 
     item
 }
 
+/// Healing potions of stronger monsters heal more.
+pub fn generate_potion(monster_difficulty: &Difficulty) -> Item {
+    let healing_percentage = match *monster_difficulty {
+        Difficulty::Easy => 10,
+        Difficulty::Normal => 25,
+        Difficulty::Hard => 50,
+        Difficulty::Boss => 100,
+    };
+
+    Item { name: "Healing Potion".to_string(), item_type: Type::Potion, modifications: vec![StatsMod::Heal(healing_percentage)], rarity: Rarity::Common }
+}
+
+/// Scrolls teach a random spell, tomes of strong monsters also raise the intelligence.
+pub fn generate_scroll(monster_difficulty: &Difficulty) -> Item {
+    let spell = SPELLS[rng::gen_range(0, SPELLS.len())];
+
+    let name = match *monster_difficulty {
+        Difficulty::Easy | Difficulty::Normal => format!("Scroll of {}", spell.name()),
+        Difficulty::Hard | Difficulty::Boss => format!("Tome of {}", spell.name()),
+    };
+    let mut scroll = Item { item_type: Type::Scroll, name, modifications: vec![StatsMod::Teach(spell)], rarity: resolve_rarity(monster_difficulty) };
+
+    match *monster_difficulty {
+        Difficulty::Hard => scroll.modifications.push(StatsMod::Add(Stat::Intelligence(1))),
+        Difficulty::Boss => scroll.modifications.push(StatsMod::Add(Stat::Intelligence(2))),
+        Difficulty::Easy | Difficulty::Normal => {},
+    }
+
+    scroll
+}
+
+/// Torn notes tell of something to do deeper down.
+pub fn generate_note() -> Item {
+    Item { name: "Torn Note".to_string(), item_type: Type::Scroll, modifications: vec![StatsMod::Quest], rarity: Rarity::Common }
+}
+
+fn resolve_rarity(difficulty: &Difficulty) -> Rarity {
+    match *difficulty {
+        Difficulty::Easy => Rarity::Common,
+        Difficulty::Normal => Rarity::Uncommon,
+        Difficulty::Hard => Rarity::Rare,
+        Difficulty::Boss => Rarity::Legendary,
+    }
+}
+
 fn generate_random_weapon_name(item_type: &Type, difficulty: &Difficulty) -> String {
     let quality = match difficulty {
         &Difficulty::Easy => "Lesser",
@@ -26,7 +70,7 @@ fn generate_random_weapon_name(item_type: &Type, difficulty: &Difficulty) -> Str
         &Type::Chest => "Armor",
         &Type::Legs => "Trousers",
         &Type::Weapon => "Sword",
-        &Type::Nothing | &Type::Potion => "Blackhole",
+        &Type::Nothing | &Type::Potion | &Type::Scroll => "Blackhole",
     }.to_string();
 
     return format!("{} {}", quality, part);
@@ -35,7 +79,7 @@ fn generate_random_weapon_name(item_type: &Type, difficulty: &Difficulty) -> Str
 fn generate_item_attributes(new_item: &mut Item, current_item: &Item, monster_difficulty: &Difficulty) {
     let mut first_value_range = calculate_attribute_range(&current_item, &monster_difficulty);
 
-    let value = rand::thread_rng().gen_range(0, first_value_range);
+    let value = rng::gen_range(0, first_value_range);
 
     if value > 0 {
         first_value_range -= value;
@@ -44,7 +88,7 @@ fn generate_item_attributes(new_item: &mut Item, current_item: &Item, monster_di
     }
 
     if first_value_range > 1 {
-        let value = rand::thread_rng().gen_range(0, first_value_range);
+        let value = rng::gen_range(0, first_value_range);
 
         if value > 0 {
             first_value_range -= value;
@@ -54,7 +98,7 @@ fn generate_item_attributes(new_item: &mut Item, current_item: &Item, monster_di
     }
 
     if first_value_range > 1 {
-        let value = rand::thread_rng().gen_range(0, first_value_range);
+        let value = rng::gen_range(0, first_value_range);
 
         if value > 0 {
             new_item.modifications.push(StatsMod::Add(Stat::Defense(value)));
@@ -75,8 +119,8 @@ fn calculate_weapon_attributes(new_item : &mut Item, current_item : &Item, monst
 
     let min_max_damage = current_item.get_damage();
 
-    let mut rnd_min = rand::thread_rng().gen_range(min_max_damage.0 + min_difficulty_bonus, min_max_damage.0 + max_difficulty_bonus);
-    let mut rnd_max = rand::thread_rng().gen_range(min_max_damage.1 + min_difficulty_bonus, min_max_damage.1 + max_difficulty_bonus);
+    let mut rnd_min = rng::gen_range(min_max_damage.0 + min_difficulty_bonus, min_max_damage.0 + max_difficulty_bonus);
+    let mut rnd_max = rng::gen_range(min_max_damage.1 + min_difficulty_bonus, min_max_damage.1 + max_difficulty_bonus);
 
     //Just make sure that min is <= than max.
     if rnd_min == rnd_max {
@@ -133,7 +177,7 @@ fn calculate_attribute_range(current_item: &Item, monster_difficulty: &Difficult
         }
     };
 
-    let attributes_count = rand::thread_rng().gen_range(current_bonus+min_difficulty_bonus, current_bonus+max_difficulty_bonus);
+    let attributes_count = rng::gen_range(current_bonus+min_difficulty_bonus, current_bonus+max_difficulty_bonus);
 
     if attributes_count <= 0 {
         1
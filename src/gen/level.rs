@@ -1,9 +1,11 @@
 extern crate rand;
 
 use std;
-use rand::{Rng, ThreadRng};
+use rand::{Rng, SeedableRng, StdRng};
 
 use super::super::level::*;
+use super::cave::CaveAlgorithm;
+use super::bsp::{BspAlgorithm, CorridorStyle};
 
 struct Room {
     row: usize,
@@ -15,18 +17,19 @@ struct Room {
 
 impl Room {
     fn intersect(&self, other: &Room) -> bool {
-        let x_min = self.col - self.width;
-        let x_max = self.col + self.width;
+        //Signed, rooms near the edge would underflow otherwise.
+        let x_min = self.col as i32 - self.width as i32;
+        let x_max = self.col as i32 + self.width as i32;
 
-        let other_x_min = other.col - other.width;
-        let other_x_max = other.col + other.width;
+        let other_x_min = other.col as i32 - other.width as i32;
+        let other_x_max = other.col as i32 + other.width as i32;
 
 
-        let y_min = self.row - self.height;
-        let y_max = self.row + self.height;
+        let y_min = self.row as i32 - self.height as i32;
+        let y_max = self.row as i32 + self.height as i32;
 
-        let other_y_min = other.row - other.height;
-        let other_y_max = other.row + other.height;
+        let other_y_min = other.row as i32 - other.height as i32;
+        let other_y_max = other.row as i32 + other.height as i32;
 
 
         return (x_min <= other_x_min && other_x_min <= x_max || other_x_min <= x_min && x_min <= other_x_max) &&
@@ -47,26 +50,187 @@ impl Room {
     }
 }
 
+/// How often a broken level is regenerated before giving up.
+pub const MAX_GENERATION_ATTEMPTS: u32 = 100;
+
+/// One in this many levels has a friendly entity.
+const NPC_CHANCE: u32 = 3;
+/// Random tiles tried for the friendly entity before the level goes without.
+const NPC_ATTEMPTS: u32 = 50;
+
+/// Inner size of a vault, its walls come on top.
+const VAULT_ROWS: usize = 3;
+const VAULT_COLS: usize = 5;
+
+/// Level size, which fits on a 80x24 console.
+pub const DEFAULT_ROWS: usize = 18;
+pub const DEFAULT_COLS: usize = 78;
+
+/// Smallest level size every algorithm can handle.
+pub const MIN_ROWS: usize = 14;
+pub const MIN_COLS: usize = 30;
+
+/// Algorithm which lays out walls, floor and meta-information of a single level.
+pub trait LevelAlgorithm {
+    /// None if the algorithm gives up on the level.
+    fn generate(&mut self, rng: &mut StdRng, level: i32) -> Option<Level>;
+}
+
+/// Uses given algorithm for every level whose depth modulo 10 lies between from and to.
+struct LevelTheme {
+    from: i32,
+    to: i32,
+    algorithm: Box<dyn LevelAlgorithm>,
+}
+
 pub struct LevelGenerator {
-    rng: ThreadRng,
+    rng: StdRng,
+    themes: Vec<LevelTheme>,
 }
 
 impl LevelGenerator {
     pub fn new() -> LevelGenerator {
-        LevelGenerator { rng: rand::thread_rng()}
+        LevelGenerator::with_rng(StdRng::new().expect("Could not seed level generator."))
+    }
+
+    /// Generator which creates the same levels for the same seed.
+    pub fn with_seed(seed: usize) -> LevelGenerator {
+        let seed: &[usize] = &[seed];
+        LevelGenerator::with_rng(SeedableRng::from_seed(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> LevelGenerator {
+        LevelGenerator { rng, themes: create_themes(DEFAULT_ROWS, DEFAULT_COLS) }
     }
 
+    /// Restarts the generator, the same seed creates the same levels again.
+    pub fn reseed(&mut self, seed: usize) {
+        let seed: &[usize] = &[seed];
+        self.rng = SeedableRng::from_seed(seed);
+    }
+
+    /// Generates all following levels with given size, the boss level keeps its size.
+    pub fn set_level_size(&mut self, size_rows: usize, size_cols: usize) {
+        assert!(size_rows >= MIN_ROWS && size_cols >= MIN_COLS, "Level size {}x{} is too small.", size_rows, size_cols);
+
+        self.themes = create_themes(size_rows, size_cols);
+    }
+
+    /// Uses given algorithm for every depth before the themes.
+    #[cfg(test)]
+    pub fn try_first(&mut self, algorithm: Box<dyn LevelAlgorithm>) {
+        self.themes.insert(0, LevelTheme { from: 0, to: 9, algorithm });
+    }
+
+    /// Generates a level, which is validated and repaired or regenerated if needed.
     pub fn generate_level(&mut self, level: i32) -> Level {
         if level != 0 && level % 10 == 0 {
-            return generate_boss_level();
+            return generate_boss_level(level);
         }
 
-        let size_rows = 18;
-        let size_cols = 78;
-        let min_room_count = 3;
-        let max_room_count = 10;
+        let depth = level % 10;
+        let theme_index = self.themes.iter().position(|theme| theme.from <= depth && depth <= theme.to).expect("No theme for level.");
+
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            //The last theme is plain rooms, which never give up.
+            let mut new_level = match self.themes[theme_index].algorithm.generate(&mut self.rng, level) {
+                Option::Some(new_level) => new_level,
+                Option::None => self.themes.last_mut().expect("No themes.").algorithm.generate(&mut self.rng, level).expect("Rooms never give up."),
+            };
+
+            if new_level.validate().is_err() {
+                repair_level(&mut new_level);
+            }
 
-        let room_count = self.rng.gen_range(min_room_count, max_room_count);
+            if new_level.validate().is_ok() {
+                if self.rng.gen_range(0, NPC_CHANCE) == 0 {
+                    add_npc(&mut self.rng, &mut new_level);
+                }
+
+                return new_level;
+            }
+        }
+
+        panic!("Could not generate a valid level after {} attempts.", MAX_GENERATION_ATTEMPTS);
+    }
+
+    /// Carves a walled room into the rock next to the floor, with a single door.
+    /// Returns the tiles of its guardians, none if the level has no room for a vault.
+    pub fn add_vault(&mut self, level: &mut Level) -> Vec<(usize, usize)> {
+        let (height, width) = (VAULT_ROWS + 2, VAULT_COLS + 2);
+        let mut places = Vec::new();
+
+        for row in 0..level.map.len().saturating_sub(height - 1) {
+            for col in 0..level.map[row].len().saturating_sub(width - 1) {
+                let solid = (row..row + height).all(|rock_row| (col..col + width).all(|rock_col| {
+                    !level.is_walkable(rock_row, rock_col) && level.meta[rock_row][rock_col] == Tile::Nothing
+                }));
+
+                if solid {
+                    //Doors in the middle of a wall lead to the floor right outside.
+                    let doors = vec![
+                        ((row, col + width / 2), (row.wrapping_sub(1), col + width / 2)),
+                        ((row + height - 1, col + width / 2), (row + height, col + width / 2)),
+                        ((row + height / 2, col), (row + height / 2, col.wrapping_sub(1))),
+                        ((row + height / 2, col + width - 1), (row + height / 2, col + width)),
+                    ];
+
+                    places.extend(doors.into_iter().filter(|&(_, (out_row, out_col))| level.is_walkable(out_row, out_col)).map(|(door, _)| ((row, col), door)));
+                }
+            }
+        }
+
+        if places.is_empty() {
+            return Vec::new();
+        }
+
+        let ((row, col), (door_row, door_col)) = places[self.rng.gen_range(0, places.len())];
+        for vault_row in row..row + height {
+            for vault_col in col..col + width {
+                let inside = vault_row > row && vault_row < row + height - 1 && vault_col > col && vault_col < col + width - 1;
+                level.map[vault_row][vault_col] = if inside { Tile::Floor } else { Tile::Wall };
+            }
+        }
+        level.map[door_row][door_col] = Tile::Floor;
+
+        (col + 2..col + width - 2).map(|guardian_col| (row + height / 2, guardian_col)).collect()
+    }
+}
+
+fn create_themes(size_rows: usize, size_cols: usize) -> Vec<LevelTheme> {
+    //Bigger levels get more rooms.
+    let scale = std::cmp::max(1, (size_rows * size_cols) / (DEFAULT_ROWS * DEFAULT_COLS));
+
+    vec![
+        LevelTheme { from: 4, to: 6, algorithm: Box::new(CaveAlgorithm::new(size_rows, size_cols)) },
+        LevelTheme { from: 7, to: 7, algorithm: Box::new(BspAlgorithm::new(size_rows, size_cols, 8 * scale, 3, 8, CorridorStyle::LShaped)) },
+        LevelTheme { from: 8, to: 8, algorithm: Box::new(BspAlgorithm::new(size_rows, size_cols, 8 * scale, 3, 8, CorridorStyle::Straight)) },
+        LevelTheme { from: 9, to: 9, algorithm: Box::new(BspAlgorithm::new(size_rows, size_cols, 6 * scale, 3, 8, CorridorStyle::Winding)) },
+        LevelTheme { from: 0, to: 9, algorithm: Box::new(RoomAlgorithm::new(size_rows, size_cols)) },
+    ]
+}
+
+/// Rooms of random size, which are pulled to the middle room and connected with corridors.
+pub struct RoomAlgorithm {
+    size_rows: usize,
+    size_cols: usize,
+}
+
+impl RoomAlgorithm {
+    pub fn new(size_rows: usize, size_cols: usize) -> RoomAlgorithm {
+        RoomAlgorithm { size_rows, size_cols }
+    }
+}
+
+impl LevelAlgorithm for RoomAlgorithm {
+    fn generate(&mut self, rng: &mut StdRng, level: i32) -> Option<Level> {
+        let size_rows = self.size_rows;
+        let size_cols = self.size_cols;
+        let scale = std::cmp::max(1, (size_rows * size_cols) / (DEFAULT_ROWS * DEFAULT_COLS));
+        let min_room_count = 3 * scale;
+        let max_room_count = 10 * scale;
+
+        let room_count = rng.gen_range(min_room_count, max_room_count);
         let mut rooms: Vec<Room> = Vec::new();
 
         //Distribute rooms.
@@ -74,15 +238,15 @@ impl LevelGenerator {
         while room_counter < room_count {
             room_counter += 1;
 
-            let room_height = self.rng.gen_range(2, 7);
-            let room_width = self.rng.gen_range(2, 7);
+            let room_height = rng.gen_range(2, 7);
+            let room_width = rng.gen_range(2, 7);
 
             let mut room = Room {
                 width: room_width,
                 height: room_height,
 
-                row: self.rng.gen_range(room_height, size_rows - (room_height)),
-                col: self.rng.gen_range(room_width, size_cols - (room_width))
+                row: rng.gen_range(room_height, size_rows - (room_height)),
+                col: rng.gen_range(room_width, size_cols - (room_width))
             };
 
             let mut retries = 0;
@@ -104,8 +268,8 @@ impl LevelGenerator {
                     break;
                 }
 
-                room.row = self.rng.gen_range(room_height, size_rows - (room_height));
-                room.col = self.rng.gen_range(room_width, size_cols - (room_width));
+                room.row = rng.gen_range(room_height, size_rows - (room_height));
+                room.col = rng.gen_range(room_width, size_cols - (room_width));
             }
 
             if position_found {
@@ -202,136 +366,154 @@ impl LevelGenerator {
         connect_rooms(&mut rooms, &mut level);
 
         //set meta-information.
-        self.add_meta_information(&rooms, &mut level);
+        add_meta_information(rng, &rooms, &mut level);
 
-        level
+        Option::Some(level)
     }
+}
 
-    fn add_meta_information(&mut self, rooms: &Vec<Room>, level: &mut Level) {
-        //First Room is the Start.
-        level.meta[rooms[0].row][rooms[0].col] = Tile::PlSpawn;
+fn add_meta_information(rng: &mut StdRng, rooms: &[Room], level: &mut Level) {
+    //First Room is the Start.
+    level.meta[rooms[0].row][rooms[0].col] = Tile::PlSpawn;
 
-        for index in 1..rooms.len() - 1 {
-            let spawn_chance = self.rng.gen_range(1, 11);
+    for index in 1..rooms.len() - 1 {
+        let spawn_chance = rng.gen_range(1, 11);
 
-            if spawn_chance <= 9 {
-                self.add_monster(&rooms[index], level);
-            }
+        if spawn_chance <= 9 {
+            add_monster(rng, rooms[index].row, rooms[index].col, level);
         }
+    }
+
+    level.meta[rooms[rooms.len() - 1].row][rooms[rooms.len() - 1].col] = Tile::Next;
+}
+
+/// Places a friendly entity on a random free tile in the open, so it never blocks a corridor.
+fn add_npc(rng: &mut StdRng, level: &mut Level) {
+    for _ in 0..NPC_ATTEMPTS {
+        let row = rng.gen_range(1, level.map.len() - 1);
+        let col = rng.gen_range(1, level.map[row].len() - 1);
+
+        let open = (row - 1..row + 2).all(|around_row| (col - 1..col + 2).all(|around_col| {
+            level.is_walkable(around_row, around_col) && level.meta[around_row][around_col] == Tile::Nothing
+        }));
 
-        level.meta[rooms[rooms.len() - 1].row][rooms[rooms.len() - 1].col] = Tile::Next;
+        if open {
+            level.meta[row][col] = Tile::NpcSpawn { npc: rng.gen_range(0, 1000) };
+            return;
+        }
     }
+}
 
-    fn add_monster(&mut self, room: &Room, level: &mut Level) {
-        //1 Easy-Many
-        //2 Normal-Few Easy-Few
-        //3 Hard-One, Normal-Few, Easy-Few
+/// Places a group of monsters around given center.
+pub fn add_monster(rng: &mut StdRng, center_row: usize, center_col: usize, level: &mut Level) {
+    //1 Easy-Many
+    //2 Normal-Few Easy-Few
+    //3 Hard-One, Normal-Few, Easy-Few
 
-        match self.rng.gen_range(1, 4) {
-            1 => {
-                let monster_type = self.rng.gen_range(1, 4);
+    match rng.gen_range(1, 4) {
+        1 => {
+            let monster_type = rng.gen_range(1, 4);
 
-                let row = room.row;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
-                }
+            let row = center_row;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
+            }
 
-                let row = room.row + 1;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
-                }
+            let row = center_row + 1;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
+            }
 
-                let row = room.row - 1;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
-                }
+            let row = center_row - 1;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
+            }
 
-                let row = room.row;
-                let col = room.col + 1;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
-                }
+            let row = center_row;
+            let col = center_col + 1;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
+            }
 
-                let row = room.row;
-                let col = room.col - 1;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
-                }
-            },
-            2 => {
-                let easy_monster_type = self.rng.gen_range(1, 4);
-                let normal_monster_type = self.rng.gen_range(1, 4);
-
-                let row = room.row;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
-                }
+            let row = center_row;
+            let col = center_col - 1;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: monster_type };
+            }
+        },
+        2 => {
+            let easy_monster_type = rng.gen_range(1, 4);
+            let normal_monster_type = rng.gen_range(1, 4);
+
+            let row = center_row;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
+            }
 
-                let row = room.row + 1;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
-                }
+            let row = center_row + 1;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
+            }
 
-                let row = room.row - 1;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
-                }
+            let row = center_row - 1;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
+            }
 
-                let row = room.row;
-                let col = room.col + 1;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
-                }
+            let row = center_row;
+            let col = center_col + 1;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
+            }
 
-                let row = room.row;
-                let col = room.col - 1;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
-                }
-            },
-            3 => {
-                let easy_monster_type = self.rng.gen_range(1, 4);
-                let normal_monster_type = self.rng.gen_range(1, 4);
-                let hard_monster_type = self.rng.gen_range(1, 4);
-
-                let row = room.row;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 3, mn_type: hard_monster_type };
-                }
+            let row = center_row;
+            let col = center_col - 1;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
+            }
+        },
+        3 => {
+            let easy_monster_type = rng.gen_range(1, 4);
+            let normal_monster_type = rng.gen_range(1, 4);
+            let hard_monster_type = rng.gen_range(1, 4);
+
+            let row = center_row;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 3, mn_type: hard_monster_type };
+            }
 
-                let row = room.row + 1;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
-                }
+            let row = center_row + 1;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
+            }
 
-                let row = room.row - 1;
-                let col = room.col;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
-                }
+            let row = center_row - 1;
+            let col = center_col;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 2, mn_type: normal_monster_type };
+            }
 
-                let row = room.row;
-                let col = room.col + 1;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
-                }
+            let row = center_row;
+            let col = center_col + 1;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
+            }
 
-                let row = room.row;
-                let col = room.col - 1;
-                if inside_level(row, col, level) {
-                    level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
-                }
-            },
-            _ => {},
-        }
+            let row = center_row;
+            let col = center_col - 1;
+            if inside_level(row, col, level) {
+                level.meta[row][col] = Tile::MnSpawn { difficulty: 1, mn_type: easy_monster_type };
+            }
+        },
+        _ => {},
     }
 }
 
@@ -339,6 +521,92 @@ fn inside_level(row: usize, col: usize, level: &Level) -> bool {
     level.map[row][col] == Tile::Floor
 }
 
+/// Connects unreachable floor to the reachable part and removes misplaced monster spawns.
+/// Missing spawn or stairs can not be repaired, such levels have to be regenerated.
+fn repair_level(level: &mut Level) {
+    let spawn = match level.find_meta(Tile::PlSpawn) {
+        Option::Some(value) => value,
+        Option::None => return,
+    };
+
+    if !level.is_walkable(spawn.0, spawn.1) {
+        return;
+    }
+
+    for row in 0..level.meta.len() {
+        for col in 0..level.meta[row].len() {
+            if let Tile::MnSpawn { .. } | Tile::NpcSpawn { .. } = level.meta[row][col] {
+                if !level.is_walkable(row, col) {
+                    level.meta[row][col] = Tile::Nothing;
+                }
+            }
+        }
+    }
+
+    loop {
+        let distances = level.distance_map(spawn.0, spawn.1);
+        let unreachable = find_unreachable_floor(level, &distances);
+
+        let (row, col) = match unreachable {
+            Option::Some(value) => value,
+            Option::None => break,
+        };
+
+        let target = nearest_reachable(&distances, row, col);
+        carve_corridor(level, (row, col), target);
+    }
+
+    assure_walls_everywhere(level);
+}
+
+fn find_unreachable_floor(level: &Level, distances: &[Vec<Option<u32>>]) -> Option<(usize, usize)> {
+    for (row, map_row) in level.map.iter().enumerate() {
+        for (col, tile) in map_row.iter().enumerate() {
+            if *tile == Tile::Floor && distances[row][col].is_none() {
+                return Option::Some((row, col));
+            }
+        }
+    }
+
+    Option::None
+}
+
+fn nearest_reachable(distances: &[Vec<Option<u32>>], row: usize, col: usize) -> (usize, usize) {
+    let mut nearest = (row, col);
+    let mut min_distance = usize::MAX;
+
+    for (other_row, distance_row) in distances.iter().enumerate() {
+        for (other_col, distance) in distance_row.iter().enumerate() {
+            if distance.is_none() {
+                continue;
+            }
+
+            let current_distance = row.abs_diff(other_row) + col.abs_diff(other_col);
+            if current_distance < min_distance {
+                nearest = (other_row, other_col);
+                min_distance = current_distance;
+            }
+        }
+    }
+
+    nearest
+}
+
+/// Digs a L-shaped corridor, first along the row then along the column.
+fn carve_corridor(level: &mut Level, from: (usize, usize), to: (usize, usize)) {
+    let (mut row, mut col) = from;
+
+    while col != to.1 {
+        col = if col < to.1 { col + 1 } else { col - 1 };
+        level.map[row][col] = Tile::Floor;
+    }
+
+    while row != to.0 {
+        row = if row < to.0 { row + 1 } else { row - 1 };
+        level.map[row][col] = Tile::Floor;
+    }
+}
+
 fn connect_rooms(rooms: &mut Vec<Room>, level: &mut Level) {
     for current_room_index in 0..rooms.len() {
         let mut first_smallest_distance = std::usize::MAX;
@@ -377,7 +645,7 @@ fn connect_rooms(rooms: &mut Vec<Room>, level: &mut Level) {
     assure_walls_everywhere(level);
 }
 
-fn assure_walls_everywhere(level: &mut Level) {
+pub fn assure_walls_everywhere(level: &mut Level) {
     for row in 1..level.map.len() - 1 {
         for col in 1..level.map[row].len() - 1 {
             if level.map[row][col] == Tile::Floor {
@@ -404,6 +672,14 @@ fn assure_walls_everywhere(level: &mut Level) {
                 if level.map[row + 1][col + 1] == Tile::Nothing {
                     level.map[row + 1][col + 1] = Tile::Wall;
                 }
+
+                if level.map[row - 1][col + 1] == Tile::Nothing {
+                    level.map[row - 1][col + 1] = Tile::Wall;
+                }
+
+                if level.map[row + 1][col - 1] == Tile::Nothing {
+                    level.map[row + 1][col - 1] = Tile::Wall;
+                }
             }
         }
     }
@@ -452,7 +728,7 @@ fn connect(level: &mut Level, room_a: &Room, room_b: &Room) {
 }
 
 fn create_level(rooms: &Vec<Room>, size_rows: usize, size_cols: usize, level : i32) -> Level {
-    let mut level = Level { map: Vec::new(), meta: Vec::new(), level : level};
+    let mut level = Level { map: Vec::new(), meta: Vec::new(), level : level, explored: Vec::new() };
     //Create empty map.
     for row in 0..size_rows {
         level.map.push(Vec::new());
@@ -510,7 +786,7 @@ fn create_level(rooms: &Vec<Room>, size_rows: usize, size_cols: usize, level : i
     level
 }
 
-fn generate_boss_level() -> Level {
+fn generate_boss_level(level: i32) -> Level {
     let mut rows = Vec::new();
 
     let mut str_map = Vec::new();
@@ -585,7 +861,132 @@ fn generate_boss_level() -> Level {
         meta.push(row);
     }
 
-    Level { map: rows, meta: meta, level: 0 }
+    Level { map: rows, meta: meta, level: level, explored: Vec::new() }
+}
+
+#[test]
+fn test_generated_levels_are_valid() {
+    for seed in 0..2000 {
+        let mut generator = LevelGenerator::with_seed(seed);
+
+        for level in 0..3 {
+            let new_level = generator.generate_level(level);
+
+            assert_eq!(Result::Ok(()), new_level.validate(), "seed {} level {}", seed, level);
+        }
+    }
+}
+
+#[test]
+fn test_generated_caves_are_valid() {
+    for seed in 0..300 {
+        let mut generator = LevelGenerator::with_seed(seed);
+
+        for level in 4..7 {
+            let new_level = generator.generate_level(level);
+
+            assert_eq!(Result::Ok(()), new_level.validate(), "seed {} level {}", seed, level);
+        }
+    }
+}
+
+#[test]
+fn test_reseed_repeats_levels() {
+    let mut generator = LevelGenerator::with_seed(42);
+    let first = generator.generate_level(1);
+
+    generator.generate_level(2);
+    generator.reseed(42);
+
+    assert!(first.map == generator.generate_level(1).map);
+}
+
+#[test]
+fn test_generated_levels_have_given_size() {
+    for &(size_rows, size_cols) in &[(MIN_ROWS, MIN_COLS), (40, 120)] {
+        for seed in 0..20 {
+            let mut generator = LevelGenerator::with_seed(seed);
+            generator.set_level_size(size_rows, size_cols);
+
+            for level in 0..10 {
+                let new_level = generator.generate_level(level);
+
+                assert_eq!(size_rows, new_level.map.len(), "seed {} level {}", seed, level);
+                assert_eq!(size_cols, new_level.map[0].len(), "seed {} level {}", seed, level);
+                assert_eq!(Result::Ok(()), new_level.validate(), "seed {} level {}", seed, level);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_npcs_stand_in_the_open() {
+    let mut npcs = 0;
+
+    for seed in 0..100 {
+        let mut generator = LevelGenerator::with_seed(seed);
+        let level = generator.generate_level(1);
+
+        for (row, meta_row) in level.meta.iter().enumerate() {
+            for (col, meta) in meta_row.iter().enumerate() {
+                if let Tile::NpcSpawn { .. } = *meta {
+                    npcs += 1;
+                    assert!(neighbours(row, col).iter().all(|&(row, col)| level.is_walkable(row, col)), "seed {}", seed);
+                }
+            }
+        }
+    }
+
+    assert!(npcs > 10 && npcs < 70, "{}", npcs);
+}
+
+#[test]
+fn test_vaults_are_walled_rooms() {
+    let mut vaults = 0;
+
+    for seed in 0..100 {
+        let mut generator = LevelGenerator::with_seed(seed);
+        let mut level = generator.generate_level(4 + seed as i32 % 5);
+        let floor_before = level.map.iter().flatten().filter(|tile| **tile == Tile::Floor).count();
+
+        let guardians = generator.add_vault(&mut level);
+        if guardians.is_empty() {
+            continue;
+        }
+        vaults += 1;
+
+        //The vault adds its room and door and stays reachable.
+        assert_eq!(3, guardians.len());
+        assert_eq!(floor_before + VAULT_ROWS * VAULT_COLS + 1, level.map.iter().flatten().filter(|tile| **tile == Tile::Floor).count(), "seed {}", seed);
+        assert_eq!(Result::Ok(()), level.validate());
+
+        let (row, col) = level.find_meta(Tile::PlSpawn).unwrap();
+        let distances = level.distance_map(row, col);
+        assert!(guardians.iter().all(|&(row, col)| level.is_walkable(row, col) && distances[row][col].is_some()), "seed {}", seed);
+    }
+
+    assert!(vaults > 90, "{}", vaults);
+}
+
+#[test]
+fn test_boss_level_is_valid() {
+    assert_eq!(Result::Ok(()), generate_boss_level(10).validate());
+}
+
+#[test]
+fn test_repair_connects_floor() {
+    let mut level = Level::new();
+    level.meta[4][5] = Tile::Next;
+
+    //Split room in two halves.
+    for row in 2..7 {
+        level.map[row][4] = Tile::Wall;
+    }
+    assert!(level.validate().is_err());
+
+    repair_level(&mut level);
+
+    assert_eq!(Result::Ok(()), level.validate());
 }
 
 #[test]
@@ -596,6 +997,14 @@ fn test_intersect() {
     assert!(a.intersect(&b));
 }
 
+#[test]
+fn test_intersect_near_edge() {
+    let a = Room { row: 1, col: 1, height: 3, width: 3 };
+    let b = Room { row: 2, col: 0, height: 2, width: 2 };
+
+    assert!(a.intersect(&b));
+}
+
 #[test]
 fn test_not_intersect() {
     let a = Room { row: 20, col: 10, height: 5, width: 10 };
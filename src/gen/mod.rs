@@ -1,3 +1,5 @@
 pub mod level;
 pub mod monster;
-pub mod item;
\ No newline at end of file
+pub mod item;
+pub mod cave;
+pub mod bsp;
@@ -1,6 +1,4 @@
-extern crate rand;
-
-use rand::Rng;
+use super::super::rng;
 use super::super::character::entity::*;
 use super::super::character::item::*;
 use super::super::character::stats::*;
@@ -60,7 +58,7 @@ pub fn create_monster(player: &Entity, mut monster: &mut Entity, mn_type: u32, d
 
     monster.current_life = monster.calculate_max_life();
 
-    let weapon_drop = rand::thread_rng().gen_range(0, 101);
+    let weapon_drop = rng::gen_range(0, 101);
     if weapon_drop <= 10 {
         let new_item = generate_item(Type::Weapon, &player.weapon, &monster.monster_difficulty);
 
@@ -69,7 +67,7 @@ pub fn create_monster(player: &Entity, mut monster: &mut Entity, mn_type: u32, d
         }
     }
 
-    let head_drop = rand::thread_rng().gen_range(0, 101);
+    let head_drop = rng::gen_range(0, 101);
     if head_drop <= 10 {
         let new_item = generate_item(Type::Head, &player.head_item, &monster.monster_difficulty);
 
@@ -78,7 +76,7 @@ pub fn create_monster(player: &Entity, mut monster: &mut Entity, mn_type: u32, d
         }
     }
 
-    let chest_drop = rand::thread_rng().gen_range(0, 101);
+    let chest_drop = rng::gen_range(0, 101);
     if chest_drop <= 10 {
         let new_item = generate_item(Type::Chest, &player.chest_item, &monster.monster_difficulty);
 
@@ -87,7 +85,7 @@ pub fn create_monster(player: &Entity, mut monster: &mut Entity, mn_type: u32, d
         }
     }
 
-    let legs_drop = rand::thread_rng().gen_range(0, 101);
+    let legs_drop = rng::gen_range(0, 101);
     if legs_drop <= 10 {
         let new_item = generate_item(Type::Legs, &player.leg_item, &monster.monster_difficulty);
 
@@ -96,22 +94,73 @@ pub fn create_monster(player: &Entity, mut monster: &mut Entity, mn_type: u32, d
         }
     }
 
-    let potion_drop = rand::thread_rng().gen_range(0, 101);
+    let potion_drop = rng::gen_range(0, 101);
     if potion_drop <= 10 {
-        let healing_percentage = match monster.monster_difficulty {
-            Difficulty::Easy => 10,
-            Difficulty::Normal => 25,
-            Difficulty::Hard => 50,
-            Difficulty::Boss => 100,
-        };
-        let mut potion = Item { name: "Healing Potion".to_string(), item_type: Type::Potion, modifications: Vec::new() };
-
-        potion.modifications.push(StatsMod::Heal(healing_percentage));
+        let potion = generate_potion(&monster.monster_difficulty);
 
         match monster.backpack.add_item(potion) {
             _ => { /*I don't care.*/ }
         }
     }
+
+    let scroll_drop = rng::gen_range(0, 101);
+    if scroll_drop <= 5 {
+        //A full backpack simply drops nothing.
+        let _ = monster.backpack.add_item(generate_scroll(&monster.monster_difficulty));
+    }
+
+    let note_drop = rng::gen_range(0, 101);
+    if note_drop <= 3 {
+        let _ = monster.backpack.add_item(generate_note());
+    }
+}
+
+/// Pets fight with their teeth until they are given a weapon, their stats follow the player.
+pub fn create_companion(player: &Entity, companion: &mut Entity, name: &str) {
+    companion.name = name.to_string();
+    companion.monster_type = MonsterType::Pet;
+    companion.faction = Faction::Allied;
+    companion.order = Order::Follow;
+
+    let player_stats = player.base_stats;
+    companion.base_stats = Stats {
+        vitality: player_stats.vitality / 2,
+        strength: player_stats.strength,
+        speed: player_stats.speed,
+        defense: player_stats.defense / 2,
+        intelligence: 0,
+    };
+
+    companion.current_life = companion.calculate_max_life();
+}
+
+/// Friendly entities are as strong as a normal monster once they fight,
+/// they carry a piece of gear for the player.
+pub fn create_npc(player: &Entity, npc: &mut Entity, name: &str) {
+    let player_stats = player.calculate_stats();
+    let player_damage = player.weapon.get_damage();
+    let mean_damage = ((player_damage.0 + player_damage.1) / 2) as f32;
+
+    calculate_monster_stats(npc, player_stats, mean_damage, 1.1f32, 1.0f32, 0.9f32);
+
+    npc.name = name.to_string();
+    npc.monster_type = MonsterType::Npc;
+    npc.monster_difficulty = Difficulty::Normal;
+    npc.faction = Faction::Neutral;
+    npc.current_life = npc.calculate_max_life();
+
+    //A full backpack simply drops nothing.
+    let _ = npc.backpack.add_item(generate_gear(player, &Difficulty::Normal));
+}
+
+/// Random head, chest, leg or weapon item, which is compared to the gear of the player.
+pub fn generate_gear(player: &Entity, difficulty: &Difficulty) -> Item {
+    match rng::gen_range(0, 4) {
+        0 => generate_item(Type::Head, &player.head_item, difficulty),
+        1 => generate_item(Type::Chest, &player.chest_item, difficulty),
+        2 => generate_item(Type::Legs, &player.leg_item, difficulty),
+        _ => generate_item(Type::Weapon, &player.weapon, difficulty),
+    }
 }
 
 fn calculate_monster_stats(monster: &mut Entity, player_stats : Stats, mean_damage : f32, vitality :f32, strength : f32, defense : f32) {
@@ -0,0 +1,124 @@
+use super::character::entity::*;
+use super::level::{Level, Tile};
+
+/// Map characters, shared by every frontend and the morgue.
+pub fn resolve_tile(tile: &Tile) -> char {
+    match tile {
+        &Tile::Floor => '.',
+        &Tile::Wall => '#',
+        &Tile::Nothing => ' ',
+        &Tile::PlSpawn => '<',
+        &Tile::MnSpawn { .. } | &Tile::NpcSpawn { .. } => '?',
+        &Tile::Next => '>',
+    }
+}
+
+pub fn resolve_player(player: &Entity) -> char {
+    if player.is_death() {
+        '_'
+    } else {
+        '@'
+    }
+}
+
+pub fn resolve_enemy(enemy: &Entity) -> char {
+    if enemy.is_death() && enemy.backpack.size() > 0 {
+        'O'
+    } else if enemy.is_death() {
+        '_'
+    } else {
+        match enemy.monster_type {
+            MonsterType::Unknown => {
+                '?'
+            },
+            MonsterType::Pet => {
+                'd'
+            },
+            MonsterType::Npc => {
+                '&'
+            },
+            MonsterType::Zombie => {
+                match enemy.monster_difficulty {
+                    Difficulty::Easy => {
+                        'f'
+                    },
+                    Difficulty::Normal => {
+                        'F'
+                    },
+                    Difficulty::Hard => {
+                        'Z'
+                    },
+                    _ => {
+                        unreachable!();
+                    }
+                }
+            },
+            MonsterType::Crab => {
+                match enemy.monster_difficulty {
+                    Difficulty::Easy => {
+                        'm'
+                    },
+                    Difficulty::Normal => {
+                        'm'
+                    },
+                    Difficulty::Hard => {
+                        'M'
+                    },
+                    _ => {
+                        unreachable!();
+                    }
+                }
+            },
+            MonsterType::Goblin => {
+                match enemy.monster_difficulty {
+                    Difficulty::Easy => {
+                        'x'
+                    },
+                    Difficulty::Normal => {
+                        'X'
+                    },
+                    Difficulty::Hard => {
+                        'G'
+                    },
+                    _ => {
+                        unreachable!();
+                    }
+                }
+            }
+            _ => {
+                unreachable!();
+            }
+        }
+    }
+}
+
+/// The level as drawn on screen, with corpses, monsters and the player on top.
+pub fn render_map(level: &Level, player: &Entity, enemies: &[Entity]) -> Vec<String> {
+    let mut map: Vec<Vec<char>> = level.map.iter().enumerate().map(|(row_index, row)| {
+        row.iter().enumerate().map(|(col_index, tile)| match level.meta[row_index][col_index] {
+            Tile::PlSpawn | Tile::Next => resolve_tile(&level.meta[row_index][col_index]),
+            _ => resolve_tile(tile),
+        }).collect()
+    }).collect();
+
+    let mut put = |row: i32, col: i32, glyph: char| {
+        if row >= 0 && col >= 0 && (row as usize) < map.len() && (col as usize) < map[row as usize].len() {
+            map[row as usize][col as usize] = glyph;
+        }
+    };
+
+    let dead = enemies.iter().filter(|enemy| enemy.is_death());
+    let alive = enemies.iter().filter(|enemy| !enemy.is_death());
+
+    for enemy in dead.chain(alive) {
+        if enemy.monster_type == MonsterType::Boss && !enemy.is_death() {
+            put(enemy.pos_row, enemy.pos_col, 'O');
+        } else if enemy.monster_type != MonsterType::Boss {
+            put(enemy.pos_row, enemy.pos_col, resolve_enemy(enemy));
+        }
+    }
+
+    put(player.pos_row, player.pos_col, resolve_player(player));
+
+    map.iter().map(|row| row.iter().collect()).collect()
+}
@@ -0,0 +1,327 @@
+use super::character::class::Class;
+use super::character::entity::{Entity, MonsterType, Difficulty, Faction};
+use super::character::item::Type;
+use super::event::Event;
+use super::game::{Game, Action};
+use super::input::Input;
+use super::level::Tile;
+
+/// Reward for every point of damage the player deals.
+pub const DAMAGE_REWARD: f32 = 0.01;
+/// Reward for every monster the player kills.
+pub const KILL_REWARD: f32 = 1.0;
+/// Reward for every level which is deeper than all before.
+pub const DEPTH_REWARD: f32 = 5.0;
+/// Reward, or rather penalty, for dying.
+pub const DEATH_REWARD: f32 = -10.0;
+
+/// Codes of the tiles in an observation.
+pub const TILE_NOTHING: u8 = 0;
+pub const TILE_FLOOR: u8 = 1;
+pub const TILE_WALL: u8 = 2;
+pub const TILE_UP: u8 = 3;
+pub const TILE_DOWN: u8 = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+pub const DIRECTIONS: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+/// Everything an agent can do in one step.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Command {
+    Wait,
+    Move(Direction),
+    Attack(Direction),
+    /// Slot 0, 1 or 2 of the class specials, directed specials use the direction.
+    Special(usize, Direction),
+    /// Loots everything on a corpse, or takes the stairs.
+    Use,
+    /// Drinks a potion or equips an item of the backpack.
+    UseItem(usize),
+}
+
+/// One entity as the agent sees it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityObservation {
+    pub row: i32,
+    pub col: i32,
+    pub life: i32,
+    pub max_life: i32,
+    pub monster_type: MonsterType,
+    pub difficulty: Difficulty,
+    /// Items which can be looted.
+    pub loot: usize,
+}
+
+/// The visible state after a reset or step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub rows: usize,
+    pub cols: usize,
+    /// Tile codes row by row, stairs overlay the floor.
+    pub tiles: Vec<u8>,
+    pub player: EntityObservation,
+    /// Dead enemies are kept as corpses with their loot.
+    pub enemies: Vec<EntityObservation>,
+    /// Pets of the player, they follow and fight on their own.
+    pub companions: Vec<EntityObservation>,
+    /// Friendly people, they block the way and talk when used next to them.
+    pub npcs: Vec<EntityObservation>,
+    /// Turns until the specials of slot 0, 1 and 2 are ready again, 0 means ready.
+    pub cool_downs: [u32; 3],
+    /// Energy of the player, specials cost energy.
+    pub energy: i32,
+    /// Item types in the backpack, in backpack order.
+    pub backpack: Vec<Type>,
+    pub depth: i32,
+    pub turn: u32,
+}
+
+/// Outcome of one step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub observation: Observation,
+    pub reward: f32,
+    /// The player is dead, only a reset starts over.
+    pub done: bool,
+}
+
+/// Headless game for agents, the same seed and commands always give the same steps.
+pub struct Environment {
+    game: Game,
+    class: Class,
+    deepest: i32,
+}
+
+impl Environment {
+    pub fn new(class: Class) -> Environment {
+        let mut game = Game::new();
+        game.set_persistent(false);
+
+        Environment { game, class, deepest: 0 }
+    }
+
+    /// Size of all generated levels except the boss level.
+    pub fn set_level_size(&mut self, size_rows: usize, size_cols: usize) {
+        self.game.set_level_size(size_rows, size_cols);
+    }
+
+    pub fn reset(&mut self, seed: usize) -> Observation {
+        self.game.new_game("Agent".to_string(), self.class, seed);
+        self.deepest = 0;
+
+        self.observe()
+    }
+
+    pub fn step(&mut self, command: Command) -> Step {
+        if self.done() {
+            return Step { observation: self.observe(), reward: 0.0, done: true };
+        }
+
+        let events = match command {
+            Command::Wait => self.game.step(Input::Nothing),
+            Command::Move(direction) => self.game.step(move_input(direction)),
+            Command::Attack(direction) => self.game.step(attack_input(direction)),
+            Command::Special(slot, direction) => {
+                let special = match slot {
+                    0 => Input::SpecialOne,
+                    1 => Input::SpecialTwo,
+                    _ => Input::SpecialThree,
+                };
+
+                //Specials which cool down are not aimed.
+                let mut events = self.game.step(special);
+                if self.game.state() == Action::Target {
+                    events.extend(self.game.step(attack_input(direction)));
+                }
+
+                events
+            },
+            Command::Use => self.use_here(),
+            Command::UseItem(index) => self.game.step(Input::UseItem(index)),
+        };
+
+        let reward = self.reward(&events);
+
+        Step { observation: self.observe(), reward, done: self.done() }
+    }
+
+    /// The wrapped game, e.g. for drawing or the log.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn done(&self) -> bool {
+        self.game.player().is_death()
+    }
+
+    pub fn observe(&self) -> Observation {
+        let level = self.game.map();
+        let rows = level.map.len();
+        let cols = level.map.first().map_or(0, |row| row.len());
+
+        let mut tiles = Vec::with_capacity(rows * cols);
+        for (row, meta_row) in level.map.iter().zip(level.meta.iter()) {
+            for (tile, meta) in row.iter().zip(meta_row.iter()) {
+                tiles.push(match (*meta, *tile) {
+                    (Tile::PlSpawn, _) => TILE_UP,
+                    (Tile::Next, _) => TILE_DOWN,
+                    (_, Tile::Wall) => TILE_WALL,
+                    (_, Tile::Nothing) => TILE_NOTHING,
+                    _ => TILE_FLOOR,
+                });
+            }
+        }
+
+        let player = self.game.player();
+        let mut cool_downs = [0; 3];
+        for (slot, special) in self.game.specials().iter().enumerate() {
+            cool_downs[slot] = self.game.cool_down(*special);
+        }
+
+        Observation {
+            rows,
+            cols,
+            tiles,
+            player: observe_entity(player),
+            enemies: self.game.enemies().iter().filter(|entity| entity.faction == Faction::Hostile).map(observe_entity).collect(),
+            companions: self.game.enemies().iter().filter(|entity| entity.faction == Faction::Allied).map(observe_entity).collect(),
+            npcs: self.game.enemies().iter().filter(|entity| entity.faction == Faction::Neutral).map(observe_entity).collect(),
+            cool_downs,
+            energy: player.current_energy,
+            backpack: player.backpack.items.iter().take(player.backpack.size()).map(|item| item.item_type).collect(),
+            depth: level.level,
+            turn: self.game.log().turn(),
+        }
+    }
+
+    fn reward(&mut self, events: &[Event]) -> f32 {
+        let player_id = self.game.player().id;
+        let mut reward = 0.0;
+
+        for event in events {
+            match *event {
+                Event::Hit { ref attacker, damage, .. } | Event::Crit { ref attacker, damage, .. } if attacker.id == player_id => {
+                    reward += DAMAGE_REWARD * damage as f32;
+                },
+                Event::Kill { ref attacker, .. } if attacker.id == player_id => {
+                    reward += KILL_REWARD;
+                },
+                Event::Kill { ref target, .. } if target.id == player_id => {
+                    reward += DEATH_REWARD;
+                },
+                Event::LevelEntered { depth, .. } if depth > self.deepest => {
+                    reward += DEPTH_REWARD * (depth - self.deepest) as f32;
+                    self.deepest = depth;
+                },
+                _ => {},
+            }
+        }
+
+        reward
+    }
+
+    /// Takes every item of a corpse which fits, other tiles behave like in the game.
+    fn use_here(&mut self) -> Vec<Event> {
+        let mut events = self.game.step(Input::Use);
+
+        match self.game.state() {
+            Action::Loot => {
+                let (row, col) = (self.game.player().pos_row, self.game.player().pos_col);
+                let loot: usize = self.game.enemies().iter()
+                    .filter(|enemy| enemy.pos_row == row && enemy.pos_col == col)
+                    .map(|enemy| enemy.backpack.size())
+                    .sum();
+
+                for _ in 0..loot {
+                    events.extend(self.game.step(Input::Use));
+                }
+                events.extend(self.game.step(Input::Quit));
+            },
+            //Standing on the floor opens the inventory, next to friendly people a dialogue.
+            Action::Inventory | Action::Dialogue => events.extend(self.game.step(Input::Quit)),
+            _ => {},
+        }
+
+        events
+    }
+}
+
+fn move_input(direction: Direction) -> Input {
+    match direction {
+        Direction::North => Input::MoveUp,
+        Direction::East => Input::MoveRight,
+        Direction::South => Input::MoveDown,
+        Direction::West => Input::MoveLeft,
+    }
+}
+
+fn attack_input(direction: Direction) -> Input {
+    match direction {
+        Direction::North => Input::AttackUp,
+        Direction::East => Input::AttackRight,
+        Direction::South => Input::AttackDown,
+        Direction::West => Input::AttackLeft,
+    }
+}
+
+fn observe_entity(entity: &Entity) -> EntityObservation {
+    EntityObservation {
+        row: entity.pos_row,
+        col: entity.pos_col,
+        life: entity.current_life.max(0),
+        max_life: entity.calculate_max_life(),
+        monster_type: entity.monster_type,
+        difficulty: entity.monster_difficulty,
+        loot: entity.backpack.size(),
+    }
+}
+
+#[cfg(test)]
+fn rollout(seed: usize, steps: usize) -> Vec<Step> {
+    let mut environment = Environment::new(Class::Warrior);
+    environment.reset(seed);
+
+    let commands = [
+        Command::Move(Direction::North), Command::Attack(Direction::East), Command::Move(Direction::East),
+        Command::Special(0, Direction::South), Command::Move(Direction::South), Command::Use,
+        Command::Move(Direction::West), Command::Special(2, Direction::North), Command::UseItem(0), Command::Wait,
+    ];
+
+    (0..steps).map(|step| environment.step(commands[step * 7 % commands.len()])).collect()
+}
+
+#[test]
+fn test_environment_deterministic() {
+    let first = rollout(42, 300);
+    let second = rollout(42, 300);
+
+    assert!(first == second);
+    assert!(rollout(43, 300) != first);
+}
+
+#[test]
+fn test_environment_observation() {
+    let mut environment = Environment::new(Class::Ranger);
+    let observation = environment.reset(7);
+
+    assert_eq!(observation.rows * observation.cols, observation.tiles.len());
+    assert_eq!(TILE_UP, observation.tiles[observation.player.row as usize * observation.cols + observation.player.col as usize]);
+    assert_eq!(observation.player.max_life, observation.player.life);
+    assert!(!observation.enemies.is_empty());
+    assert_eq!(1, observation.companions.len());
+    assert_eq!([0, 0, 0], observation.cool_downs);
+    assert_eq!(0, observation.depth);
+
+    //Volley needs no direction and goes on cooldown right away.
+    let step = environment.step(Command::Special(1, Direction::North));
+    assert!(step.observation.cool_downs[1] > 0);
+    assert_eq!(1, step.observation.turn);
+    assert!(!step.done);
+}
@@ -0,0 +1,57 @@
+/// What the player wants to do, frontends turn keys or commands into it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Input {
+    Nothing,
+
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+
+    Quit,
+    Use,
+    Drop,
+
+    SpecialOne,
+    SpecialTwo,
+    SpecialThree,
+
+    History,
+
+    /// Walks to the nearest unexplored place.
+    Explore,
+    /// Walks to the stairs down.
+    Travel,
+    /// Walks to given row and column.
+    TravelTo(usize, usize),
+    /// Describes what is on the map.
+    Look,
+    /// Aims the special at the next monster.
+    NextTarget,
+    /// Binds specials and spells to the slots.
+    Spellbook,
+    /// Lists the open and completed quests.
+    Quests,
+    /// Tells the companions to stay or to follow.
+    Order,
+    /// Hands the selected item to a companion.
+    Give,
+    /// Uses the backpack item at given index, without opening the inventory.
+    UseItem(usize),
+    /// Drops the backpack item at given index.
+    DropItem(usize),
+    /// Hands the backpack item at given index to a companion.
+    GiveItem(usize),
+
+    /// The console has been resized.
+    Resize,
+
+    /// Typed characters, only while entering text.
+    Char(char),
+    Backspace,
+
+    AttackUp,
+    AttackDown,
+    AttackLeft,
+    AttackRight,
+}
@@ -1,8 +1,12 @@
 use super::level::*;
 use super::character::entity::*;
 use super::effect::{WeaponHit, Effect, AttackDirection};
-use super::ui::*;
+use super::input::Input;
 
+/// Companions which follow stay this close to the player.
+const FOLLOW_DISTANCE: f32 = 2f32;
+
+/// Monsters go for the player or a companion, companions for the monsters.
 pub fn handle_ki(map: &Level, player: &mut Entity, enemies: &mut Vec<Entity>, effects: &mut Vec<Box<Effect>>) {
     let size = enemies.len();
     for index in 0..size {
@@ -10,13 +14,18 @@ pub fn handle_ki(map: &Level, player: &mut Entity, enemies: &mut Vec<Entity>, ef
             continue;
         }
 
-        let row_diff = player.pos_row - enemies[index].pos_row;
-        let col_diff = player.pos_col - enemies[index].pos_col;
+        let (target_row, target_col, attack) = match choose_target(player, enemies, index) {
+            Option::Some(target) => target,
+            Option::None => continue,
+        };
+
+        let row_diff = target_row - enemies[index].pos_row;
+        let col_diff = target_col - enemies[index].pos_col;
 
         let distance = ((row_diff * row_diff + col_diff * col_diff) as f32).sqrt();
 
         //GameCode!
-        if distance == 1f32 {
+        if distance == 1f32 && attack {
             let direction = if row_diff == 1 {
                 AttackDirection::South
             } else if row_diff == -1 {
@@ -33,6 +42,14 @@ pub fn handle_ki(map: &Level, player: &mut Entity, enemies: &mut Vec<Entity>, ef
             if hit.valid(&effects) {
                 effects.push(Box::new(hit));
             }
+        } else if enemies[index].is_companion() {
+            //Companions find their way around walls.
+            if let Option::Some((row, col)) = step_towards(map, &enemies[index], target_row, target_col) {
+                if is_free(map, player, enemies, index, row, col) {
+                    enemies[index].pos_row = row;
+                    enemies[index].pos_col = col;
+                }
+            }
         } else if distance <= 4f32 {
             let direction = if row_diff >= 0 && col_diff >= 0 {
                 if row_diff > col_diff {
@@ -74,33 +91,132 @@ pub fn handle_ki(map: &Level, player: &mut Entity, enemies: &mut Vec<Entity>, ef
                 _ => { continue; },
             }
 
-            //Collision with Wall uncool.
-            if map.map[row_diff as usize][col_diff as usize] == Tile::Wall {
-                continue;
+            if is_free(map, player, enemies, index, row_diff, col_diff) {
+                let mut mut_enemy = &mut enemies[index];
+                mut_enemy.pos_row = row_diff as i32;
+                mut_enemy.pos_col = col_diff as i32;
             }
+        }
+    }
+}
 
-            if player.pos_row == row_diff && player.pos_col == col_diff {
-                continue;
-            }
+/// Position the entity goes for and whether it attacks there.
+/// Monsters go for the nearest of the player and the companions,
+/// companions for the nearest monster and otherwise back to the player.
+/// Friendly entities keep their place.
+fn choose_target(player: &Entity, enemies: &[Entity], index: usize) -> Option<(i32, i32, bool)> {
+    let me = &enemies[index];
+    if me.is_friendly() {
+        return Option::None;
+    }
+    let distance = |other: &Entity| {
+        let row_diff = other.pos_row - me.pos_row;
+        let col_diff = other.pos_col - me.pos_col;
 
-            let mut collision_with_other_enemy = false;
+        ((row_diff * row_diff + col_diff * col_diff) as f32).sqrt()
+    };
 
-            for inner_index in 0..size {
-                if inner_index == index {
-                    continue;
-                }
+    if !me.is_companion() {
+        let nearest = enemies.iter()
+            .filter(|other| other.is_companion() && distance(other) < distance(player))
+            .min_by(|first, second| distance(first).partial_cmp(&distance(second)).unwrap());
 
-                if !enemies[inner_index].is_death() && row_diff == enemies[inner_index].pos_row && col_diff == enemies[inner_index].pos_col {
-                    collision_with_other_enemy = true;
-                    break;
-                }
-            }
+        let target = nearest.unwrap_or(player);
+        return Option::Some((target.pos_row, target.pos_col, true));
+    }
 
-            if !collision_with_other_enemy {
-                let mut mut_enemy = &mut enemies[index];
-                mut_enemy.pos_row = row_diff as i32;
-                mut_enemy.pos_col = col_diff as i32;
-            }
+    //Companions who stay only fight what stands next to them.
+    let reach = if me.order == Order::Stay { 1f32 } else { 4f32 };
+    let monster = enemies.iter()
+        .filter(|other| me.is_enemy_of(other) && distance(other) <= reach)
+        .min_by(|first, second| distance(first).partial_cmp(&distance(second)).unwrap());
+
+    match monster {
+        Option::Some(monster) => Option::Some((monster.pos_row, monster.pos_col, true)),
+        Option::None if me.order == Order::Follow && distance(player) > FOLLOW_DISTANCE => Option::Some((player.pos_row, player.pos_col, false)),
+        Option::None => Option::None,
+    }
+}
+
+/// Next position on a shortest way to the target.
+fn step_towards(map: &Level, entity: &Entity, target_row: i32, target_col: i32) -> Option<(i32, i32)> {
+    let distances = map.distance_map(target_row as usize, target_col as usize);
+    let here = distances[entity.pos_row as usize][entity.pos_col as usize]?;
+
+    neighbours(entity.pos_row as usize, entity.pos_col as usize).into_iter()
+        .find(|&(row, col)| row < distances.len() && col < distances[row].len() && distances[row][col].is_some_and(|next| next < here))
+        .map(|(row, col)| (row as i32, col as i32))
+}
+
+/// Walls, the player and other living entities block the way.
+fn is_free(map: &Level, player: &Entity, enemies: &[Entity], index: usize, row: i32, col: i32) -> bool {
+    //Collision with Wall uncool.
+    if map.map[row as usize][col as usize] == Tile::Wall {
+        return false;
+    }
+
+    if player.pos_row == row && player.pos_col == col {
+        return false;
+    }
+
+    for (inner_index, other) in enemies.iter().enumerate() {
+        if inner_index != index && !other.is_death() && row == other.pos_row && col == other.pos_col {
+            return false;
         }
     }
-}
\ No newline at end of file
+
+    true
+}
+
+#[test]
+fn test_companion() {
+    let mut level = Level::new();
+    level.map[4][4] = Tile::Wall;
+
+    let mut player = Entity::new(0);
+    player.faction = Faction::Allied;
+    player.pos_row = 2;
+    player.pos_col = 2;
+
+    let mut companion = Entity::new(1);
+    companion.faction = Faction::Allied;
+    companion.pos_row = 6;
+    companion.pos_col = 6;
+
+    let mut monster = Entity::new(2);
+    monster.pos_row = 6;
+    monster.pos_col = 4;
+
+    let mut npc = Entity::new(3);
+    npc.faction = Faction::Neutral;
+    npc.pos_row = 2;
+    npc.pos_col = 6;
+
+    let mut enemies = vec![companion, monster, npc];
+    let mut effects: Vec<Box<dyn Effect>> = Vec::new();
+
+    //The companion walks to the monster, which goes for it instead of the player.
+    handle_ki(&level, &mut player, &mut enemies, &mut effects);
+    assert_eq!((6, 5), (enemies[0].pos_row, enemies[0].pos_col));
+    assert_eq!((6, 4), (enemies[1].pos_row, enemies[1].pos_col));
+    assert_eq!(vec![2], effects.iter().map(|effect| effect.actor_id()).collect::<Vec<u32>>());
+
+    handle_ki(&level, &mut player, &mut enemies, &mut effects);
+    assert_eq!(vec![2, 1], effects.iter().map(|effect| effect.actor_id()).collect::<Vec<u32>>());
+
+    //Without monsters the companion follows the player, unless it stays.
+    enemies[1].current_life = 0;
+    effects.clear();
+    enemies[0].order = Order::Stay;
+    handle_ki(&level, &mut player, &mut enemies, &mut effects);
+    assert_eq!((6, 5), (enemies[0].pos_row, enemies[0].pos_col));
+
+    enemies[0].order = Order::Follow;
+    for _ in 0..10 {
+        handle_ki(&level, &mut player, &mut enemies, &mut effects);
+    }
+    let (row_diff, col_diff) = (enemies[0].pos_row - player.pos_row, enemies[0].pos_col - player.pos_col);
+    assert!(((row_diff * row_diff + col_diff * col_diff) as f32).sqrt() <= FOLLOW_DISTANCE);
+    assert!(effects.is_empty());
+    assert_eq!((2, 6), (enemies[2].pos_row, enemies[2].pos_col));
+}
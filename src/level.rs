@@ -1,11 +1,20 @@
+use std::cmp;
+use std::collections::VecDeque;
+
 pub struct Level {
     pub map: Vec<Vec<Tile>>,
 
     pub meta: Vec<Vec<Tile>>,
 
     pub level: i32,
+
+    /// Tiles the player has seen, grows with the first look around.
+    pub explored: Vec<Vec<bool>>,
 }
 
+/// How far the player sees, walls block the sight.
+pub const SIGHT_RADIUS: i32 = 7;
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Tile {
     Nothing,
@@ -16,6 +25,10 @@ pub enum Tile {
         mn_type: u32,
         difficulty: u32
     },
+    /// Friendly entity, the number picks what it says.
+    NpcSpawn {
+        npc: u32
+    },
     Next,
 }
 
@@ -62,6 +75,227 @@ impl Level {
         let row = vec![Tile::Nothing, Tile::Nothing, Tile::Nothing, Tile::Nothing, Tile::Nothing, Tile::Nothing, Tile::Nothing, Tile::Nothing, Tile::Nothing];
         meta.push(row);
 
-        Level { map: rows, meta: meta, level: 0 }
+        Level { map: rows, meta: meta, level: 0, explored: Vec::new() }
+    }
+
+    /// Position of the first meta tile equal to given tile.
+    pub fn find_meta(&self, tile: Tile) -> Option<(usize, usize)> {
+        for (row_index, meta_row) in self.meta.iter().enumerate() {
+            for (col_index, meta_col) in meta_row.iter().enumerate() {
+                if *meta_col == tile {
+                    return Option::Some((row_index, col_index));
+                }
+            }
+        }
+
+        Option::None
+    }
+
+    /// Checks if an entity can stand on given position.
+    pub fn is_walkable(&self, row: usize, col: usize) -> bool {
+        row < self.map.len() && col < self.map[row].len() && self.map[row][col] == Tile::Floor
     }
+
+    /// Steps needed to reach every tile from given position, None if unreachable.
+    pub fn distance_map(&self, row: usize, col: usize) -> Vec<Vec<Option<u32>>> {
+        let mut distances: Vec<Vec<Option<u32>>> = self.map.iter().map(|map_row| vec![Option::None; map_row.len()]).collect();
+
+        if !self.is_walkable(row, col) {
+            return distances;
+        }
+
+        let mut queue = VecDeque::new();
+        distances[row][col] = Option::Some(0);
+        queue.push_back((row, col));
+
+        while let Option::Some((current_row, current_col)) = queue.pop_front() {
+            let next_distance = distances[current_row][current_col].unwrap() + 1;
+
+            for (next_row, next_col) in neighbours(current_row, current_col) {
+                if self.is_walkable(next_row, next_col) && distances[next_row][next_col].is_none() {
+                    distances[next_row][next_col] = Option::Some(next_distance);
+                    queue.push_back((next_row, next_col));
+                }
+            }
+        }
+
+        distances
+    }
+
+    pub fn is_explored(&self, row: usize, col: usize) -> bool {
+        self.explored.get(row).and_then(|explored_row| explored_row.get(col)).cloned().unwrap_or(false)
+    }
+
+    /// Checks if given position can be seen from the other one.
+    pub fn in_sight(&self, from_row: i32, from_col: i32, row: i32, col: i32) -> bool {
+        let row_diff = row - from_row;
+        let col_diff = col - from_col;
+
+        if row_diff * row_diff + col_diff * col_diff > SIGHT_RADIUS * SIGHT_RADIUS {
+            return false;
+        }
+
+        //Walls are seen, but everything behind them is hidden.
+        let steps = cmp::max(row_diff.abs(), col_diff.abs());
+        for step in 1..steps {
+            let between_row = from_row as f32 + (row_diff * step) as f32 / steps as f32;
+            let between_col = from_col as f32 + (col_diff * step) as f32 / steps as f32;
+
+            if !self.is_walkable(between_row.round() as usize, between_col.round() as usize) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Marks the whole level as explored, e.g. after reading a map.
+    pub fn explore_all(&mut self) {
+        self.explored = self.map.iter().map(|map_row| map_row.iter().map(|tile| *tile != Tile::Nothing).collect()).collect();
+    }
+
+    /// Marks every tile in sight of given position as explored.
+    pub fn explore(&mut self, row: i32, col: i32) {
+        if self.explored.len() != self.map.len() {
+            self.explored = self.map.iter().map(|map_row| vec![false; map_row.len()]).collect();
+        }
+
+        for explored_row in cmp::max(0, row - SIGHT_RADIUS)..row + SIGHT_RADIUS + 1 {
+            for explored_col in cmp::max(0, col - SIGHT_RADIUS)..col + SIGHT_RADIUS + 1 {
+                let inside = (explored_row as usize) < self.map.len() && (explored_col as usize) < self.map[explored_row as usize].len();
+
+                if inside && self.in_sight(row, col, explored_row, explored_col) {
+                    self.explored[explored_row as usize][explored_col as usize] = true;
+                }
+            }
+        }
+    }
+
+    /// Flood-fills from the player spawn and checks that the level is playable.
+    pub fn validate(&self) -> Result<(), Vec<LevelError>> {
+        let spawn = match self.find_meta(Tile::PlSpawn) {
+            Option::Some(value) => value,
+            Option::None => return Result::Err(vec![LevelError::NoSpawn]),
+        };
+
+        let mut errors = Vec::new();
+        let distances = self.distance_map(spawn.0, spawn.1);
+
+        if distances[spawn.0][spawn.1].is_none() {
+            errors.push(LevelError::SpawnNotOnFloor { row: spawn.0, col: spawn.1 });
+        }
+
+        match self.find_meta(Tile::Next) {
+            Option::Some((row, col)) => {
+                if distances[row][col].is_none() {
+                    errors.push(LevelError::UnreachableNext { row, col });
+                }
+            },
+            Option::None => errors.push(LevelError::NoNext),
+        }
+
+        for (row_index, map_row) in self.map.iter().enumerate() {
+            for (col_index, tile) in map_row.iter().enumerate() {
+                let on_border = row_index == 0 || col_index == 0 || row_index == self.map.len() - 1 || col_index == map_row.len() - 1;
+
+                if *tile == Tile::Floor && on_border {
+                    errors.push(LevelError::FloorOnBorder { row: row_index, col: col_index });
+                } else if *tile == Tile::Floor && distances[row_index][col_index].is_none() {
+                    errors.push(LevelError::UnreachableFloor { row: row_index, col: col_index });
+                }
+
+                if let Tile::MnSpawn { .. } | Tile::NpcSpawn { .. } = self.meta[row_index][col_index] {
+                    if *tile != Tile::Floor {
+                        errors.push(LevelError::SpawnNotOnFloor { row: row_index, col: col_index });
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Result::Ok(())
+        } else {
+            Result::Err(errors)
+        }
+    }
+}
+
+/// Problems found by `Level::validate`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LevelError {
+    NoSpawn,
+    NoNext,
+    UnreachableNext {
+        row: usize,
+        col: usize
+    },
+    UnreachableFloor {
+        row: usize,
+        col: usize
+    },
+    SpawnNotOnFloor {
+        row: usize,
+        col: usize
+    },
+    FloorOnBorder {
+        row: usize,
+        col: usize
+    },
+}
+
+/// Positions next to given position, entities only move horizontal or vertical.
+pub fn neighbours(row: usize, col: usize) -> Vec<(usize, usize)> {
+    let mut result = vec![(row + 1, col), (row, col + 1)];
+
+    if row > 0 {
+        result.push((row - 1, col));
+    }
+    if col > 0 {
+        result.push((row, col - 1));
+    }
+
+    result
+}
+
+#[test]
+fn test_validate_default_level() {
+    let level = Level::new();
+
+    assert_eq!(Result::Err(vec![LevelError::NoNext]), level.validate());
+}
+
+#[test]
+fn test_validate_unreachable_next() {
+    let mut level = Level::new();
+    level.meta[4][5] = Tile::Next;
+
+    assert_eq!(Result::Ok(()), level.validate());
+
+    //Wall off the stairs.
+    level.map[3][5] = Tile::Wall;
+    level.map[4][4] = Tile::Wall;
+    level.map[5][5] = Tile::Wall;
+    level.map[4][6] = Tile::Wall;
+
+    match level.validate() {
+        Result::Err(errors) => assert!(errors.contains(&LevelError::UnreachableNext { row: 4, col: 5 })),
+        Result::Ok(()) => panic!("Stairs should not be reachable."),
+    }
+}
+
+#[test]
+fn test_explore() {
+    let mut level = Level::new();
+    level.map[4][4] = Tile::Wall;
+
+    level.explore(3, 4);
+
+    assert!(level.is_explored(3, 4));
+    assert!(level.is_explored(4, 4));
+    assert!(level.is_explored(1, 1));
+    //Hidden behind the wall.
+    assert!(!level.is_explored(6, 4));
+    assert!(!level.in_sight(3, 4, 6, 4));
+    assert!(level.in_sight(3, 4, 6, 2));
+    assert!(!level.is_explored(20, 20));
 }
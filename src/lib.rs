@@ -0,0 +1,30 @@
+extern crate rand;
+#[cfg(feature = "terminal")]
+extern crate ncurses;
+
+pub mod character;
+pub mod combat;
+pub mod level;
+pub mod input;
+pub mod frontend;
+pub mod glyph;
+#[cfg(feature = "terminal")]
+pub mod ui;
+pub mod log;
+pub mod game;
+pub mod gen;
+pub mod ki;
+pub mod effect;
+pub mod dungeon;
+pub mod save;
+pub mod event;
+pub mod camera;
+pub mod theme;
+pub mod rng;
+pub mod gym;
+pub mod bot;
+pub mod text;
+pub mod score;
+pub mod morgue;
+pub mod dialogue;
+pub mod quest;
@@ -1,25 +1,120 @@
+use std::collections::VecDeque;
+
+use super::event::{Event, Subscriber};
+
+/// How many messages are remembered, older ones are dropped.
+pub const LOG_CAPACITY: usize = 500;
+
+pub struct LogEntry {
+    pub turn: u32,
+    pub message: String,
+    /// How often this message was repeated in a row.
+    pub count: u32,
+}
+
+impl LogEntry {
+    pub fn text(&self) -> String {
+        if self.count > 1 {
+            format!("{} x{}", self.message, self.count)
+        } else {
+            self.message.clone()
+        }
+    }
+}
+
 pub struct Log {
-    pub messages: Vec<String>,
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    turn: u32,
+    /// How many of the oldest entries were dropped.
+    dropped: usize,
 }
 
 impl Log {
     pub fn new() -> Log {
-        Log {messages: Vec::new()}
+        Log::with_capacity(LOG_CAPACITY)
     }
 
-    pub fn add_message(&mut self, msg: String) {
-        self.messages.push(msg);
+    pub fn with_capacity(capacity: usize) -> Log {
+        Log { entries: VecDeque::new(), capacity, turn: 0, dropped: 0 }
     }
 
-    pub fn get_message(&mut self) -> Option<String> {
-        if self.messages.len() > 0 {
-            let copy = self.messages[0].clone();
-            self.messages.remove(0);
+    pub fn add_message(&mut self, msg: String) {
+        if let Option::Some(last) = self.entries.back_mut() {
+            if last.message == msg {
+                last.count += 1;
+                last.turn = self.turn;
+                return;
+            }
+        }
 
-            Option::Some(copy)
-        } else {
-            Option::None
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+            self.dropped += 1;
         }
+
+        self.entries.push_back(LogEntry { turn: self.turn, message: msg, count: 1 });
+    }
+
+    pub fn next_turn(&mut self) {
+        self.turn += 1;
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// Continues counting from a saved turn.
+    pub fn set_turn(&mut self, turn: u32) {
+        self.turn = turn;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Dropped entries, adding them to an index gives a position which does not change when old entries are dropped.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Entry by index, 0 is the oldest remembered one.
+    pub fn get(&self, index: usize) -> Option<&LogEntry> {
+        self.entries.get(index)
+    }
+
+    /// The newest count entries, oldest first.
+    pub fn last(&self, count: usize) -> Vec<&LogEntry> {
+        let skip = self.entries.len().saturating_sub(count);
+
+        self.entries.iter().skip(skip).collect()
+    }
+
+    /// Number of entries written during the current turn.
+    pub fn current_turn_count(&self) -> usize {
+        self.entries.iter().rev().take_while(|entry| entry.turn == self.turn).count()
+    }
+}
+
+/// Turns events into readable messages.
+impl Subscriber for Log {
+    fn notify(&mut self, event: &Event) {
+        let message = match *event {
+            Event::Hit { ref attacker, ref target, damage } => format!("{} hit {} with {}!", attacker.name, target.name, damage),
+            Event::Crit { ref attacker, ref target, damage } => format!("CRIT! {} hit {} with {}!", attacker.name, target.name, damage),
+            Event::Miss { ref attacker, ref target } => format!("{} missed {}", attacker.name, target.name),
+            Event::Kill { ref attacker, ref target } => format!("{} killed {}!", attacker.name, target.name),
+            Event::Heal { ref target, amount } => format!("{} has been healed by {}.", target.name, amount),
+            Event::ItemPickedUp { ref item, .. } => format!("Item {} added to Backpack", item),
+            Event::LevelEntered { depth, .. } => format!("Entered dungeon {}.", depth),
+            Event::EffectApplied { .. } => return,
+        };
+
+        self.add_message(message);
     }
 }
 
@@ -32,18 +127,41 @@ fn test_log() {
     log.add_message(foo);
     log.add_message(bar);
 
-    match log.get_message() {
-        Option::Some(val) => assert_eq!("foo", val),
-        Option::None => panic!("Log should not be empty."),
-    }
+    let entries = log.last(5);
+    assert_eq!(2, entries.len());
+    assert_eq!("foo", entries[0].text());
+    assert_eq!("bar", entries[1].text());
 
-    match log.get_message() {
-        Option::Some(val) => assert_eq!("bar", val),
-        Option::None => panic!("Log should not be empty."),
-    }
+    // Messages stay in the log.
+    assert_eq!(2, log.last(5).len());
+}
+
+#[test]
+fn test_log_collapses_repeats() {
+    let mut log = Log::new();
+
+    log.add_message("Zombie missed TamaNu".to_string());
+    log.next_turn();
+    log.add_message("Zombie missed TamaNu".to_string());
+    log.add_message("Zombie missed TamaNu".to_string());
 
-    match log.get_message() {
-        Option::Some(_) => panic!("Log should not contain messages!"),
-        Option::None => (),
+    assert_eq!(1, log.len());
+    assert_eq!("Zombie missed TamaNu x3", log.last(1)[0].text());
+    assert_eq!(1, log.last(1)[0].turn);
+}
+
+#[test]
+fn test_log_ring_buffer() {
+    let mut log = Log::with_capacity(3);
+
+    for index in 0..5 {
+        log.add_message(format!("{}", index));
+        log.next_turn();
     }
-}
\ No newline at end of file
+    log.add_message("now".to_string());
+    log.add_message("again".to_string());
+
+    assert_eq!(3, log.len());
+    assert_eq!("4", log.get(0).unwrap().message);
+    assert_eq!(2, log.current_turn_count());
+}
@@ -1,19 +1,50 @@
-extern crate rand;
-extern crate ncurses;
-
-pub mod character;
-pub mod combat;
-pub mod level;
-pub mod ui;
-pub mod log;
-pub mod game;
-pub mod gen;
-pub mod ki;
-pub mod effect;
+extern crate rpg;
+
+use rpg::{game, gen};
+use rpg::ui::Window;
+
+use std::env;
+use std::process;
 
 fn main() {
+    let level_size = match parse_level_size(env::args().collect()) {
+        Result::Ok(level_size) => level_size,
+        Result::Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("Usage: rpg [--size ROWSxCOLS]");
+            process::exit(1);
+        },
+    };
+
     let mut game = game::Game::new();
-    game.init();
-    game.run();
-    game.cleanup();
-}
\ No newline at end of file
+    if let Option::Some((size_rows, size_cols)) = level_size {
+        game.set_level_size(size_rows, size_cols);
+    }
+
+    let mut window = Window::new();
+    game.init(&mut window);
+    game.run(&mut window);
+    Window::clear();
+}
+
+/// Reads the optional level size, given as --size 40x120.
+fn parse_level_size(args: Vec<String>) -> Result<Option<(usize, usize)>, String> {
+    let index = match args.iter().position(|arg| arg == "--size") {
+        Option::Some(index) => index,
+        Option::None => return Result::Ok(Option::None),
+    };
+
+    let value = args.get(index + 1).ok_or("Missing value for --size.")?;
+    let mut parts = value.split('x').map(|part| part.parse::<usize>());
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Option::Some(Result::Ok(size_rows)), Option::Some(Result::Ok(size_cols)), Option::None) => {
+            if size_rows < gen::level::MIN_ROWS || size_cols < gen::level::MIN_COLS {
+                return Result::Err(format!("Levels need at least {}x{}.", gen::level::MIN_ROWS, gen::level::MIN_COLS));
+            }
+
+            Result::Ok(Option::Some((size_rows, size_cols)))
+        },
+        _ => Result::Err(format!("Invalid level size {}.", value)),
+    }
+}
@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io;
+use std::io::Write;
+
+use super::character::entity::*;
+#[cfg(test)]
+use super::character::item::*;
+use super::event::Statistics;
+use super::level::*;
+use super::log::Log;
+use super::glyph::render_map;
+
+/// How many log messages end up in the morgue.
+pub const MORGUE_MESSAGES: usize = 20;
+
+/// Everything known about a finished run.
+pub struct Morgue<'a> {
+    pub player: &'a Entity,
+    pub class: &'a str,
+    pub statistics: &'a Statistics,
+    pub log: &'a Log,
+    pub level: &'a Level,
+    pub enemies: &'a [Entity],
+}
+
+impl<'a> Morgue<'a> {
+    /// File name for the player, e.g. "morgue-Tama_Nu-480.txt".
+    pub fn file_name(&self) -> String {
+        format!("morgue-{}-{}.txt", self.player.name.replace(' ', "_"), self.log.turn())
+    }
+
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(self.text().as_bytes())
+    }
+
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        let player = self.player;
+        let statistics = self.statistics;
+
+        out.push_str(&format!("{} the {}\n", player.name, self.class));
+        let killer = statistics.killed_by.as_deref().unwrap_or("something");
+        out.push_str(&format!("Killed by {} on depth {} after {} turns.\n\n", killer, self.level.level, self.log.turn()));
+
+        let stats = player.calculate_stats();
+        out.push_str(&format!("Life {}/{}, Vitality {}, Strength {}, Speed {}, Defense {}, Intelligence {}\n", player.current_life.max(0), player.calculate_max_life(), stats.vitality, stats.strength, stats.speed, stats.defense, stats.intelligence));
+        out.push_str(&format!("Deepest level {}, kills {}, crits {}, misses {}, specials used {}\n", statistics.deepest_level, statistics.kills, statistics.crits, statistics.misses, statistics.specials_used));
+        out.push_str(&format!("Damage dealt {}, damage taken {}, healed {}, items picked up {}\n\n", statistics.damage_dealt, statistics.damage_taken, statistics.healed, statistics.items_picked_up));
+
+        out.push_str("Equipment\n");
+        for &(slot, item) in [("Head", &player.head_item), ("Chest", &player.chest_item), ("Legs", &player.leg_item), ("Weapon", &player.weapon)].iter() {
+            out.push_str(&format!("  {:<7} {}\n", slot, item.describe()));
+        }
+
+        out.push_str("\nBackpack\n");
+        let size = player.backpack.size();
+        if size == 0 {
+            out.push_str("  Empty\n");
+        }
+        for item in player.backpack.items.iter().take(size) {
+            out.push_str(&format!("  {}\n", item.describe()));
+        }
+
+        out.push_str("\nLast messages\n");
+        for entry in self.log.last(MORGUE_MESSAGES) {
+            out.push_str(&format!("{:>5} {}\n", entry.turn, entry.text()));
+        }
+
+        out.push_str("\nMap\n");
+        for row in render_map(self.level, self.player, self.enemies) {
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+#[test]
+fn test_morgue_text() {
+    let mut player = Entity::new(0);
+    player.name = "Tama Nu".to_string();
+    player.pos_row = 1;
+    player.pos_col = 1;
+    player.current_life = 0;
+    player.weapon = Item { item_type: Type::Weapon, name: "Good Sword".to_string(), modifications: vec![StatsMod::Damage { min: 3, max: 7 }], rarity: Rarity::Rare };
+
+    let mut enemy = Entity::new(1);
+    enemy.monster_type = MonsterType::Goblin;
+    enemy.monster_difficulty = Difficulty::Hard;
+    enemy.pos_row = 1;
+    enemy.pos_col = 2;
+
+    let mut level = Level::new();
+    level.level = 3;
+    level.map = vec![vec![Tile::Wall; 4], vec![Tile::Wall, Tile::Floor, Tile::Floor, Tile::Floor], vec![Tile::Wall; 4]];
+
+    let mut log = Log::new();
+    for turn in 0..30 {
+        log.add_message(format!("Message {}", turn));
+        log.next_turn();
+    }
+
+    let mut statistics = Statistics::new(0);
+    statistics.kills = 7;
+    statistics.killed_by = Option::Some("(Hard) Goblin".to_string());
+
+    let enemies = [enemy];
+    let morgue = Morgue { player: &player, class: "Rogue", statistics: &statistics, log: &log, level: &level, enemies: &enemies };
+    let text = morgue.text();
+
+    assert_eq!("morgue-Tama_Nu-30.txt", morgue.file_name());
+    assert!(text.starts_with("Tama Nu the Rogue\nKilled by (Hard) Goblin on depth 3 after 30 turns.\n"));
+    assert!(text.contains("kills 7"));
+    assert!(text.contains("  Weapon  Good Sword (Rare) Damage 3-7\n"));
+    assert!(!text.contains("Message 9\n"));
+    assert!(text.contains("   10 Message 10\n"));
+    assert!(text.ends_with("Map\n####\n#_G.\n##<#\n"));
+}
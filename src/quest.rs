@@ -0,0 +1,218 @@
+use super::character::entity::MonsterType;
+use super::character::item::Type;
+use super::event::{Event, Subscriber};
+use super::rng;
+
+/// Open quests the player can have at once.
+pub const MAX_QUESTS: usize = 5;
+
+/// Monsters which can be hunted.
+const HUNTED: [MonsterType; 3] = [MonsterType::Zombie, MonsterType::Crab, MonsterType::Goblin];
+
+/// Kind of quest, e.g. offered in a dialogue.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum QuestKind {
+    Kill,
+    Recover,
+    Vault,
+}
+
+/// What has to be done.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Objective {
+    /// Kill given number of monsters of the type.
+    Kill {
+        monster_type: MonsterType,
+        count: u32
+    },
+    /// Pick up the heirloom of the type, a monster on the depth carries it.
+    Recover {
+        item_type: Type,
+        depth: i32
+    },
+    /// Kill the guardians of a vault on the depth, they are spawned once the depth is generated.
+    Vault {
+        depth: i32,
+        guardians: Vec<u32>
+    },
+}
+
+/// Given when the quest is completed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Reward {
+    Potions,
+    Tome,
+    Gear,
+}
+
+pub const REWARDS: [Reward; 3] = [Reward::Potions, Reward::Tome, Reward::Gear];
+
+impl Reward {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Reward::Potions => "two strong healing potions",
+            Reward::Tome => "a tome",
+            Reward::Gear => "a piece of master gear",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quest {
+    /// Who gave the quest, e.g. "Merchant" or "Torn Note".
+    pub giver: String,
+    pub objective: Objective,
+    pub progress: u32,
+    pub reward: Reward,
+    pub done: bool,
+}
+
+impl Quest {
+    /// Random quest of the kind, places to go to lie on given depth.
+    pub fn new(kind: QuestKind, giver: &str, depth: i32) -> Quest {
+        let objective = match kind {
+            QuestKind::Kill => Objective::Kill { monster_type: HUNTED[rng::gen_range(0, HUNTED.len())], count: rng::gen_range(3, 7) as u32 },
+            QuestKind::Recover => {
+                let item_type = [Type::Head, Type::Chest, Type::Legs, Type::Weapon][rng::gen_range(0, 4)];
+                Objective::Recover { item_type, depth }
+            },
+            QuestKind::Vault => Objective::Vault { depth, guardians: Vec::new() },
+        };
+
+        Quest { giver: giver.to_string(), objective, progress: 0, reward: REWARDS[rng::gen_range(0, REWARDS.len())], done: false }
+    }
+
+    /// Progress needed to complete the quest.
+    pub fn goal(&self) -> u32 {
+        match self.objective {
+            Objective::Kill { count, .. } => count,
+            Objective::Recover { .. } => 1,
+            Objective::Vault { ref guardians, .. } => guardians.len() as u32,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.objective {
+            Objective::Kill { monster_type, count } => format!("Kill {} {:?}s", count, monster_type),
+            Objective::Recover { item_type, depth } => format!("Recover the {} from depth {}", heirloom_name(item_type), depth),
+            Objective::Vault { depth, .. } => format!("Clear the vault on depth {}", depth),
+        }
+    }
+
+    fn complete_if_reached(&mut self, completed: &mut bool) {
+        if !self.done && self.progress >= self.goal() {
+            self.done = true;
+            *completed = true;
+        }
+    }
+}
+
+/// Name of the heirloom of a recover quest.
+pub fn heirloom_name(item_type: Type) -> &'static str {
+    match item_type {
+        Type::Head => "Heirloom Helm",
+        Type::Chest => "Heirloom Armor",
+        Type::Legs => "Heirloom Trousers",
+        _ => "Heirloom Sword",
+    }
+}
+
+/// Tracks the progress of all quests, completed ones are collected until they are rewarded.
+pub struct Quests {
+    pub quests: Vec<Quest>,
+    pub completed: Vec<usize>,
+
+    player_id: u32,
+}
+
+impl Quests {
+    pub fn new(player_id: u32) -> Quests {
+        Quests { quests: Vec::new(), completed: Vec::new(), player_id }
+    }
+
+    pub fn with_quests(player_id: u32, quests: Vec<Quest>) -> Quests {
+        Quests { quests, completed: Vec::new(), player_id }
+    }
+
+    pub fn open(&self) -> usize {
+        self.quests.iter().filter(|quest| !quest.done).count()
+    }
+
+    /// Whether the giver has an open quest already.
+    pub fn has_open(&self, giver: &str) -> bool {
+        self.quests.iter().any(|quest| !quest.done && quest.giver == giver)
+    }
+}
+
+impl Subscriber for Quests {
+    fn notify(&mut self, event: &Event) {
+        let player_id = self.player_id;
+
+        for (index, quest) in self.quests.iter_mut().enumerate().filter(|(_, quest)| !quest.done) {
+            let mut completed = false;
+
+            match (event, &quest.objective) {
+                (Event::Kill { attacker, target }, Objective::Kill { monster_type, .. }) if attacker.id == player_id && target.monster_type == *monster_type => {
+                    quest.progress += 1;
+                    quest.complete_if_reached(&mut completed);
+                },
+                //Companions may clear the vault too.
+                (Event::Kill { target, .. }, Objective::Vault { guardians, .. }) if guardians.contains(&target.id) => {
+                    quest.progress += 1;
+                    quest.complete_if_reached(&mut completed);
+                },
+                (Event::ItemPickedUp { actor, item }, Objective::Recover { item_type, .. }) if actor.id == player_id && item == heirloom_name(*item_type) => {
+                    quest.progress = 1;
+                    quest.complete_if_reached(&mut completed);
+                },
+                _ => {},
+            }
+
+            if completed {
+                self.completed.push(index);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_quest_progress() {
+    use super::event::Actor;
+
+    let player = Actor { id: 0, name: "Player".to_string(), monster_type: MonsterType::Unknown };
+    let pet = Actor { id: 1, name: "Wolf".to_string(), monster_type: MonsterType::Pet };
+    let goblin = |id: u32| Actor { id, name: "Goblin".to_string(), monster_type: MonsterType::Goblin };
+
+    let hunt = Quest { giver: "Merchant".to_string(), objective: Objective::Kill { monster_type: MonsterType::Goblin, count: 2 }, progress: 0, reward: Reward::Gear, done: false };
+    let recover = Quest { giver: "Torn Note".to_string(), objective: Objective::Recover { item_type: Type::Legs, depth: 2 }, progress: 0, reward: Reward::Tome, done: false };
+    let vault = Quest { giver: "Hermit".to_string(), objective: Objective::Vault { depth: 3, guardians: vec![7, 8] }, progress: 0, reward: Reward::Potions, done: false };
+    let mut quests = Quests::with_quests(0, vec![hunt, recover, vault]);
+    assert!(quests.has_open("Merchant"));
+
+    //Only kills of the player count for hunts, every kill of a guardian for vaults.
+    quests.notify(&Event::Kill { attacker: pet.clone(), target: goblin(7) });
+    quests.notify(&Event::Kill { attacker: player.clone(), target: goblin(5) });
+    assert_eq!(vec![1, 0, 1], quests.quests.iter().map(|quest| quest.progress).collect::<Vec<u32>>());
+
+    quests.notify(&Event::ItemPickedUp { actor: player.clone(), item: "Heirloom Helm".to_string() });
+    quests.notify(&Event::ItemPickedUp { actor: player.clone(), item: "Heirloom Trousers".to_string() });
+    quests.notify(&Event::Kill { attacker: player.clone(), target: goblin(8) });
+    assert_eq!(vec![1, 0, 2], quests.completed);
+    assert!(quests.quests.iter().all(|quest| quest.done));
+    assert!(!quests.has_open("Merchant"));
+
+    quests.notify(&Event::Kill { attacker: player.clone(), target: goblin(9) });
+    assert_eq!(2, quests.quests[0].progress);
+    assert_eq!(3, quests.completed.len());
+}
+
+#[test]
+fn test_empty_vault() {
+    let player = super::event::Actor { id: 0, name: "Player".to_string(), monster_type: MonsterType::Unknown };
+    let mut quests = Quests::with_quests(0, vec![Quest::new(QuestKind::Vault, "Hermit", 4)]);
+
+    //Vaults without guardians were not built yet, entering their depth is no progress.
+    quests.notify(&Event::LevelEntered { actor: player, depth: 4 });
+    assert!(!quests.quests[0].done);
+    assert!(quests.completed.is_empty());
+}
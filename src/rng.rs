@@ -0,0 +1,31 @@
+use rand::{Rng, SeedableRng, StdRng};
+use rand::distributions::range::SampleRange;
+
+use std::cell::RefCell;
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::new().expect("Could not seed random generator."));
+}
+
+/// Restarts the random numbers of fights and loot, the same seed plays the same again.
+/// Every thread has its own generator, so parallel games do not disturb each other.
+pub fn reseed(seed: usize) {
+    let seed: &[usize] = &[seed];
+    RNG.with(|rng| *rng.borrow_mut() = SeedableRng::from_seed(seed));
+}
+
+/// Random number from low inclusive to high exclusive.
+pub fn gen_range<T: PartialOrd + SampleRange>(low: T, high: T) -> T {
+    RNG.with(|rng| rng.borrow_mut().gen_range(low, high))
+}
+
+#[test]
+fn test_reseed() {
+    reseed(7);
+    let first: Vec<i32> = (0..10).map(|_| gen_range(0, 1000)).collect();
+
+    reseed(7);
+    let second: Vec<i32> = (0..10).map(|_| gen_range(0, 1000)).collect();
+
+    assert_eq!(first, second);
+}
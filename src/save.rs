@@ -0,0 +1,634 @@
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::str::{FromStr, Lines};
+
+use super::character::entity::*;
+use super::character::item::*;
+use super::character::stats::*;
+use super::character::class::{Class, CLASSES};
+use super::dungeon::{Dungeon, Floor};
+use super::effect::{Special, SPECIALS};
+use super::event::Statistics;
+use super::quest::{Quest, Objective, REWARDS};
+use super::level::*;
+
+pub const SAVE_FILE: &str = "savegame.txt";
+
+const SAVE_HEADER: &str = "rpg-save 9";
+
+/// How far the player got, needed for the high score.
+pub struct Progress {
+    pub class: Class,
+    pub turn: u32,
+    pub statistics: Statistics,
+    /// Specials bound to the three slots.
+    pub specials: [Special; 3],
+    /// Spells learned from scrolls and tomes.
+    pub spells: Vec<Special>,
+    /// Open and completed quests.
+    pub quests: Vec<Quest>,
+}
+
+/// Everything needed to continue a game.
+pub struct SaveGame {
+    pub player: Entity,
+    pub progress: Progress,
+    pub entity_count: u32,
+    pub current: Floor,
+    pub dungeon: Dungeon,
+}
+
+pub fn write_save(path: &str, player: &Entity, progress: &Progress, entity_count: u32, level: &Level, enemies: &[Entity], dungeon: &Dungeon) -> io::Result<()> {
+    let mut out = String::new();
+
+    out.push_str(SAVE_HEADER);
+    out.push('\n');
+    out.push_str(&format!("entity_count {}\n", entity_count));
+    write_progress(&mut out, progress);
+    write_entity(&mut out, player);
+    write_floor(&mut out, level, enemies);
+
+    let floors = dungeon.floors();
+    out.push_str(&format!("floors {}\n", floors.len()));
+    for floor in floors {
+        write_floor(&mut out, &floor.level, &floor.enemies);
+    }
+
+    let mut file = File::create(path)?;
+    file.write_all(out.as_bytes())
+}
+
+pub fn read_save(path: &str) -> io::Result<SaveGame> {
+    let mut content = String::new();
+    File::open(path)?.read_to_string(&mut content)?;
+
+    let mut reader = Reader { lines: content.lines(), line_number: 0 };
+
+    if reader.next_line()? != SAVE_HEADER {
+        return Result::Err(invalid("Unknown save format.".to_string()));
+    }
+
+    let entity_count = parse(reader.values("entity_count")?[0])?;
+    let progress = read_progress(&mut reader)?;
+    let player = read_entity(&mut reader)?;
+    let current = read_floor(&mut reader)?;
+
+    let mut dungeon = Dungeon::new();
+    let floor_count: usize = parse(reader.values("floors")?[0])?;
+    for _ in 0..floor_count {
+        dungeon.store(read_floor(&mut reader)?);
+    }
+
+    Result::Ok(SaveGame { player, progress, entity_count, current, dungeon })
+}
+
+fn write_progress(out: &mut String, progress: &Progress) {
+    let statistics = &progress.statistics;
+
+    out.push_str(&format!("class {}\n", progress.class.name()));
+    out.push_str(&format!("turn {}\n", progress.turn));
+    out.push_str(&format!("statistics {} {} {} {} {} {} {} {} {}\n", statistics.kills, statistics.crits, statistics.misses, statistics.damage_dealt, statistics.damage_taken,
+        statistics.healed, statistics.items_picked_up, statistics.specials_used, statistics.deepest_level));
+
+    let specials: Vec<String> = progress.specials.iter().map(|special| format!("{:?}", special)).collect();
+    out.push_str(&format!("specials {}\n", specials.join(" ")));
+    let spells: Vec<String> = progress.spells.iter().map(|spell| format!("{:?}", spell)).collect();
+    out.push_str(&format!("spells {}\n", spells.join(" ")));
+
+    out.push_str(&format!("quests {}\n", progress.quests.len()));
+    for quest in &progress.quests {
+        let objective = match quest.objective {
+            Objective::Kill { monster_type, count } => format!("Kill {:?} {}", monster_type, count),
+            Objective::Recover { item_type, depth } => format!("Recover {:?} {}", item_type, depth),
+            Objective::Vault { depth, ref guardians } => {
+                let guardians: Vec<String> = guardians.iter().map(|id| id.to_string()).collect();
+                format!("Vault {} {}", depth, guardians.join(" ")).trim_end().to_string()
+            },
+        };
+
+        out.push_str(&format!("quest {} {} {:?} {}\n", quest.done, quest.progress, quest.reward, objective));
+        out.push_str(&format!("giver {}\n", quest.giver));
+    }
+}
+
+fn write_entity(out: &mut String, entity: &Entity) {
+    out.push_str(&format!("entity {} {} {} {} {} {:?} {:?} {:?} {:?}\n", entity.id, entity.pos_row, entity.pos_col, entity.current_life, entity.current_energy,
+        entity.monster_type, entity.monster_difficulty, entity.faction, entity.order));
+    out.push_str(&format!("name {}\n", entity.name));
+
+    let stats = &entity.base_stats;
+    out.push_str(&format!("stats {} {} {} {} {}\n", stats.vitality, stats.strength, stats.speed, stats.defense, stats.intelligence));
+
+    write_item(out, &entity.head_item);
+    write_item(out, &entity.chest_item);
+    write_item(out, &entity.leg_item);
+    write_item(out, &entity.weapon);
+
+    let size = entity.backpack.size();
+    out.push_str(&format!("backpack {}\n", size));
+    for item in entity.backpack.items.iter().take(size) {
+        write_item(out, item);
+    }
+}
+
+fn write_item(out: &mut String, item: &Item) {
+    out.push_str(&format!("item {:?} {:?} {} {}\n", item.item_type, item.rarity, item.modifications.len(), item.name));
+
+    for modification in &item.modifications {
+        match *modification {
+            StatsMod::Add(stat) => {
+                let (name, value) = match stat {
+                    Stat::Vitality(value) => ("Vitality", value),
+                    Stat::Strength(value) => ("Strength", value),
+                    Stat::Speed(value) => ("Speed", value),
+                    Stat::Defense(value) => ("Defense", value),
+                    Stat::Intelligence(value) => ("Intelligence", value),
+                };
+                out.push_str(&format!("add {} {}\n", name, value));
+            },
+            StatsMod::Damage { min, max } => out.push_str(&format!("damage {} {}\n", min, max)),
+            StatsMod::AttackSpeed(value) => out.push_str(&format!("attack_speed {}\n", value)),
+            StatsMod::Heal(value) => out.push_str(&format!("heal {}\n", value)),
+            StatsMod::Teach(spell) => out.push_str(&format!("teach {:?}\n", spell)),
+            StatsMod::Quest => out.push_str("quest\n"),
+        }
+    }
+}
+
+fn write_floor(out: &mut String, level: &Level, enemies: &[Entity]) {
+    let mut meta = Vec::new();
+    for (row_index, meta_row) in level.meta.iter().enumerate() {
+        for (col_index, tile) in meta_row.iter().enumerate() {
+            match *tile {
+                Tile::PlSpawn => meta.push(format!("meta {} {} PlSpawn\n", row_index, col_index)),
+                Tile::Next => meta.push(format!("meta {} {} Next\n", row_index, col_index)),
+                Tile::MnSpawn { mn_type, difficulty } => meta.push(format!("meta {} {} MnSpawn {} {}\n", row_index, col_index, mn_type, difficulty)),
+                Tile::NpcSpawn { npc } => meta.push(format!("meta {} {} NpcSpawn {}\n", row_index, col_index, npc)),
+                _ => {},
+            }
+        }
+    }
+
+    out.push_str(&format!("floor {} {} {} {} {}\n", level.level, level.map.len(), level.explored.len(), meta.len(), enemies.len()));
+
+    for map_row in &level.map {
+        let row: String = map_row.iter().map(|tile| match *tile {
+            Tile::Floor => '_',
+            Tile::Wall => 'x',
+            _ => ' ',
+        }).collect();
+
+        out.push_str(&format!("map {}\n", row));
+    }
+
+    //Levels which were never seen have no explored tiles.
+    for explored_row in &level.explored {
+        let row: String = explored_row.iter().map(|explored| if *explored { '1' } else { '0' }).collect();
+
+        out.push_str(&format!("explored {}\n", row));
+    }
+
+    for line in meta {
+        out.push_str(&line);
+    }
+
+    for enemy in enemies {
+        write_entity(out, enemy);
+    }
+}
+
+fn read_progress(reader: &mut Reader) -> io::Result<Progress> {
+    let class = parse_class(reader.values("class")?[0])?;
+    let turn = parse(reader.values("turn")?[0])?;
+
+    let values = reader.values("statistics")?;
+    if values.len() != 9 {
+        return Result::Err(reader.error("statistics"));
+    }
+
+    let mut statistics = Statistics::new(0);
+    statistics.kills = parse(values[0])?;
+    statistics.crits = parse(values[1])?;
+    statistics.misses = parse(values[2])?;
+    statistics.damage_dealt = parse(values[3])?;
+    statistics.damage_taken = parse(values[4])?;
+    statistics.healed = parse(values[5])?;
+    statistics.items_picked_up = parse(values[6])?;
+    statistics.specials_used = parse(values[7])?;
+    statistics.deepest_level = parse(values[8])?;
+
+    let values = reader.values("specials")?;
+    if values.len() != 3 {
+        return Result::Err(reader.error("specials"));
+    }
+    let specials = [parse_special(values[0])?, parse_special(values[1])?, parse_special(values[2])?];
+
+    let mut spells = Vec::new();
+    for value in reader.rest("spells")?.split_whitespace() {
+        spells.push(parse_special(value)?);
+    }
+
+    let quest_count: usize = parse(reader.values("quests")?[0])?;
+    let mut quests = Vec::new();
+    for _ in 0..quest_count {
+        quests.push(read_quest(reader)?);
+    }
+
+    Result::Ok(Progress { class, turn, statistics, specials, spells, quests })
+}
+
+fn read_quest(reader: &mut Reader) -> io::Result<Quest> {
+    let values = reader.values("quest")?;
+    if values.len() < 5 {
+        return Result::Err(reader.error("quest"));
+    }
+
+    let done = match values[0] {
+        "true" => true,
+        "false" => false,
+        _ => return Result::Err(reader.error("quest")),
+    };
+    let progress = parse(values[1])?;
+    let reward = match REWARDS.iter().find(|reward| format!("{:?}", reward) == values[2]) {
+        Option::Some(reward) => *reward,
+        Option::None => return Result::Err(reader.error("quest")),
+    };
+
+    let objective = match (values[3], values.len()) {
+        ("Kill", 6) => Objective::Kill { monster_type: parse_monster_type(values[4])?, count: parse(values[5])? },
+        ("Recover", 6) => Objective::Recover { item_type: parse_type(values[4])?, depth: parse(values[5])? },
+        ("Vault", _) => {
+            let mut guardians = Vec::new();
+            for value in &values[5..] {
+                guardians.push(parse(value)?);
+            }
+
+            Objective::Vault { depth: parse(values[4])?, guardians }
+        },
+        _ => return Result::Err(reader.error("quest")),
+    };
+
+    let giver = reader.rest("giver")?.to_string();
+
+    Result::Ok(Quest { giver, objective, progress, reward, done })
+}
+
+fn read_entity(reader: &mut Reader) -> io::Result<Entity> {
+    let values = reader.values("entity")?;
+    if values.len() != 9 {
+        return Result::Err(reader.error("entity"));
+    }
+
+    let mut entity = Entity::new(parse(values[0])?);
+    entity.pos_row = parse(values[1])?;
+    entity.pos_col = parse(values[2])?;
+    entity.current_life = parse(values[3])?;
+    entity.current_energy = parse(values[4])?;
+    entity.monster_type = parse_monster_type(values[5])?;
+    entity.monster_difficulty = parse_difficulty(values[6])?;
+    entity.faction = parse_faction(values[7])?;
+    entity.order = parse_order(values[8])?;
+
+    entity.name = reader.rest("name")?.to_string();
+
+    let stats = reader.values("stats")?;
+    if stats.len() != 5 {
+        return Result::Err(reader.error("stats"));
+    }
+    entity.base_stats = Stats { vitality: parse(stats[0])?, strength: parse(stats[1])?, speed: parse(stats[2])?, defense: parse(stats[3])?, intelligence: parse(stats[4])? };
+
+    entity.head_item = read_item(reader)?;
+    entity.chest_item = read_item(reader)?;
+    entity.leg_item = read_item(reader)?;
+    entity.weapon = read_item(reader)?;
+
+    let size: usize = parse(reader.values("backpack")?[0])?;
+    for _ in 0..size {
+        let item = read_item(reader)?;
+
+        if entity.backpack.add_item(item).is_err() {
+            return Result::Err(reader.error("backpack"));
+        }
+    }
+
+    Result::Ok(entity)
+}
+
+fn read_item(reader: &mut Reader) -> io::Result<Item> {
+    let line = reader.rest("item")?;
+    let mut parts = line.splitn(4, ' ');
+
+    let item_type = parse_type(parts.next().unwrap_or(""))?;
+    let rarity = parse_rarity(parts.next().unwrap_or(""))?;
+    let modification_count: usize = parse(parts.next().unwrap_or(""))?;
+    let name = parts.next().unwrap_or("").to_string();
+
+    let mut item = Item { item_type, name, modifications: Vec::new(), rarity };
+
+    for _ in 0..modification_count {
+        let line = reader.next_line()?;
+        let values: Vec<&str> = line.split(' ').collect();
+
+        let modification = match (values[0], values.len()) {
+            ("add", 3) => {
+                let value = parse(values[2])?;
+
+                match values[1] {
+                    "Vitality" => StatsMod::Add(Stat::Vitality(value)),
+                    "Strength" => StatsMod::Add(Stat::Strength(value)),
+                    "Speed" => StatsMod::Add(Stat::Speed(value)),
+                    "Defense" => StatsMod::Add(Stat::Defense(value)),
+                    "Intelligence" => StatsMod::Add(Stat::Intelligence(value)),
+                    _ => return Result::Err(reader.error("add")),
+                }
+            },
+            ("damage", 3) => StatsMod::Damage { min: parse(values[1])?, max: parse(values[2])? },
+            ("attack_speed", 2) => StatsMod::AttackSpeed(parse(values[1])?),
+            ("heal", 2) => StatsMod::Heal(parse(values[1])?),
+            ("teach", 2) => StatsMod::Teach(parse_special(values[1])?),
+            ("quest", 1) => StatsMod::Quest,
+            _ => return Result::Err(reader.error("modification")),
+        };
+
+        item.modifications.push(modification);
+    }
+
+    Result::Ok(item)
+}
+
+fn read_floor(reader: &mut Reader) -> io::Result<Floor> {
+    let values = reader.values("floor")?;
+    if values.len() != 5 {
+        return Result::Err(reader.error("floor"));
+    }
+
+    let depth = parse(values[0])?;
+    let row_count: usize = parse(values[1])?;
+    let explored_count: usize = parse(values[2])?;
+    let meta_count: usize = parse(values[3])?;
+    let enemy_count: usize = parse(values[4])?;
+
+    let mut level = Level { map: Vec::new(), meta: Vec::new(), level: depth, explored: Vec::new() };
+    for _ in 0..row_count {
+        let row: Vec<Tile> = reader.rest("map")?.chars().map(|char| match char {
+            '_' => Tile::Floor,
+            'x' => Tile::Wall,
+            _ => Tile::Nothing,
+        }).collect();
+
+        level.meta.push(vec![Tile::Nothing; row.len()]);
+        level.map.push(row);
+    }
+
+    for _ in 0..explored_count {
+        let row: Vec<bool> = reader.rest("explored")?.chars().map(|char| char == '1').collect();
+        level.explored.push(row);
+    }
+
+    for _ in 0..meta_count {
+        let values = reader.values("meta")?;
+        let row: usize = parse(values[0])?;
+        let col: usize = parse(values[1])?;
+
+        if row >= level.meta.len() || col >= level.meta[row].len() {
+            return Result::Err(reader.error("meta"));
+        }
+
+        level.meta[row][col] = match (values[2], values.len()) {
+            ("PlSpawn", 3) => Tile::PlSpawn,
+            ("Next", 3) => Tile::Next,
+            ("MnSpawn", 5) => Tile::MnSpawn { mn_type: parse(values[3])?, difficulty: parse(values[4])? },
+            ("NpcSpawn", 4) => Tile::NpcSpawn { npc: parse(values[3])? },
+            _ => return Result::Err(reader.error("meta")),
+        };
+    }
+
+    let mut enemies = Vec::new();
+    for _ in 0..enemy_count {
+        enemies.push(read_entity(reader)?);
+    }
+
+    Result::Ok(Floor { level, enemies })
+}
+
+fn parse_type(value: &str) -> io::Result<Type> {
+    match value {
+        "Head" => Result::Ok(Type::Head),
+        "Chest" => Result::Ok(Type::Chest),
+        "Legs" => Result::Ok(Type::Legs),
+        "Weapon" => Result::Ok(Type::Weapon),
+        "Potion" => Result::Ok(Type::Potion),
+        "Scroll" => Result::Ok(Type::Scroll),
+        "Nothing" => Result::Ok(Type::Nothing),
+        _ => Result::Err(invalid(format!("Unknown item type {}.", value))),
+    }
+}
+
+fn parse_special(value: &str) -> io::Result<Special> {
+    match SPECIALS.iter().find(|special| format!("{:?}", special) == value) {
+        Option::Some(special) => Result::Ok(*special),
+        Option::None => Result::Err(invalid(format!("Unknown special {}.", value))),
+    }
+}
+
+fn parse_rarity(value: &str) -> io::Result<Rarity> {
+    match value {
+        "Common" => Result::Ok(Rarity::Common),
+        "Uncommon" => Result::Ok(Rarity::Uncommon),
+        "Rare" => Result::Ok(Rarity::Rare),
+        "Legendary" => Result::Ok(Rarity::Legendary),
+        _ => Result::Err(invalid(format!("Unknown rarity {}.", value))),
+    }
+}
+
+fn parse_class(value: &str) -> io::Result<Class> {
+    CLASSES.iter().find(|class| class.name() == value).cloned().ok_or_else(|| invalid(format!("Unknown class {}.", value)))
+}
+
+fn parse_monster_type(value: &str) -> io::Result<MonsterType> {
+    match value {
+        "Unknown" => Result::Ok(MonsterType::Unknown),
+        "Crab" => Result::Ok(MonsterType::Crab),
+        "Zombie" => Result::Ok(MonsterType::Zombie),
+        "Goblin" => Result::Ok(MonsterType::Goblin),
+        "Boss" => Result::Ok(MonsterType::Boss),
+        "Pet" => Result::Ok(MonsterType::Pet),
+        "Npc" => Result::Ok(MonsterType::Npc),
+        _ => Result::Err(invalid(format!("Unknown monster type {}.", value))),
+    }
+}
+
+fn parse_faction(value: &str) -> io::Result<Faction> {
+    match value {
+        "Hostile" => Result::Ok(Faction::Hostile),
+        "Allied" => Result::Ok(Faction::Allied),
+        "Neutral" => Result::Ok(Faction::Neutral),
+        _ => Result::Err(invalid(format!("Unknown faction {}.", value))),
+    }
+}
+
+fn parse_order(value: &str) -> io::Result<Order> {
+    match value {
+        "Follow" => Result::Ok(Order::Follow),
+        "Stay" => Result::Ok(Order::Stay),
+        _ => Result::Err(invalid(format!("Unknown order {}.", value))),
+    }
+}
+
+fn parse_difficulty(value: &str) -> io::Result<Difficulty> {
+    match value {
+        "Easy" => Result::Ok(Difficulty::Easy),
+        "Normal" => Result::Ok(Difficulty::Normal),
+        "Hard" => Result::Ok(Difficulty::Hard),
+        "Boss" => Result::Ok(Difficulty::Boss),
+        _ => Result::Err(invalid(format!("Unknown difficulty {}.", value))),
+    }
+}
+
+fn parse<T: FromStr>(value: &str) -> io::Result<T> {
+    value.parse().map_err(|_| invalid(format!("Invalid number {}.", value)))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+struct Reader<'a> {
+    lines: Lines<'a>,
+    line_number: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn next_line(&mut self) -> io::Result<&'a str> {
+        self.line_number += 1;
+
+        match self.lines.next() {
+            Option::Some(line) => Result::Ok(line),
+            Option::None => Result::Err(invalid("Unexpected end of save.".to_string())),
+        }
+    }
+
+    /// Everything after given key, keeps spaces.
+    fn rest(&mut self, key: &str) -> io::Result<&'a str> {
+        let line = self.next_line()?;
+
+        if line == key {
+            Result::Ok("")
+        } else if line.starts_with(key) && line[key.len()..].starts_with(' ') {
+            Result::Ok(&line[key.len() + 1..])
+        } else {
+            Result::Err(self.error(key))
+        }
+    }
+
+    /// Space separated values after given key.
+    fn values(&mut self, key: &str) -> io::Result<Vec<&'a str>> {
+        let values: Vec<&'a str> = self.rest(key)?.split(' ').collect();
+
+        if values.is_empty() || values[0].is_empty() {
+            Result::Err(self.error(key))
+        } else {
+            Result::Ok(values)
+        }
+    }
+
+    fn error(&self, key: &str) -> io::Error {
+        invalid(format!("Expected valid {} in line {}.", key, self.line_number))
+    }
+}
+
+#[test]
+fn test_save_and_load() {
+    use std::env;
+    use std::fs;
+    use super::quest::Reward;
+
+    let path = env::temp_dir().join("rpg_test_save_and_load.txt");
+    let path = path.to_str().unwrap();
+
+    let mut player = Entity::new(0);
+    player.name = "Tama Nu".to_string();
+    player.pos_row = 3;
+    player.current_life = 17;
+    player.current_energy = 35;
+    player.weapon = Item { item_type: Type::Weapon, name: "Good Sword".to_string(), modifications: vec![StatsMod::Add(Stat::Strength(2)), StatsMod::Damage { min: 3, max: 7 }, StatsMod::AttackSpeed(1)], rarity: Rarity::Uncommon };
+    player.base_stats.intelligence = 7;
+    player.backpack.add_item(Item { item_type: Type::Potion, name: "Healing Potion".to_string(), modifications: vec![StatsMod::Heal(25)], rarity: Rarity::Common }).unwrap();
+    player.backpack.add_item(Item { item_type: Type::Scroll, name: "Tome of Blink".to_string(), modifications: vec![StatsMod::Teach(Special::Blink), StatsMod::Add(Stat::Intelligence(2))], rarity: Rarity::Rare }).unwrap();
+    player.backpack.add_item(Item { item_type: Type::Scroll, name: "Torn Note".to_string(), modifications: vec![StatsMod::Quest], rarity: Rarity::Common }).unwrap();
+
+    let mut enemy = Entity::new(4);
+    enemy.name = "(Easy) Crab".to_string();
+    enemy.monster_type = MonsterType::Crab;
+
+    let mut level = Level::new();
+    level.explore(3, 3);
+    let mut old_level = Level::new();
+    old_level.level = 1;
+    old_level.meta[4][4] = Tile::Next;
+    old_level.meta[3][3] = Tile::NpcSpawn { npc: 17 };
+
+    let mut pet = Entity::new(5);
+    pet.name = "Wolf".to_string();
+    pet.monster_type = MonsterType::Pet;
+    pet.faction = Faction::Allied;
+    pet.order = Order::Stay;
+
+    let mut hermit = Entity::new(6);
+    hermit.name = "Hermit".to_string();
+    hermit.monster_type = MonsterType::Npc;
+    hermit.faction = Faction::Neutral;
+
+    let mut dungeon = Dungeon::new();
+    dungeon.store(Floor { level: old_level, enemies: vec![pet, hermit] });
+
+    let mut statistics = Statistics::new(0);
+    statistics.kills = 12;
+    statistics.deepest_level = 3;
+    let progress = Progress { class: Class::Rogue, turn: 480, statistics, specials: [Special::Backstab, Special::Firebolt, Special::RoundHouse], spells: vec![Special::Firebolt], quests: vec![
+        Quest { giver: "Merchant".to_string(), objective: Objective::Kill { monster_type: MonsterType::Goblin, count: 4 }, progress: 4, reward: Reward::Gear, done: true },
+        Quest { giver: "Torn Note".to_string(), objective: Objective::Recover { item_type: Type::Legs, depth: 3 }, progress: 0, reward: Reward::Potions, done: false },
+        Quest { giver: "Hermit".to_string(), objective: Objective::Vault { depth: 2, guardians: vec![7, 9] }, progress: 1, reward: Reward::Tome, done: false },
+        Quest { giver: "Hermit".to_string(), objective: Objective::Vault { depth: 5, guardians: Vec::new() }, progress: 0, reward: Reward::Tome, done: false },
+    ] };
+
+    write_save(path, &player, &progress, 5, &level, &[enemy], &dungeon).unwrap();
+    let mut save = read_save(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(5, save.entity_count);
+    assert_eq!(Class::Rogue, save.progress.class);
+    assert_eq!(480, save.progress.turn);
+    assert_eq!(progress.statistics, save.progress.statistics);
+    assert_eq!(progress.specials, save.progress.specials);
+    assert_eq!(progress.spells, save.progress.spells);
+    assert_eq!(progress.quests, save.progress.quests);
+    assert_eq!("Tama Nu", save.player.name);
+    assert_eq!(3, save.player.pos_row);
+    assert_eq!(17, save.player.current_life);
+    assert_eq!(35, save.player.current_energy);
+    assert_eq!((3, 7), save.player.weapon.get_damage());
+    assert_eq!(Rarity::Uncommon, save.player.weapon.rarity);
+    assert_eq!(StatsMod::Heal(25), save.player.backpack.items[0].modifications[0]);
+    assert_eq!(7, save.player.base_stats.intelligence);
+    assert_eq!(vec![StatsMod::Teach(Special::Blink), StatsMod::Add(Stat::Intelligence(2))], save.player.backpack.items[1].modifications);
+    assert_eq!(vec![StatsMod::Quest], save.player.backpack.items[2].modifications);
+
+    assert_eq!(level.map, save.current.level.map);
+    assert_eq!(level.meta, save.current.level.meta);
+    assert_eq!(level.explored, save.current.level.explored);
+    assert_eq!(MonsterType::Crab, save.current.enemies[0].monster_type);
+    assert_eq!("(Easy) Crab", save.current.enemies[0].name);
+
+    assert_eq!(Faction::Hostile, save.current.enemies[0].faction);
+
+    let old_floor = save.dungeon.take(1).unwrap();
+    assert_eq!(Tile::Next, old_floor.level.meta[4][4]);
+    assert_eq!(MonsterType::Pet, old_floor.enemies[0].monster_type);
+    assert_eq!(Faction::Allied, old_floor.enemies[0].faction);
+    assert_eq!(Order::Stay, old_floor.enemies[0].order);
+    assert_eq!(Tile::NpcSpawn { npc: 17 }, old_floor.level.meta[3][3]);
+    assert_eq!(MonsterType::Npc, old_floor.enemies[1].monster_type);
+    assert_eq!(Faction::Neutral, old_floor.enemies[1].faction);
+}
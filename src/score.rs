@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::path::Path;
+
+pub const HIGH_SCORE_FILE: &str = "highscores.txt";
+
+/// How many scores are kept.
+pub const HIGH_SCORE_CAPACITY: usize = 10;
+
+/// Result of one finished run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighScore {
+    pub name: String,
+    pub class: String,
+    pub depth: i32,
+    pub kills: u32,
+    pub turns: u32,
+}
+
+impl HighScore {
+    /// Deeper is better, then more kills, then fewer turns.
+    fn rank(&self, other: &HighScore) -> Ordering {
+        other.depth.cmp(&self.depth)
+            .then(other.kills.cmp(&self.kills))
+            .then(self.turns.cmp(&other.turns))
+    }
+}
+
+/// Best runs, best first.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HighScores {
+    pub scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn new() -> HighScores {
+        HighScores { scores: Vec::new() }
+    }
+
+    /// A missing file is an empty table.
+    pub fn load(path: &str) -> io::Result<HighScores> {
+        if !Path::new(path).exists() {
+            return Result::Ok(HighScores::new());
+        }
+
+        let mut content = String::new();
+        File::open(path)?.read_to_string(&mut content)?;
+
+        HighScores::parse(&content)
+    }
+
+    /// Reads lines like "3 12 480 Warrior Tama Nu", the name is the rest of the line.
+    pub fn parse(content: &str) -> io::Result<HighScores> {
+        let mut high_scores = HighScores::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("Invalid high score line {}: {}", line_number + 1, line));
+            let values: Vec<&str> = line.splitn(5, ' ').collect();
+
+            if values.len() != 5 {
+                return Result::Err(invalid());
+            }
+
+            high_scores.scores.push(HighScore {
+                depth: values[0].parse().map_err(|_| invalid())?,
+                kills: values[1].parse().map_err(|_| invalid())?,
+                turns: values[2].parse().map_err(|_| invalid())?,
+                class: values[3].to_string(),
+                name: values[4].to_string(),
+            });
+        }
+
+        high_scores.scores.sort_by(|a, b| a.rank(b));
+
+        Result::Ok(high_scores)
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut out = String::new();
+
+        for score in &self.scores {
+            out.push_str(&format!("{} {} {} {} {}\n", score.depth, score.kills, score.turns, score.class, score.name));
+        }
+
+        let mut file = File::create(path)?;
+        file.write_all(out.as_bytes())
+    }
+
+    /// Adds the score at its rank, returns the place starting with 1 or None if it was not good enough.
+    pub fn add(&mut self, score: HighScore) -> Option<usize> {
+        //Equal scores keep the older one in front.
+        let index = self.scores.iter().position(|other| score.rank(other) == Ordering::Less).unwrap_or(self.scores.len());
+
+        if index >= HIGH_SCORE_CAPACITY {
+            return Option::None;
+        }
+
+        self.scores.insert(index, score);
+        self.scores.truncate(HIGH_SCORE_CAPACITY);
+
+        Option::Some(index + 1)
+    }
+}
+
+#[cfg(test)]
+fn score(name: &str, depth: i32, kills: u32, turns: u32) -> HighScore {
+    HighScore { name: name.to_string(), class: "Warrior".to_string(), depth, kills, turns }
+}
+
+#[test]
+fn test_high_score_rank() {
+    let mut high_scores = HighScores::new();
+
+    assert_eq!(Option::Some(1), high_scores.add(score("Shallow", 1, 30, 100)));
+    assert_eq!(Option::Some(1), high_scores.add(score("Deep", 4, 2, 900)));
+    assert_eq!(Option::Some(1), high_scores.add(score("Deep Fast", 4, 2, 300)));
+    assert_eq!(Option::Some(4), high_scores.add(score("Shallow Slow", 1, 30, 200)));
+
+    let names: Vec<&str> = high_scores.scores.iter().map(|score| score.name.as_str()).collect();
+    assert_eq!(vec!["Deep Fast", "Deep", "Shallow", "Shallow Slow"], names);
+
+    for _ in 0..HIGH_SCORE_CAPACITY {
+        high_scores.add(score("Deeper", 5, 0, 0));
+    }
+    assert_eq!(HIGH_SCORE_CAPACITY, high_scores.scores.len());
+    assert_eq!(Option::None, high_scores.add(score("Late", 1, 0, 0)));
+}
+
+#[test]
+fn test_high_score_parse() {
+    let high_scores = HighScores::parse("1 3 50 Rogue Tama Nu\n2 0 10 Ranger Nu\n").unwrap();
+
+    assert_eq!("Nu", high_scores.scores[0].name);
+    assert_eq!(score("Tama Nu", 1, 3, 50).name, high_scores.scores[1].name);
+    assert_eq!("Rogue", high_scores.scores[1].class);
+
+    assert!(HighScores::parse("1 3 Rogue").is_err());
+    assert!(HighScores::parse("one 3 50 Rogue Tama").is_err());
+}
@@ -0,0 +1,566 @@
+use super::character::backpack::Backpack;
+use super::character::class::Class;
+use super::character::entity::{Entity, MAX_ENERGY};
+use super::character::item::Type;
+use super::event::{Event, Subscriber};
+use super::frontend::Frontend;
+use super::glyph::render_map;
+use super::input::Input;
+use super::level::Level;
+use super::log::Log;
+use super::score::HighScore;
+use super::theme::Theme;
+
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+
+/// Messages shown in the history screen.
+const HISTORY_LINES: usize = 20;
+
+/// Backspaces sent by clear, more than any text field holds.
+const CLEAR_LENGTH: usize = 32;
+
+const HELP: &str = "Commands, directions are n, e, s and w:
+  move D, attack D, special 1-3 [D], wait, use, equip N, drop N, history
+  explore, travel [ROW COL]   walk to unexplored places, the stairs or a position
+  look   move a cursor with up, down, left and right, travel walks there
+  next   while aiming a special, aims at the next monster, enter uses it, back cancels
+  spellbook   lists specials and learned spells, special N binds the selected one to slot N
+  order, give N   tells the companions to stay or follow, hands item N to a companion
+  use   next to a friendly person starts talking, choose N answers
+  quests   lists the open and completed quests
+  up, down, left, right, enter, back
+  type TEXT, clear   edit the name or seed of a new game";
+
+/// Screen which was drawn last, commands depend on it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Screen {
+    Title,
+    NewGame,
+    Options,
+    HighScores,
+    Map,
+    Loot,
+    Inventory,
+    Menu,
+    GameOver,
+    History,
+    Look,
+    Target,
+    Spellbook,
+    /// Talking, with the number of answers.
+    Dialogue(usize),
+    Quests,
+}
+
+/// Prints every screen as plain text and reads one command per line,
+/// e.g. for shell pipelines, serial consoles or screen readers.
+pub struct TextFrontend<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    /// Inputs of the last command which are not yet handled.
+    pending: VecDeque<Input>,
+    /// Last drawn screen, printed before the next command is read.
+    frame: String,
+    /// Log messages which were not printed yet.
+    messages: Vec<String>,
+    /// Position of the newest message seen, counted with the dropped ones, and how often it was repeated then.
+    last_message: Option<(usize, u32)>,
+    screen: Screen,
+    backpack_size: usize,
+}
+
+impl<R: BufRead, W: Write> TextFrontend<R, W> {
+    pub fn new(input: R, output: W) -> TextFrontend<R, W> {
+        TextFrontend {
+            input,
+            output,
+            pending: VecDeque::new(),
+            frame: String::new(),
+            messages: Vec::new(),
+            last_message: Option::None,
+            screen: Screen::Title,
+            backpack_size: 0,
+        }
+    }
+
+    fn print_frame(&mut self) {
+        if self.frame.is_empty() && self.messages.is_empty() {
+            return;
+        }
+
+        let mut out = String::new();
+        out.push_str(&self.frame);
+        for message in self.messages.drain(..) {
+            out.push_str(&message);
+            out.push('\n');
+        }
+        self.frame.clear();
+
+        //A closed output ends the game on the next read.
+        self.output.write_all(out.as_bytes()).ok();
+        self.output.write_all(b"\n").ok();
+        self.output.flush().ok();
+    }
+
+    /// Leaves every screen towards quitting, a running game is saved first.
+    fn leave(&self) -> Input {
+        match self.screen {
+            Screen::Menu => Input::Use,
+            _ => Input::Quit,
+        }
+    }
+
+    fn read_input(&mut self) -> Input {
+        loop {
+            if let Option::Some(input) = self.pending.pop_front() {
+                return input;
+            }
+
+            self.print_frame();
+
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Result::Ok(0) | Result::Err(..) => return self.leave(),
+                Result::Ok(..) => {},
+            }
+
+            match parse_command(line.trim(), self.screen, self.backpack_size) {
+                Result::Ok(inputs) => self.pending.extend(inputs),
+                Result::Err(message) => {
+                    self.output.write_all(format!("{}\n\n", message).as_bytes()).ok();
+                    self.output.flush().ok();
+                },
+            }
+        }
+    }
+
+    /// Remembers all messages after the last one seen.
+    fn collect_messages(&mut self, log: &Log) {
+        let entries = log.last(log.len());
+
+        //A message repeated since is printed again with its new count.
+        let start = match self.last_message.and_then(|(position, count)| position.checked_sub(log.dropped()).map(|index| (index, count))) {
+            Option::Some((index, count)) if index < entries.len() => if entries[index].count > count { index } else { index + 1 },
+            _ => 0,
+        };
+
+        for entry in &entries[start..] {
+            self.messages.push(entry.text());
+        }
+
+        if let Option::Some(entry) = entries.last() {
+            self.last_message = Option::Some((log.dropped() + entries.len() - 1, entry.count));
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Subscriber for TextFrontend<R, W> {
+    //Everything worth reading ends up in the log.
+    fn notify(&mut self, _event: &Event) {}
+}
+
+impl<R: BufRead, W: Write> Frontend for TextFrontend<R, W> {
+    fn apply_theme(&mut self, _theme: &Theme) {}
+
+    fn get_input(&mut self) -> Input {
+        self.read_input()
+    }
+
+    fn get_text_input(&mut self) -> Input {
+        self.read_input()
+    }
+
+    fn resize(&mut self) {}
+
+    fn fits(&self) -> bool {
+        true
+    }
+
+    fn draw_too_small(&mut self) {}
+
+    fn draw_title(&mut self, entries: &[(&str, bool)], index: usize, message: &str) {
+        self.screen = Screen::Title;
+        self.frame = "R P G\n".to_string();
+
+        for (entry_index, &(name, enabled)) in entries.iter().enumerate() {
+            let cursor = if entry_index == index { ">" } else { " " };
+            let disabled = if enabled { "" } else { " (not available)" };
+            self.frame.push_str(&format!("{} {}{}\n", cursor, name, disabled));
+        }
+
+        push_message(&mut self.frame, message);
+    }
+
+    fn draw_new_game(&mut self, name: &str, class: &Class, seed: &str, active_field: usize, message: &str) {
+        self.screen = Screen::NewGame;
+        self.frame = "New Game\n".to_string();
+
+        let fields = [
+            format!("Name:  {}", name),
+            format!("Class: {}, {}", class.name(), class.description()),
+            format!("Seed:  {}", if seed.is_empty() { "random" } else { seed }),
+        ];
+
+        for (index, field) in fields.iter().enumerate() {
+            let cursor = if index == active_field { ">" } else { " " };
+            self.frame.push_str(&format!("{} {}\n", cursor, field));
+        }
+
+        push_message(&mut self.frame, message);
+    }
+
+    fn draw_options(&mut self, level_size: &str) {
+        self.screen = Screen::Options;
+        self.frame = format!("Options\n> Level size: {}\n", level_size);
+    }
+
+    fn draw_high_scores(&mut self, scores: &[HighScore]) {
+        self.screen = Screen::HighScores;
+        self.frame = "High Scores\n".to_string();
+
+        if scores.is_empty() {
+            self.frame.push_str("No runs yet.\n");
+        }
+        for (index, score) in scores.iter().enumerate() {
+            self.frame.push_str(&format!("{:>2}. {} the {}, depth {}, {} kills, {} turns\n", index + 1, score.name, score.class, score.depth, score.kills, score.turns));
+        }
+    }
+
+    fn draw(&mut self, log: &Log, level: &Level, player: &Entity, enemies: &[Entity], specials: &[(&str, u32, i32)]) {
+        self.screen = Screen::Map;
+        self.frame.clear();
+
+        //Every level row is printed, so line numbers are rows for travel.
+        for row in render_map(level, player, enemies) {
+            self.frame.push_str(row.trim_end());
+            self.frame.push('\n');
+        }
+
+        self.frame.push_str(&format!("{}  Life {}/{}  Energy {}/{}  Depth {}  Turn {}\n", player.name, player.current_life.max(0), player.calculate_max_life(), player.current_energy, MAX_ENERGY, level.level, log.turn()));
+
+        let specials: Vec<String> = specials.iter().enumerate()
+            .map(|(index, &(name, cool_down, cost))| match cool_down {
+                0 => format!("{} {} ready ({} energy)", index + 1, name, cost),
+                _ => format!("{} {} in {} turns ({} energy)", index + 1, name, cool_down, cost),
+            })
+            .collect();
+        self.frame.push_str(&format!("Specials: {}\n", specials.join(", ")));
+
+        self.backpack_size = player.backpack.size();
+
+        self.collect_messages(log);
+    }
+
+    fn draw_loot(&mut self, backpack: &Backpack, backpack_index: usize, active: bool, name: &str) {
+        if name.is_empty() {
+            self.screen = Screen::Inventory;
+            self.frame.push_str("Backpack\n");
+        } else {
+            self.screen = Screen::Loot;
+            self.frame.push_str(&format!("Loot of {}\n", name));
+        }
+
+        if backpack.size() == 0 {
+            self.frame.push_str("  Empty\n");
+        }
+        for (index, item) in backpack.items.iter().take(backpack.size()).enumerate() {
+            let cursor = if active && index == backpack_index { ">" } else { " " };
+            self.frame.push_str(&format!("{} {:>2} {}\n", cursor, index + 1, item.describe()));
+        }
+    }
+
+    fn draw_entity(&mut self, player: &Entity, character_pointer: Type, active: bool) {
+        self.screen = Screen::Inventory;
+        self.frame.push_str("Equipment\n");
+
+        for &(slot, name, item) in [(Type::Head, "Head", &player.head_item), (Type::Chest, "Chest", &player.chest_item), (Type::Legs, "Legs", &player.leg_item), (Type::Weapon, "Weapon", &player.weapon)].iter() {
+            let cursor = if active && slot == character_pointer { ">" } else { " " };
+            self.frame.push_str(&format!("{} {:<7} {}\n", cursor, name, item.describe()));
+        }
+
+        let stats = player.calculate_stats();
+        let damage = player.weapon.get_damage();
+        self.frame.push_str(&format!("Vitality {}, Strength {}, Defense {}, Speed {}, Intelligence {}, Damage {}-{}\n", stats.vitality, stats.strength, stats.defense, stats.speed, stats.intelligence, damage.0, damage.1));
+    }
+
+    fn draw_menu(&mut self) {
+        self.screen = Screen::Menu;
+        self.frame.push_str("Save and quit? enter saves, back returns to the game.\n");
+    }
+
+    fn draw_game_over(&mut self, summary: &[String]) {
+        self.screen = Screen::GameOver;
+        self.frame.push_str("Game Over\n");
+
+        for line in summary {
+            self.frame.push_str(line);
+            self.frame.push('\n');
+        }
+    }
+
+    fn draw_history(&mut self, log: &Log, offset: usize) {
+        self.screen = Screen::History;
+        self.frame = "Message History\n".to_string();
+
+        let offset = std::cmp::min(offset, log.len().saturating_sub(HISTORY_LINES));
+        let end = log.len() - offset;
+
+        for index in end.saturating_sub(HISTORY_LINES)..end {
+            if let Option::Some(entry) = log.get(index) {
+                self.frame.push_str(&format!("{:>5} {}\n", entry.turn, entry.text()));
+            }
+        }
+    }
+
+    fn draw_look(&mut self, row: usize, col: usize, description: &[String]) {
+        self.screen = Screen::Look;
+        self.frame.push_str(&format!("Look at row {}, col {}\n", row, col));
+
+        for line in description {
+            self.frame.push_str(&format!("  {}\n", line));
+        }
+    }
+
+    fn draw_target(&mut self, special: &str, direction: &str, area: &[(i32, i32)], targets: &[String]) {
+        self.screen = Screen::Target;
+
+        //The map rows come first, the area is marked on the floor.
+        let mut lines: Vec<Vec<char>> = self.frame.lines().map(|line| line.chars().collect()).collect();
+        for &(row, col) in area {
+            if let Option::Some(tile) = lines.get_mut(row as usize).and_then(|line| line.get_mut(col as usize)) {
+                if *tile == '.' {
+                    *tile = '*';
+                }
+            }
+        }
+
+        self.frame = lines.iter().map(|line| line.iter().collect::<String>() + "\n").collect();
+        let hits = if targets.is_empty() { "nothing".to_string() } else { targets.join(", ") };
+        self.frame.push_str(&format!("Aiming {} to the {}, hits {}\n", special, direction, hits));
+    }
+
+    fn draw_spellbook(&mut self, abilities: &[(&str, i32)], index: usize, slots: &[&str]) {
+        self.screen = Screen::Spellbook;
+        self.frame.push_str("Spellbook\n");
+
+        for (ability_index, &(name, cost)) in abilities.iter().enumerate() {
+            let cursor = if ability_index == index { ">" } else { " " };
+            let slot = slots.iter().position(|slot| *slot == name).map_or(String::new(), |slot| format!(", slot {}", slot + 1));
+
+            self.frame.push_str(&format!("{} {} ({} energy{})\n", cursor, name, cost, slot));
+        }
+    }
+
+    fn draw_dialogue(&mut self, name: &str, text: &[String], choices: &[&str], index: usize) {
+        self.screen = Screen::Dialogue(choices.len());
+        self.frame = format!("{}\n", name);
+
+        for line in text {
+            self.frame.push_str(&format!("  {}\n", line));
+        }
+        for (choice_index, choice) in choices.iter().enumerate() {
+            let cursor = if choice_index == index { ">" } else { " " };
+            self.frame.push_str(&format!("{} {}. {}\n", cursor, choice_index + 1, choice));
+        }
+    }
+
+    fn draw_quests(&mut self, quests: &[(String, bool)]) {
+        self.screen = Screen::Quests;
+        self.frame = "Quests\n".to_string();
+
+        if quests.is_empty() {
+            self.frame.push_str("  No quests yet, talk to people and read notes.\n");
+        }
+        for (quest, done) in quests {
+            self.frame.push_str(&format!("  {} {}\n", if *done { "[x]" } else { "[ ]" }, quest));
+        }
+    }
+}
+
+fn push_message(frame: &mut String, message: &str) {
+    if !message.is_empty() {
+        frame.push_str(message);
+        frame.push('\n');
+    }
+}
+
+/// Translates one line into the inputs of the game.
+fn parse_command(line: &str, screen: Screen, backpack_size: usize) -> Result<Vec<Input>, String> {
+    let mut words = line.split_whitespace();
+    let command = match words.next() {
+        Option::Some(command) => command.to_lowercase(),
+        Option::None => return Result::Ok(Vec::new()),
+    };
+    let arguments: Vec<&str> = words.collect();
+
+    let inputs = match (command.as_str(), arguments.as_slice()) {
+        ("help", []) | ("?", []) => return Result::Err(HELP.to_string()),
+
+        ("up", []) => vec![Input::MoveUp],
+        ("down", []) => vec![Input::MoveDown],
+        ("left", []) => vec![Input::MoveLeft],
+        ("right", []) => vec![Input::MoveRight],
+        ("enter", []) | ("use", []) => vec![Input::Use],
+        ("back", []) | ("quit", []) => vec![Input::Quit],
+
+        ("move", [direction]) => vec![parse_direction(direction, Input::MoveUp, Input::MoveRight, Input::MoveDown, Input::MoveLeft)?],
+        ("attack", [direction]) => vec![parse_attack(direction)?],
+        ("special", [slot]) => vec![parse_special(slot)?],
+        ("special", [slot, direction]) => vec![parse_special(slot)?, parse_attack(direction)?],
+        ("wait", []) => vec![Input::Nothing],
+        ("history", []) => vec![Input::History],
+        ("look", []) => vec![Input::Look],
+        ("next", []) => vec![Input::NextTarget],
+        ("spellbook", []) => vec![Input::Spellbook],
+        ("quests", []) => vec![Input::Quests],
+        ("order", []) => vec![Input::Order],
+        ("explore", []) => vec![Input::Explore],
+        ("travel", []) => vec![Input::Travel],
+        ("travel", [row, col]) => {
+            let parse_position = |value: &str| value.parse::<usize>().map_err(|_| format!("Invalid position \"{}\".", value));
+            vec![Input::TravelTo(parse_position(row)?, parse_position(col)?)]
+        },
+
+        ("equip", [number]) => vec![Input::UseItem(item_index(number, screen, backpack_size)?)],
+        ("drop", [number]) => vec![Input::DropItem(item_index(number, screen, backpack_size)?)],
+        ("give", [number]) => vec![Input::GiveItem(item_index(number, screen, backpack_size)?)],
+        ("choose", [number]) => choice_inputs(number, screen)?,
+
+        ("type", _) if !arguments.is_empty() => {
+            //Keeps the case and spaces of the typed text.
+            line.trim_start()[command.len()..].trim().chars().map(Input::Char).collect()
+        },
+        ("clear", []) => vec![Input::Backspace; CLEAR_LENGTH],
+
+        _ => return Result::Err(format!("Unknown command \"{}\", type help for all commands.", line)),
+    };
+
+    Result::Ok(inputs)
+}
+
+fn parse_direction(direction: &str, north: Input, east: Input, south: Input, west: Input) -> Result<Input, String> {
+    match direction.to_lowercase().as_str() {
+        "n" | "north" => Result::Ok(north),
+        "e" | "east" => Result::Ok(east),
+        "s" | "south" => Result::Ok(south),
+        "w" | "west" => Result::Ok(west),
+        _ => Result::Err(format!("Unknown direction \"{}\", use n, e, s or w.", direction)),
+    }
+}
+
+fn parse_attack(direction: &str) -> Result<Input, String> {
+    parse_direction(direction, Input::AttackUp, Input::AttackRight, Input::AttackDown, Input::AttackLeft)
+}
+
+fn parse_special(slot: &str) -> Result<Input, String> {
+    match slot {
+        "1" => Result::Ok(Input::SpecialOne),
+        "2" => Result::Ok(Input::SpecialTwo),
+        "3" => Result::Ok(Input::SpecialThree),
+        _ => Result::Err(format!("Unknown special \"{}\", use 1, 2 or 3.", slot)),
+    }
+}
+
+/// Index of backpack item number, counted from 1.
+fn item_index(number: &str, screen: Screen, backpack_size: usize) -> Result<usize, String> {
+    let number: usize = number.parse().map_err(|_| format!("Invalid item number \"{}\".", number))?;
+    if number == 0 || number > backpack_size {
+        return Result::Err(format!("There is no item {} in the backpack.", number));
+    }
+
+    match screen {
+        Screen::Map | Screen::Inventory => Result::Ok(number - 1),
+        _ => Result::Err("Items can only be used on the map or in the inventory.".to_string()),
+    }
+}
+
+/// Selects answer number, counted from 1, and gives it.
+fn choice_inputs(number: &str, screen: Screen) -> Result<Vec<Input>, String> {
+    let count = match screen {
+        Screen::Dialogue(count) => count,
+        _ => return Result::Err("There is nobody to answer.".to_string()),
+    };
+
+    let number: usize = number.parse().map_err(|_| format!("Invalid answer number \"{}\".", number))?;
+    if number == 0 || number > count {
+        return Result::Err(format!("There is no answer {}.", number));
+    }
+
+    let mut inputs = vec![Input::MoveUp; count];
+    inputs.extend(vec![Input::MoveDown; number - 1]);
+    inputs.push(Input::Use);
+
+    Result::Ok(inputs)
+}
+
+#[test]
+fn test_parse_command() {
+    assert_eq!(Result::Ok(vec![Input::MoveUp]), parse_command("move n", Screen::Map, 0));
+    assert_eq!(Result::Ok(vec![Input::AttackRight]), parse_command("attack east", Screen::Map, 0));
+    assert_eq!(Result::Ok(vec![Input::SpecialOne, Input::AttackLeft]), parse_command("special 1 w", Screen::Map, 0));
+    assert_eq!(Result::Ok(vec![Input::Char('T'), Input::Char('a'), Input::Char(' '), Input::Char('N')]), parse_command("type Ta N", Screen::NewGame, 0));
+    assert_eq!(Result::Ok(Vec::new()), parse_command("  ", Screen::Map, 0));
+    assert_eq!(Result::Ok(vec![Input::TravelTo(4, 12)]), parse_command("travel 4 12", Screen::Map, 0));
+
+    assert_eq!(Result::Ok(vec![Input::UseItem(2)]), parse_command("equip 3", Screen::Map, 5));
+    assert_eq!(Result::Ok(vec![Input::DropItem(0)]), parse_command("drop 1", Screen::Inventory, 5));
+    assert!(parse_command("equip 3", Screen::Look, 5).is_err());
+    assert!(parse_command("equip 6", Screen::Map, 5).is_err());
+    assert!(parse_command("move up", Screen::Map, 0).is_err());
+    assert!(parse_command("dance", Screen::Map, 0).is_err());
+
+    assert_eq!(Result::Ok(vec![Input::MoveUp, Input::MoveUp, Input::MoveUp, Input::MoveDown, Input::Use]), parse_command("choose 2", Screen::Dialogue(3), 0));
+    assert!(parse_command("choose 4", Screen::Dialogue(3), 0).is_err());
+    assert!(parse_command("choose 1", Screen::Map, 0).is_err());
+}
+
+#[test]
+fn test_new_messages_are_printed_once() {
+    let mut frontend = TextFrontend::new("wait\n".as_bytes(), Vec::new());
+    let mut level = Level::new();
+    level.level = 2;
+    let mut player = Entity::new(0);
+    player.name = "Tama Nu".to_string();
+    player.pos_row = 2;
+    player.pos_col = 2;
+
+    let mut log = Log::new();
+    log.add_message("Welcome.".to_string());
+    frontend.draw(&log, &level, &player, &[], &[("Storm", 0, 40)]);
+
+    log.next_turn();
+    log.add_message("Something happened.".to_string());
+    frontend.draw(&log, &level, &player, &[], &[("Storm", 3, 40)]);
+
+    assert_eq!(Input::Nothing, frontend.get_input());
+    //End of input leaves the map.
+    assert_eq!(Input::Quit, frontend.get_input());
+
+    let output = String::from_utf8(frontend.output).unwrap();
+    assert!(output.contains("Tama Nu  Life 200/200  Energy 100/100  Depth 2  Turn 1\nSpecials: 1 Storm in 3 turns (40 energy)\nWelcome.\nSomething happened.\n"), "{}", output);
+    assert_eq!(1, output.matches("Welcome.").count());
+}
+
+#[test]
+fn test_repeated_messages_are_printed_once() {
+    let mut frontend = TextFrontend::new("".as_bytes(), Vec::new());
+    let level = Level::new();
+    let player = Entity::new(0);
+
+    //Old messages are dropped from a short log.
+    let mut log = Log::with_capacity(2);
+    log.add_message("Welcome.".to_string());
+    log.add_message("Hit.".to_string());
+    frontend.draw(&log, &level, &player, &[], &[]);
+
+    log.add_message("Hit.".to_string());
+    frontend.draw(&log, &level, &player, &[], &[]);
+
+    log.add_message("Missed.".to_string());
+    log.add_message("Hit.".to_string());
+    frontend.draw(&log, &level, &player, &[], &[]);
+    frontend.draw(&log, &level, &player, &[], &[]);
+
+    assert_eq!(vec!["Welcome.", "Hit.", "Hit. x2", "Missed.", "Hit."], frontend.messages);
+}
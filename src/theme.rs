@@ -0,0 +1,222 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+
+pub const THEME_FILE: &str = "theme.txt";
+
+/// Console colour, 0-7 are the basic colours, 8-15 their bright variants and 16-255 the xterm palette.
+pub type Color = i16;
+
+/// Everything on screen with its own colour.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Element {
+    Highlight,
+
+    Wall,
+    Floor,
+    Stairs,
+
+    Player,
+    /// Living pets and other allies of the player.
+    Companion,
+    /// Living friendly entities to talk to.
+    Npc,
+    MonsterEasy,
+    MonsterNormal,
+    MonsterHard,
+    MonsterBoss,
+    /// Dead monster without loot.
+    Corpse,
+    /// Dead monster with loot.
+    Loot,
+
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+pub const ELEMENTS: [Element; 17] = [
+    Element::Highlight,
+    Element::Wall, Element::Floor, Element::Stairs,
+    Element::Player, Element::Companion, Element::Npc, Element::MonsterEasy, Element::MonsterNormal, Element::MonsterHard, Element::MonsterBoss, Element::Corpse, Element::Loot,
+    Element::Common, Element::Uncommon, Element::Rare, Element::Legendary,
+];
+
+impl Element {
+    /// Position in ELEMENTS, which orders the colours of a theme.
+    pub fn index(&self) -> usize {
+        ELEMENTS.iter().position(|element| element == self).expect("Every element is listed in ELEMENTS.")
+    }
+
+    /// Name used in the theme file.
+    pub fn key(&self) -> &'static str {
+        match *self {
+            Element::Highlight => "highlight",
+            Element::Wall => "wall",
+            Element::Floor => "floor",
+            Element::Stairs => "stairs",
+            Element::Player => "player",
+            Element::Companion => "companion",
+            Element::Npc => "npc",
+            Element::MonsterEasy => "monster_easy",
+            Element::MonsterNormal => "monster_normal",
+            Element::MonsterHard => "monster_hard",
+            Element::MonsterBoss => "monster_boss",
+            Element::Corpse => "corpse",
+            Element::Loot => "loot",
+            Element::Common => "common",
+            Element::Uncommon => "uncommon",
+            Element::Rare => "rare",
+            Element::Legendary => "legendary",
+        }
+    }
+}
+
+const COLOR_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// Colour for every element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    colors: Vec<Color>,
+}
+
+impl Theme {
+    pub fn new() -> Theme {
+        let colors = ELEMENTS.iter().map(|element| match *element {
+            Element::Highlight => 15,
+            Element::Wall => 7,
+            Element::Floor => 244,
+            Element::Stairs => 11,
+            Element::Player => 15,
+            Element::Companion => 12,
+            Element::Npc => 6,
+            Element::MonsterEasy => 2,
+            Element::MonsterNormal => 3,
+            Element::MonsterHard => 9,
+            Element::MonsterBoss => 13,
+            Element::Corpse => 8,
+            Element::Loot => 14,
+            Element::Common => 7,
+            Element::Uncommon => 10,
+            Element::Rare => 12,
+            Element::Legendary => 13,
+        }).collect();
+
+        Theme { colors }
+    }
+
+    /// Reads lines like "wall white" or "floor 244", missing elements keep their default colour.
+    pub fn load(path: &str) -> io::Result<Theme> {
+        let mut content = String::new();
+        File::open(path)?.read_to_string(&mut content)?;
+
+        Theme::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> io::Result<Theme> {
+        let mut theme = Theme::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let values: Vec<&str> = line.split_whitespace().collect();
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("Invalid theme line {}: {}", line_number + 1, line));
+
+            if values.len() != 2 {
+                return Result::Err(invalid());
+            }
+
+            let index = ELEMENTS.iter().position(|element| element.key() == values[0]).ok_or_else(invalid)?;
+            theme.colors[index] = parse_color(values[1]).ok_or_else(invalid)?;
+        }
+
+        Result::Ok(theme)
+    }
+
+    pub fn color(&self, element: Element) -> Color {
+        self.colors[element.index()]
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::new()
+    }
+}
+
+/// Accepts a number from 0 to 255, a colour name or a colour name prefixed with "bright_".
+fn parse_color(value: &str) -> Option<Color> {
+    if let Result::Ok(number) = value.parse::<Color>() {
+        return if (0..256).contains(&number) { Option::Some(number) } else { Option::None };
+    }
+
+    let (name, offset) = if let Some(name) = value.strip_prefix("bright_") { (name, 8) } else { (value, 0) };
+
+    COLOR_NAMES.iter().position(|color_name| *color_name == name).map(|index| index as Color + offset)
+}
+
+/// Nearest basic colour for consoles with only 8 colours, true if it should be drawn bold.
+pub fn to_basic(color: Color) -> (Color, bool) {
+    match color {
+        0..=7 => (color, false),
+        8..=15 => (color - 8, true),
+        16..=231 => {
+            let cube = color - 16;
+            let (red, green, blue) = (cube / 36, (cube / 6) % 6, cube % 6);
+            let max = red.max(green).max(blue);
+
+            //Components which are at least half as strong as the strongest count.
+            let on = |component: Color| if component > 0 && component * 2 > max { 1 } else { 0 };
+
+            (on(red) | on(green) << 1 | on(blue) << 2, max >= 4)
+        },
+        _ => {
+            let gray = color - 232;
+
+            if gray < 6 {
+                (0, true)
+            } else {
+                (7, gray >= 18)
+            }
+        },
+    }
+}
+
+#[test]
+fn test_parse_theme() {
+    let theme = Theme::parse("# Comment\n\nwall red\nfloor 244\nloot bright_cyan\n").unwrap();
+
+    assert_eq!(1, theme.color(Element::Wall));
+    assert_eq!(244, theme.color(Element::Floor));
+    assert_eq!(14, theme.color(Element::Loot));
+    assert_eq!(Theme::new().color(Element::Player), theme.color(Element::Player));
+
+    assert!(Theme::parse("wall").is_err());
+    assert!(Theme::parse("roof red").is_err());
+    assert!(Theme::parse("wall 256").is_err());
+}
+
+#[test]
+fn test_to_basic() {
+    assert_eq!((3, false), to_basic(3));
+    assert_eq!((1, true), to_basic(9));
+    //Pure red, dark blue and orange of the colour cube.
+    assert_eq!((1, true), to_basic(196));
+    assert_eq!((4, false), to_basic(17));
+    assert_eq!((3, true), to_basic(214));
+    //Grays.
+    assert_eq!((0, true), to_basic(233));
+    assert_eq!((7, false), to_basic(244));
+    assert_eq!((7, true), to_basic(255));
+}
+
+#[test]
+fn test_element_index() {
+    for (index, element) in ELEMENTS.iter().enumerate() {
+        assert_eq!(index, element.index());
+    }
+}
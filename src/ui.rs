@@ -6,7 +6,17 @@ use super::character::entity::*;
 use super::character::backpack::*;
 use super::character::item::*;
 use super::character::stats::*;
+use super::character::class::Class;
 use super::log::*;
+use super::event::{Event, Subscriber};
+use super::camera::Camera;
+use super::score::HighScore;
+use super::theme::{Theme, Element, ELEMENTS, to_basic};
+use super::frontend::Frontend;
+use super::input::Input;
+use super::glyph::*;
+
+use std::cmp;
 
 pub struct Window {
     player_window: WINDOW,
@@ -16,27 +26,37 @@ pub struct Window {
     character_window: WINDOW,
     item_window: WINDOW,
     menu_window: WINDOW,
-}
+    history_window: WINDOW,
+    screen_window: WINDOW,
+
+    /// Ids of entities which have been hit since the last map draw.
+    hit_ids: Vec<u32>,
+
+    /// Part of the level shown by the last map draw.
+    camera: Camera,
 
-static COLOR_HIGHLIGHT: i16 = 16;
-static COLOR_BACKGROUND: i16 = 17;
+    /// Attributes for every theme element.
+    palette: Vec<attr_t>,
+}
 
-static COLOR_PAIR_HIGHLIGHT: i16 = 1;
+/// Smallest console the panels can be laid out on.
+pub const MIN_CONSOLE_LINES: i32 = 20;
+pub const MIN_CONSOLE_COLS: i32 = 80;
 
 impl Window {
     pub fn new() -> Window {
         let locale_conf = LcCategory::all;
         setlocale(locale_conf, "ISO-8859-1");
 
-        initscr();
-
-        start_color();
-
-        init_color(COLOR_HIGHLIGHT, 250 * 4, 250 * 4, 250 * 4);
-        init_color(COLOR_BACKGROUND, 0 * 4, 0 * 4, 0 * 4);
+        //Escape goes back, so do not wait long for escape sequences.
+        std::env::set_var("ESCDELAY", "25");
 
-        init_pair(COLOR_PAIR_HIGHLIGHT, COLOR_HIGHLIGHT, COLOR_BACKGROUND);
+        initscr();
 
+        if has_colors() {
+            start_color();
+            use_default_colors();
+        }
 
         raw();//cbreak();
         //halfdelay(5);
@@ -52,75 +72,19 @@ impl Window {
             character_window: create_character_window(),
             item_window: create_item_window(),
             menu_window: create_menu_window(),
-        }
-    }
+            history_window: create_history_window(),
+            screen_window: create_screen_window(),
 
-    pub fn draw_game_over(&mut self) {
-        destroy_win(self.menu_window);
-        self.menu_window = create_menu_window();
+            hit_ids: Vec::new(),
 
-        let message = "Game Over".to_string();
-        mvwprintw(self.menu_window, 1, (33 - message.len() as i32) / 2 + 1, &message);
+            camera: Camera::new(0, 0),
 
-        wrefresh(self.menu_window);
-    }
-
-    pub fn draw_menu(&mut self) {
-        destroy_win(self.menu_window);
-        self.menu_window = create_menu_window();
-
-        let message = "Press Q to Return to Game".to_string();
-        mvwprintw(self.menu_window, 1, (33 - message.len() as i32) / 2 +1, &message);
-        let message = "Press E to Exit".to_string();
-        mvwprintw(self.menu_window, 2, (33 - message.len() as i32) / 2 +1, &message);
-
-        wrefresh(self.menu_window);
-    }
-
-    pub fn draw_entity(&mut self, player: &Entity, character_pointer: Type, active: bool) {
-        destroy_win(self.character_window);
-        self.character_window = create_character_window();
-
-        let character_offset_row = 1;
-        let character_offset_col = 1;
-
-        mvwprintw(self.character_window, character_offset_row + 0, character_offset_col, &format!(" Head:     {}", player.head_item.name));
-        mvwprintw(self.character_window, character_offset_row + 1, character_offset_col, &format!(" Chest:    {}", player.chest_item.name));
-        mvwprintw(self.character_window, character_offset_row + 2, character_offset_col, &format!(" Legs:     {}", player.leg_item.name));
-        mvwprintw(self.character_window, character_offset_row + 3, character_offset_col, &format!(" Weapon:   {}", player.weapon.name));
-        mvwprintw(self.character_window, character_offset_row + 4, character_offset_col, "----------------");
-
-        let stats = player.calculate_stats();
-        let damage = player.weapon.get_damage();
-        mvwprintw(self.character_window, character_offset_row + 5, character_offset_col, &format!(" Vitality: {}", stats.vitality));
-        mvwprintw(self.character_window, character_offset_row + 6, character_offset_col, &format!(" Strength: {}", stats.strength));
-        mvwprintw(self.character_window, character_offset_row + 7, character_offset_col, &format!(" Defense:  {}", stats.defense));
-        mvwprintw(self.character_window, character_offset_row + 8, character_offset_col, &format!(" Speed:    {}", stats.speed));
-        mvwprintw(self.character_window, character_offset_row + 9, character_offset_col, &format!(" Damage:   {}-{}", damage.0, damage.1));
-
-        if active {
-            match character_pointer {
-                Type::Head => {
-                    mvwaddch(self.character_window, (character_offset_row + 0) as i32, character_offset_col, resolve_item_cursor());
-                    self.draw_item(&player.head_item);
-                },
-                Type::Chest => {
-                    mvwaddch(self.character_window, (character_offset_row + 1) as i32, character_offset_col, resolve_item_cursor());
-                    self.draw_item(&player.chest_item);
-                },
-                Type::Legs => {
-                    mvwaddch(self.character_window, (character_offset_row + 2) as i32, character_offset_col, resolve_item_cursor());
-                    self.draw_item(&player.leg_item);
-                },
-                Type::Weapon => {
-                    mvwaddch(self.character_window, (character_offset_row + 3) as i32, character_offset_col, resolve_item_cursor());
-                    self.draw_item(&player.weapon);
-                },
-                _ => {},
-            }
+            palette: create_palette(&Theme::new()),
         }
+    }
 
-        wrefresh(self.character_window);
+    fn attr(&self, element: Element) -> attr_t {
+        self.palette[element.index()]
     }
 
     pub fn draw_item(&mut self, item: &Item) {
@@ -131,7 +95,8 @@ impl Window {
         let item_offset_col = 1;
 
         let mut row = item_offset_row;
-        mvwprintw(self.item_window, row as i32, item_offset_col, &item.name);
+        wmove(self.item_window, row as i32, item_offset_col);
+        self.print_item_name(self.item_window, item);
         row += 1;
 
         let type_str = resolve_type(item.item_type);
@@ -159,12 +124,21 @@ impl Window {
                         },
                         Stat::Vitality(val) => {
                             mvwprintw(self.item_window, row as i32, item_offset_col, &format!("Vitality {}", val));
+                        },
+                        Stat::Intelligence(val) => {
+                            mvwprintw(self.item_window, row, item_offset_col, &format!("Intelligence {}", val));
                         }
                     }
                 }
                 &StatsMod::Heal(val) => {
                     mvwprintw(self.item_window, row as i32, item_offset_col, &format!("Heals {}%", val));
                 }
+                &StatsMod::Teach(spell) => {
+                    mvwprintw(self.item_window, row, item_offset_col, &format!("Teaches {}", spell.name()));
+                }
+                &StatsMod::Quest => {
+                    mvwprintw(self.item_window, row, item_offset_col, "Starts a quest");
+                }
             }
             row += 1;
         }
@@ -172,65 +146,7 @@ impl Window {
         wrefresh(self.item_window);
     }
 
-    pub fn draw_loot(&mut self, backpack: &Backpack, backpack_index: usize, active: bool, name: &String) {
-        destroy_win(self.backpack_window);
-        self.backpack_window = create_backpack_window();
-
-        let mut loot_offset_row = 1;
-        let loot_offset_col = 2;
-        let display_row_count = 5;
-
-        let mut items: Vec<&Item> = Vec::new();
-        let start_index = display_row_count * (backpack_index / display_row_count);
-
-        //Fill items vector with items to display.
-        for index in start_index..start_index + display_row_count {
-            if !backpack.empty_slot(index) {
-                items.push(&backpack.items[index]);
-            }
-        }
-
-        //Display name
-        if name.len() > 0 {
-            mvwprintw(self.backpack_window, loot_offset_row as i32, loot_offset_col, &name);
-            loot_offset_row += 1;
-        }
-
-        //Display items.
-        let mut counter = 0;
-        for item in items {
-            mvwprintw(self.backpack_window, (counter + loot_offset_row) as i32, 1 + loot_offset_col, &item.name);
-            counter += 1;
-        }
-
-        if active {
-            //Mark current items.
-            mvwaddch(self.backpack_window, ((backpack_index % display_row_count) + loot_offset_row) as i32, loot_offset_col, resolve_item_cursor());
-        }
-
-        //Fill empty spaces.
-        while counter < display_row_count {
-            mvwprintw(self.backpack_window, (counter + loot_offset_row) as i32, 1 + loot_offset_col, "Empty");
-            counter += 1;
-        }
-
-        if active {
-            //Draw full item
-            self.draw_item(&backpack.items[backpack_index]);
-        }
-
-        wrefresh(self.backpack_window);
-    }
-
-    pub fn draw(&mut self, log: &mut Log, level: &Level, player: &Entity, enemies: &Vec<Entity>, special_one_ready: bool, special_two_ready: bool) {
-
-        self.draw_player(player, level, special_one_ready, special_two_ready);
-        self.draw_game_msg(log);
-        self.draw_map(level, player, enemies);
-
-    }
-
-    pub fn draw_player(&mut self, player: &Entity, level: &Level, special_one_ready: bool, special_two_ready: bool) {
+    pub fn draw_player(&mut self, player: &Entity, level: &Level, specials: &[(&str, u32, i32)]) {
         destroy_win(self.player_window);
         self.player_window = create_player_window();
 
@@ -267,24 +183,28 @@ impl Window {
             mvwaddch(self.player_window, 1, 11, '#' as u32);
         }
         mvwaddch(self.player_window, 1, 12, ']' as u32);
-        mvwprintw(self.player_window, 1, 14, &player.name);
-
+        mvwaddnstr(self.player_window, 1, 14, &player.name, 10);
+        mvwprintw(self.player_window, 1, 25, &format!("EN {:>3}", player.current_energy));
+
+        //Ready specials are highlighted, the others show their cooldown or are dimmed without energy.
+        let mut col = 33;
+        for &(name, cool_down, cost) in specials {
+            let label = if cool_down > 0 { format!("{} {}", name, cool_down) } else { name.to_string() };
+            let attr = if cool_down > 0 {
+                0
+            } else if player.current_energy < cost {
+                A_DIM()
+            } else {
+                self.attr(Element::Highlight)
+            };
 
-        let attr = COLOR_PAIR(COLOR_PAIR_HIGHLIGHT);
-        if special_one_ready {
             wattron(self.player_window, attr);
-        }
-
-        mvwprintw(self.player_window, 1, 30, "Storm");
-        wattroff(self.player_window, attr);
+            mvwprintw(self.player_window, 1, col, &label);
+            wattroff(self.player_window, attr);
 
-        if special_two_ready {
-            wattron(self.player_window, attr);
+            col += label.len() as i32 + 2;
         }
 
-        mvwprintw(self.player_window, 1, 42, "Round-House");
-        wattroff(self.player_window, attr);
-
         let x = getmaxx(self.player_window);
         let dungeon = format!("{} Dungeon", level.level);
         mvwaddstr(self.player_window, 1, x - (dungeon.len() + 1) as i32, &dungeon);
@@ -292,24 +212,41 @@ impl Window {
         wrefresh(self.player_window);
     }
 
-    pub fn draw_map(&mut self, level: &Level, player: &Entity, enemies: &Vec<Entity>) {
+    pub fn draw_map(&mut self, level: &Level, player: &Entity, enemies: &[Entity]) {
         destroy_win(self.map_window);
         self.map_window = create_map_window();
 
         let offset = 1;
+        let window = self.map_window;
+
+        //Scroll the map, if it is bigger than the window.
+        let mut camera = Camera::new(getmaxy(window) - 2, getmaxx(window) - 2);
+        camera.focus(level.map.len(), level.map[0].len(), player.pos_row, player.pos_col);
+        self.camera = camera;
+
+        let palette = &self.palette;
+        let put_dimmed = |row: i32, col: i32, glyph: char, element: Element, dimmed: bool| {
+            if let Option::Some((screen_row, screen_col)) = camera.to_screen(row, col) {
+                let attr = if dimmed { A_DIM() } else { 0 };
+                mvwaddch(window, screen_row + offset, screen_col + offset, glyph as u32 | palette[element.index()] | attr);
+            }
+        };
+        let put = |row: i32, col: i32, glyph: char, element: Element| put_dimmed(row, col, glyph, element, false);
 
-        //Draw Map.
+        //Draw Map, tiles which were never seen are dimmed.
         let mut row_index: usize = 0;
         for row in &level.map {
             let mut col_index: usize = 0;
 
             for col in row {
+                let dimmed = !level.is_explored(row_index, col_index);
+
                 match &level.meta[row_index][col_index] {
                     &Tile::PlSpawn | &Tile::Next => {
-                        mvwaddch(self.map_window, row_index as i32 + offset, col_index as i32 + offset, resolve_tile(&level.meta[row_index][col_index]));
+                        put_dimmed(row_index as i32, col_index as i32, resolve_tile(&level.meta[row_index][col_index]), Element::Stairs, dimmed);
                     },
                     _ => {
-                        mvwaddch(self.map_window, row_index as i32 + offset, col_index as i32 + offset, resolve_tile(col));
+                        put_dimmed(row_index as i32, col_index as i32, resolve_tile(col), resolve_tile_element(col), dimmed);
                     }
                 }
 
@@ -325,7 +262,7 @@ impl Window {
                 continue;
             }
 
-            mvwaddch(self.map_window, enemy.pos_row + offset, enemy.pos_col + offset, resolve_enemy(enemy));
+            put(enemy.pos_row, enemy.pos_col, resolve_enemy(enemy), resolve_enemy_element(enemy));
         }
 
         //Draw Enemies with loot.
@@ -335,7 +272,7 @@ impl Window {
             match lootable_enemies_iter.next() {
                 Some(enemy) => {
                     if enemy.monster_type != MonsterType::Boss {
-                        mvwaddch(self.map_window, enemy.pos_row + offset, enemy.pos_col + offset, resolve_enemy(enemy));
+                        put(enemy.pos_row, enemy.pos_col, resolve_enemy(enemy), resolve_enemy_element(enemy));
                     }
                 },
                 None => { break; }
@@ -345,19 +282,32 @@ impl Window {
         //Draw alive enemies, avoid that lootable enemy is over alive enemy.
         for enemy in enemies {
             if !enemy.is_death() && enemy.monster_type == MonsterType::Boss {
-                mvwaddch(self.map_window, enemy.pos_row + offset, enemy.pos_col + offset, 'O' as u32);
-                mvwaddch(self.map_window, enemy.pos_row - 1 + offset, enemy.pos_col + offset, 'o' as u32);
-                mvwaddch(self.map_window, enemy.pos_row + offset, enemy.pos_col + 1 + offset, '-' as u32);
-                mvwaddch(self.map_window, enemy.pos_row + offset, enemy.pos_col - 1 + offset, '-' as u32);
-                mvwaddch(self.map_window, enemy.pos_row + 1 + offset, enemy.pos_col - 1 + offset, '/' as u32);
-                mvwaddch(self.map_window, enemy.pos_row + 1 + offset, enemy.pos_col + 1 + offset, '\\' as u32);
+                put(enemy.pos_row, enemy.pos_col, 'O', Element::MonsterBoss);
+                put(enemy.pos_row - 1, enemy.pos_col, 'o', Element::MonsterBoss);
+                put(enemy.pos_row, enemy.pos_col + 1, '-', Element::MonsterBoss);
+                put(enemy.pos_row, enemy.pos_col - 1, '-', Element::MonsterBoss);
+                put(enemy.pos_row + 1, enemy.pos_col - 1, '/', Element::MonsterBoss);
+                put(enemy.pos_row + 1, enemy.pos_col + 1, '\\', Element::MonsterBoss);
             } else if !enemy.is_death() && enemy.monster_type != MonsterType::Boss {
-                mvwaddch(self.map_window, enemy.pos_row + offset, enemy.pos_col + offset, resolve_enemy(enemy));
+                put(enemy.pos_row, enemy.pos_col, resolve_enemy(enemy), resolve_enemy_element(enemy));
             }
         }
 
         //Draw Player.
-        mvwaddch(self.map_window, player.pos_row + offset, player.pos_col + offset, resolve_player(player));
+        put(player.pos_row, player.pos_col, resolve_player(player), Element::Player);
+
+        //Flash everyone who has been hit.
+        for entity in enemies.iter().chain(Some(player)) {
+            if !self.hit_ids.contains(&entity.id) {
+                continue;
+            }
+
+            if let Option::Some((screen_row, screen_col)) = camera.to_screen(entity.pos_row, entity.pos_col) {
+                mvwchgat(window, screen_row + offset, screen_col + offset, 1, A_REVERSE(), 0);
+            }
+        }
+        self.hit_ids.clear();
+
         //Draw Effects.
         /*for effect in effect_list {
             for &(row, col) in &effect.area {
@@ -371,27 +321,51 @@ impl Window {
         wrefresh(self.map_window);
     }
 
-    pub fn draw_game_msg(&mut self, log: &mut Log) {
+    pub fn draw_game_msg(&mut self, log: &Log) {
         destroy_win(self.status_window);
         self.status_window = create_status_window();
 
-        let msg = log.get_message();
-        match msg {
-            Option::Some(val) => {
-                mvwaddstr(self.status_window, 1, 1, &val);
-            },
-            Option::None => {
-                mvwaddstr(self.status_window, 1, 1, "                                      ");
-            },
+        let line_count = (getmaxy(self.status_window) - 2) as usize;
+        let width = getmaxx(self.status_window) - 2;
+
+        //Messages of earlier turns are dimmed.
+        for (row, entry) in (1..).zip(log.last(line_count)) {
+            let attr = if entry.turn == log.turn() { 0 } else { A_DIM() };
+
+            wattron(self.status_window, attr);
+            mvwaddnstr(self.status_window, row, 1, &entry.text(), width);
+            wattroff(self.status_window, attr);
         }
 
         box_(self.status_window, 0, 0);
+
+        //Hint if this turn has more messages than fit.
+        let hidden = log.current_turn_count().saturating_sub(line_count);
+        if hidden > 0 {
+            let hint = format!(" +{} more, press l ", hidden);
+            mvwaddstr(self.status_window, getmaxy(self.status_window) - 1, width - hint.len() as i32, &hint);
+        }
+
         wrefresh(self.status_window);
     }
 
-    pub fn get_input(&self) -> Input {
-        keypad(self.map_window, true);
-        resolve_input(wgetch(self.map_window))
+    fn draw_screen_help(&self, help: &str) {
+        let width = getmaxx(self.screen_window);
+        mvwaddstr(self.screen_window, getmaxy(self.screen_window) - 1, width - 2 - help.len() as i32, help);
+    }
+
+    /// Prints the item name at the cursor in the colour of its rarity.
+    fn print_item_name(&self, window: WINDOW, item: &Item) {
+        let attr = self.attr(resolve_rarity_element(item.rarity));
+
+        wattron(window, attr);
+        waddstr(window, &item.name);
+        wattroff(window, attr);
+    }
+
+    /// Checks if the console is big enough for all panels.
+    pub fn fits_console() -> bool {
+        LINES() >= MIN_CONSOLE_LINES && COLS() >= MIN_CONSOLE_COLS
     }
 
     pub fn clear() {
@@ -399,55 +373,555 @@ impl Window {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum Input {
-    Nothing,
+impl Frontend for Window {
+    fn apply_theme(&mut self, theme: &Theme) {
+        self.palette = create_palette(theme);
+    }
+
+    fn get_input(&mut self) -> Input {
+        keypad(self.map_window, true);
+        resolve_input(wgetch(self.map_window))
+    }
+
+    fn get_text_input(&mut self) -> Input {
+        keypad(self.map_window, true);
+        resolve_text_input(wgetch(self.map_window))
+    }
+
+    /// Lays out all windows again after the console has been resized.
+    fn resize(&mut self) {
+        //Input is read from the map window, keep the old one until the console is big enough.
+        if !Window::fits_console() {
+            return;
+        }
+
+        //Destroying the old windows would blank parts of the new layout.
+        for window in &[self.player_window, self.map_window, self.status_window, self.backpack_window, self.character_window, self.item_window, self.menu_window, self.history_window, self.screen_window] {
+            delwin(*window);
+        }
+
+        self.player_window = create_player_window();
+        self.map_window = create_map_window();
+        self.status_window = create_status_window();
+        self.backpack_window = create_backpack_window();
+        self.character_window = create_character_window();
+        self.item_window = create_item_window();
+        self.menu_window = create_menu_window();
+        self.history_window = create_history_window();
+        self.screen_window = create_screen_window();
+
+        //Hide the borders of the new windows until they are drawn.
+        clear();
+        refresh();
+    }
+
+    fn fits(&self) -> bool {
+        Window::fits_console()
+    }
+
+    fn draw_too_small(&mut self) {
+        clear();
+
+        let message = format!("Please enlarge the console to at least {}x{}.", MIN_CONSOLE_COLS, MIN_CONSOLE_LINES);
+        mvaddnstr(0, 0, &message, COLS());
+
+        refresh();
+    }
+
+    /// Title screen, disabled entries are dimmed.
+    fn draw_title(&mut self, entries: &[(&str, bool)], index: usize, message: &str) {
+        destroy_win(self.screen_window);
+        self.screen_window = create_screen_window();
+
+        let center_col = getmaxx(self.screen_window) / 2;
+        let mut row = getmaxy(self.screen_window) / 2 - entries.len() as i32 - 2;
+
+        let title = "R P G";
+        wattron(self.screen_window, self.attr(Element::Highlight));
+        mvwaddstr(self.screen_window, row, center_col - title.len() as i32 / 2, title);
+        wattroff(self.screen_window, self.attr(Element::Highlight));
+        row += 3;
+
+        for (entry_index, &(name, enabled)) in entries.iter().enumerate() {
+            let attr = if enabled { 0 } else { A_DIM() };
+            let col = center_col - 6;
+
+            wattron(self.screen_window, attr);
+            mvwaddstr(self.screen_window, row, col, name);
+            wattroff(self.screen_window, attr);
+
+            if entry_index == index {
+                mvwaddch(self.screen_window, row, col - 2, resolve_item_cursor());
+            }
+
+            row += 1;
+        }
+
+        mvwaddstr(self.screen_window, row + 1, center_col - message.len() as i32 / 2, message);
+
+        self.draw_screen_help(" Up/Down select, E choose, Q quit ");
+        wrefresh(self.screen_window);
+    }
+
+    fn draw_new_game(&mut self, name: &str, class: &Class, seed: &str, active_field: usize, message: &str) {
+        destroy_win(self.screen_window);
+        self.screen_window = create_screen_window();
+
+        let col = getmaxx(self.screen_window) / 2 - 20;
+        let row = getmaxy(self.screen_window) / 2 - 4;
+
+        mvwaddstr(self.screen_window, row, col, "New Game");
+        mvwaddstr(self.screen_window, row + 2, col, &format!("Name:  {}", name));
+        mvwaddstr(self.screen_window, row + 3, col, &format!("Class: < {} >", class.name()));
+        wattron(self.screen_window, A_DIM());
+        mvwaddstr(self.screen_window, row + 4, col + 7, class.description());
+        wattroff(self.screen_window, A_DIM());
+        mvwaddstr(self.screen_window, row + 5, col, &format!("Seed:  {}", seed));
+        if seed.is_empty() {
+            wattron(self.screen_window, A_DIM());
+            mvwaddstr(self.screen_window, row + 5, col + 7, "random");
+            wattroff(self.screen_window, A_DIM());
+        }
+
+        let active_row = match active_field {
+            0 => row + 2,
+            1 => row + 3,
+            _ => row + 5,
+        };
+        mvwaddch(self.screen_window, active_row, col - 2, resolve_item_cursor());
+
+        mvwaddstr(self.screen_window, row + 7, col, message);
+
+        self.draw_screen_help(" Type to edit, Left/Right class, Enter next/start, Esc back ");
+        wrefresh(self.screen_window);
+    }
+
+    fn draw_options(&mut self, level_size: &str) {
+        destroy_win(self.screen_window);
+        self.screen_window = create_screen_window();
+
+        let col = getmaxx(self.screen_window) / 2 - 16;
+        let row = getmaxy(self.screen_window) / 2 - 2;
+
+        mvwaddstr(self.screen_window, row, col, "Options");
+        mvwaddstr(self.screen_window, row + 2, col, &format!("Level size: < {} >", level_size));
+
+        self.draw_screen_help(" Left/Right change, Q back ");
+        wrefresh(self.screen_window);
+    }
+
+    fn draw_high_scores(&mut self, scores: &[HighScore]) {
+        destroy_win(self.screen_window);
+        self.screen_window = create_screen_window();
+
+        let col = getmaxx(self.screen_window) / 2 - 30;
+        let row = cmp::max(1, getmaxy(self.screen_window) / 2 - 8);
+
+        mvwaddstr(self.screen_window, row, col, "High Scores");
+
+        if scores.is_empty() {
+            mvwaddstr(self.screen_window, row + 2, col, "No high scores yet.");
+        } else {
+            wattron(self.screen_window, A_BOLD());
+            mvwaddstr(self.screen_window, row + 2, col, &format!("{:>2}  {:<16} {:<8} {:>5} {:>5} {:>6}", "#", "Name", "Class", "Depth", "Kills", "Turns"));
+            wattroff(self.screen_window, A_BOLD());
+
+            for (index, score) in scores.iter().enumerate() {
+                let line = format!("{:>2}. {:<16} {:<8} {:>5} {:>5} {:>6}", index + 1, score.name, score.class, score.depth, score.kills, score.turns);
+                mvwaddstr(self.screen_window, row + 3 + index as i32, col, &line);
+            }
+        }
+
+        self.draw_screen_help(" Q back ");
+        wrefresh(self.screen_window);
+    }
+
+    fn draw(&mut self, log: &Log, level: &Level, player: &Entity, enemies: &[Entity], specials: &[(&str, u32, i32)]) {
+
+        self.draw_player(player, level, specials);
+        self.draw_game_msg(log);
+        self.draw_map(level, player, enemies);
+
+    }
+
+    fn draw_loot(&mut self, backpack: &Backpack, backpack_index: usize, active: bool, name: &str) {
+        destroy_win(self.backpack_window);
+        self.backpack_window = create_backpack_window();
+
+        let mut loot_offset_row = 1;
+        let loot_offset_col = 2;
+        let display_row_count = 5;
+
+        let mut items: Vec<&Item> = Vec::new();
+        let start_index = display_row_count * (backpack_index / display_row_count);
+
+        //Fill items vector with items to display.
+        for index in start_index..start_index + display_row_count {
+            if !backpack.empty_slot(index) {
+                items.push(&backpack.items[index]);
+            }
+        }
+
+        //Display name
+        if name.len() > 0 {
+            mvwprintw(self.backpack_window, loot_offset_row as i32, loot_offset_col, &name);
+            loot_offset_row += 1;
+        }
+
+        //Display items.
+        let mut counter = 0;
+        for item in items {
+            wmove(self.backpack_window, (counter + loot_offset_row) as i32, 1 + loot_offset_col);
+            self.print_item_name(self.backpack_window, item);
+            counter += 1;
+        }
+
+        if active {
+            //Mark current items.
+            mvwaddch(self.backpack_window, ((backpack_index % display_row_count) + loot_offset_row) as i32, loot_offset_col, resolve_item_cursor());
+        }
+
+        //Fill empty spaces.
+        while counter < display_row_count {
+            mvwprintw(self.backpack_window, (counter + loot_offset_row) as i32, 1 + loot_offset_col, "Empty");
+            counter += 1;
+        }
+
+        if active {
+            //Draw full item
+            self.draw_item(&backpack.items[backpack_index]);
+        }
+
+        wrefresh(self.backpack_window);
+    }
+
+    fn draw_entity(&mut self, player: &Entity, character_pointer: Type, active: bool) {
+        destroy_win(self.character_window);
+        self.character_window = create_character_window();
+
+        let character_offset_row = 1;
+        let character_offset_col = 1;
+
+        mvwprintw(self.character_window, character_offset_row + 0, character_offset_col, " Head:     ");
+        self.print_item_name(self.character_window, &player.head_item);
+        mvwprintw(self.character_window, character_offset_row + 1, character_offset_col, " Chest:    ");
+        self.print_item_name(self.character_window, &player.chest_item);
+        mvwprintw(self.character_window, character_offset_row + 2, character_offset_col, " Legs:     ");
+        self.print_item_name(self.character_window, &player.leg_item);
+        mvwprintw(self.character_window, character_offset_row + 3, character_offset_col, " Weapon:   ");
+        self.print_item_name(self.character_window, &player.weapon);
+        mvwprintw(self.character_window, character_offset_row + 4, character_offset_col, "----------------");
+
+        let stats = player.calculate_stats();
+        let damage = player.weapon.get_damage();
+        mvwprintw(self.character_window, character_offset_row + 5, character_offset_col, &format!(" Vitality: {}", stats.vitality));
+        mvwprintw(self.character_window, character_offset_row + 6, character_offset_col, &format!(" Strength: {}", stats.strength));
+        mvwprintw(self.character_window, character_offset_row + 7, character_offset_col, &format!(" Defense:  {}", stats.defense));
+        mvwprintw(self.character_window, character_offset_row + 8, character_offset_col, &format!(" Speed:    {}", stats.speed));
+        mvwprintw(self.character_window, character_offset_row + 9, character_offset_col, &format!(" Intel.:   {}", stats.intelligence));
+        mvwprintw(self.character_window, character_offset_row + 10, character_offset_col, &format!(" Damage:   {}-{}", damage.0, damage.1));
+
+        if active {
+            match character_pointer {
+                Type::Head => {
+                    mvwaddch(self.character_window, (character_offset_row + 0) as i32, character_offset_col, resolve_item_cursor());
+                    self.draw_item(&player.head_item);
+                },
+                Type::Chest => {
+                    mvwaddch(self.character_window, (character_offset_row + 1) as i32, character_offset_col, resolve_item_cursor());
+                    self.draw_item(&player.chest_item);
+                },
+                Type::Legs => {
+                    mvwaddch(self.character_window, (character_offset_row + 2) as i32, character_offset_col, resolve_item_cursor());
+                    self.draw_item(&player.leg_item);
+                },
+                Type::Weapon => {
+                    mvwaddch(self.character_window, (character_offset_row + 3) as i32, character_offset_col, resolve_item_cursor());
+                    self.draw_item(&player.weapon);
+                },
+                _ => {},
+            }
+        }
+
+        wrefresh(self.character_window);
+    }
+
+    fn draw_menu(&mut self) {
+        destroy_win(self.menu_window);
+        self.menu_window = create_menu_window();
+
+        let message = "Press Q to Return to Game".to_string();
+        mvwprintw(self.menu_window, 1, (33 - message.len() as i32) / 2 +1, &message);
+        let message = "Press E to Save and Exit".to_string();
+        mvwprintw(self.menu_window, 2, (33 - message.len() as i32) / 2 +1, &message);
+
+        wrefresh(self.menu_window);
+    }
+
+    /// Summary are a few lines about the death, high score and morgue.
+    fn draw_game_over(&mut self, summary: &[String]) {
+        destroy_win(self.menu_window);
+        self.menu_window = create_game_over_window();
+
+        let width = getmaxx(self.menu_window) - 2;
+        let center = |message: &str| (width - message.len() as i32) / 2 + 1;
+
+        let message = "Game Over";
+        mvwprintw(self.menu_window, 1, center(message), message);
+
+        for (index, line) in summary.iter().enumerate() {
+            mvwaddnstr(self.menu_window, 3 + index as i32, center(line).max(1), line, width);
+        }
+
+        let message = "Press E for Title, Q to Quit";
+        mvwprintw(self.menu_window, getmaxy(self.menu_window) - 2, center(message), message);
+
+        wrefresh(self.menu_window);
+    }
+
+    fn draw_history(&mut self, log: &Log, offset: usize) {
+        destroy_win(self.history_window);
+        self.history_window = create_history_window();
+
+        let line_count = (getmaxy(self.history_window) - 2) as usize;
+        let width = getmaxx(self.history_window) - 2;
+
+        let offset = std::cmp::min(offset, log.len().saturating_sub(line_count));
+        let end = log.len() - offset;
+        let start = end.saturating_sub(line_count);
+
+        for index in start..end {
+            if let Option::Some(entry) = log.get(index) {
+                let line = format!("{:>5} {}", entry.turn, entry.text());
+                mvwaddnstr(self.history_window, (index - start + 1) as i32, 1, &line, width);
+            }
+        }
+
+        mvwaddstr(self.history_window, 0, 2, " Message History ");
+        let help = " Up/Down scroll, Q close ";
+        mvwaddstr(self.history_window, getmaxy(self.history_window) - 1, width - help.len() as i32, help);
+
+        wrefresh(self.history_window);
+    }
+
+    fn draw_look(&mut self, row: usize, col: usize, description: &[String]) {
+        if let Option::Some((screen_row, screen_col)) = self.camera.to_screen(row as i32, col as i32) {
+            mvwchgat(self.map_window, screen_row + 1, screen_col + 1, 1, A_REVERSE(), 0);
+            wrefresh(self.map_window);
+        }
+
+        destroy_win(self.status_window);
+        self.status_window = create_status_window();
+
+        let width = getmaxx(self.status_window) - 2;
+        let line_count = (getmaxy(self.status_window) - 2) as usize;
+
+        //Small consoles show everything on one line.
+        let lines = if description.len() > line_count { vec![description.join(", ")] } else { description.to_vec() };
+        for (index, line) in lines.iter().take(line_count).enumerate() {
+            mvwaddnstr(self.status_window, index as i32 + 1, 1, line, width);
+        }
+
+        box_(self.status_window, 0, 0);
+        let help = " Arrows move, T travel, Q back ";
+        mvwaddstr(self.status_window, getmaxy(self.status_window) - 1, width - help.len() as i32, help);
+
+        wrefresh(self.status_window);
+    }
+
+    fn draw_target(&mut self, special: &str, direction: &str, area: &[(i32, i32)], targets: &[String]) {
+        for &(row, col) in area {
+            if let Option::Some((screen_row, screen_col)) = self.camera.to_screen(row, col) {
+                mvwchgat(self.map_window, screen_row + 1, screen_col + 1, 1, A_REVERSE(), 0);
+            }
+        }
+        wrefresh(self.map_window);
+
+        destroy_win(self.status_window);
+        self.status_window = create_status_window();
+
+        let width = getmaxx(self.status_window) - 2;
+        let hits = if targets.is_empty() { "nothing".to_string() } else { targets.join(", ") };
+        mvwaddnstr(self.status_window, 1, 1, &format!("{} to the {}, hits {}", special, direction, hits), width);
+
+        box_(self.status_window, 0, 0);
+        let help = " Arrows aim, Tab next, E use, Q cancel ";
+        mvwaddstr(self.status_window, getmaxy(self.status_window) - 1, width - help.len() as i32, help);
+
+        wrefresh(self.status_window);
+    }
+
+    fn draw_spellbook(&mut self, abilities: &[(&str, i32)], index: usize, slots: &[&str]) {
+        destroy_win(self.history_window);
+        self.history_window = create_history_window();
+
+        let width = getmaxx(self.history_window) - 2;
+
+        for (row, &(name, cost)) in abilities.iter().enumerate() {
+            let slot = slots.iter().position(|slot| *slot == name).map_or(String::new(), |slot| format!("slot {}", slot + 1));
+            let line = format!("  {:<12} {:>3} energy  {}", name, cost, slot);
+            mvwaddnstr(self.history_window, row as i32 + 1, 1, &line, width);
+
+            if row == index {
+                mvwaddch(self.history_window, row as i32 + 1, 1, resolve_item_cursor());
+            }
+        }
+
+        mvwaddstr(self.history_window, 0, 2, " Spellbook ");
+        let help = " Up/Down select, 1-3 bind, Q close ";
+        mvwaddstr(self.history_window, getmaxy(self.history_window) - 1, width - help.len() as i32, help);
+
+        wrefresh(self.history_window);
+    }
+
+    fn draw_dialogue(&mut self, name: &str, text: &[String], choices: &[&str], index: usize) {
+        destroy_win(self.history_window);
+        self.history_window = create_history_window();
+
+        let width = getmaxx(self.history_window) - 2;
+
+        for (row, line) in text.iter().enumerate() {
+            mvwaddnstr(self.history_window, row as i32 + 1, 2, line, width - 1);
+        }
+
+        let first = text.len() as i32 + 2;
+        for (row, choice) in choices.iter().enumerate() {
+            let line = format!("  {}. {}", row + 1, choice);
+            mvwaddnstr(self.history_window, first + row as i32, 1, &line, width);
+
+            if row == index {
+                mvwaddch(self.history_window, first + row as i32, 1, resolve_item_cursor());
+            }
+        }
+
+        mvwaddstr(self.history_window, 0, 2, &format!(" {} ", name));
+        let help = " Up/Down select, Enter answer, Q leave ";
+        mvwaddstr(self.history_window, getmaxy(self.history_window) - 1, width - help.len() as i32, help);
+
+        wrefresh(self.history_window);
+    }
+
+    fn draw_quests(&mut self, quests: &[(String, bool)]) {
+        destroy_win(self.history_window);
+        self.history_window = create_history_window();
+
+        let width = getmaxx(self.history_window) - 2;
+
+        if quests.is_empty() {
+            mvwaddnstr(self.history_window, 1, 2, "No quests yet, talk to people and read notes.", width - 1);
+        }
+
+        for (row, (quest, done)) in quests.iter().enumerate() {
+            let line = format!("{} {}", if *done { "[x]" } else { "[ ]" }, quest);
+            mvwaddnstr(self.history_window, row as i32 + 1, 2, &line, width - 1);
+        }
+
+        mvwaddstr(self.history_window, 0, 2, " Quests ");
+        let help = " Q close ";
+        mvwaddstr(self.history_window, getmaxy(self.history_window) - 1, width - help.len() as i32, help);
+
+        wrefresh(self.history_window);
+    }
+}
+
+/// Remembers hit entities, so they flash on the next map draw.
+impl Subscriber for Window {
+    fn notify(&mut self, event: &Event) {
+        match *event {
+            Event::Hit { ref target, .. } | Event::Crit { ref target, .. } => {
+                self.hit_ids.push(target.id);
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Creates a colour pair for every theme element, consoles with 8 colours get the nearest basic colour.
+fn create_palette(theme: &Theme) -> Vec<attr_t> {
+    if !has_colors() {
+        //At least keep important things visible.
+        return ELEMENTS.iter().map(|element| match *element {
+            Element::Highlight | Element::Stairs | Element::MonsterHard | Element::MonsterBoss | Element::Loot | Element::Legendary => A_BOLD(),
+            _ => 0,
+        }).collect();
+    }
 
-    MoveUp,
-    MoveDown,
-    MoveLeft,
-    MoveRight,
+    ELEMENTS.iter().enumerate().map(|(index, element)| {
+        let pair = index as i16 + 1;
+        let mut color = theme.color(*element);
+        let mut attr = 0;
 
-    Quit,
-    Use,
-    Drop,
+        if color as i32 >= COLORS() {
+            let (basic, bold) = to_basic(color);
+            color = basic;
 
-    SpecialOne,
-    SpecialTwo,
-    SpecialThree,
+            if bold {
+                attr = A_BOLD();
+            }
+        }
 
-    AttackUp,
-    AttackDown,
-    AttackLeft,
-    AttackRight,
+        //-1 keeps the background of the console.
+        init_pair(pair, color, -1);
+        COLOR_PAIR(pair) | attr
+    }).collect()
+}
+
+/// Top left corner of the 80x24 area, in which the popups are centered.
+fn popup_offset() -> (i32, i32) {
+    (cmp::max(0, (LINES() - 24) / 2), cmp::max(0, (COLS() - 80) / 2))
+}
+
+pub fn create_game_over_window() -> WINDOW {
+    let (row, col) = popup_offset();
+    create_windows(9, 60, row + 7, col + 10)
 }
 
 pub fn create_menu_window() -> WINDOW {
-    create_windows(5, 35, 9, 25)
+    let (row, col) = popup_offset();
+    create_windows(5, 35, row + 9, col + 25)
 }
 
 pub fn create_backpack_window() -> WINDOW {
-    create_windows(8, 25, 5, 51)
+    let (row, col) = popup_offset();
+    create_windows(8, 25, row + 5, col + 51)
 }
 
 pub fn create_character_window() -> WINDOW {
-    create_windows(12, 25, 5, 1)
+    let (row, col) = popup_offset();
+    create_windows(13, 25, row + 5, col + 1)
 }
 
 fn create_item_window() -> WINDOW {
-    create_windows(7, 25, 5, 26)
+    let (row, col) = popup_offset();
+    create_windows(7, 25, row + 5, col + 26)
+}
+
+/// At least three messages, the camera scrolls the map when it does not fit above them.
+/// Taller consoles show more messages.
+fn status_height() -> i32 {
+    (LINES() - 19).clamp(5, 8)
 }
 
 fn create_status_window() -> WINDOW {
-    create_windows(3, 80, 21, 0)
+    create_windows(status_height(), COLS(), LINES() - status_height(), 0)
+}
+
+fn create_history_window() -> WINDOW {
+    create_windows(LINES(), COLS(), 0, 0)
+}
+
+/// Full screen window for the screens before the game.
+fn create_screen_window() -> WINDOW {
+    create_windows(LINES(), COLS(), 0, 0)
 }
 
+/// The map takes all space between player and status window, borders overlap.
 fn create_map_window() -> WINDOW {
-    create_windows(20, 80, 2, 0)
+    create_windows(LINES() - status_height() - 1, COLS(), 2, 0)
 }
 
 fn create_player_window() -> WINDOW {
-    create_windows(3, 80, 0, 0)
+    create_windows(3, COLS(), 0, 0)
 }
 
 fn create_windows(height: i32, width: i32, start_row: i32, start_col: i32) -> WINDOW {
@@ -488,6 +962,36 @@ fn resolve_input(input: i32) -> Input {
         113 => Input::Quit, //113 is q.
         101 => Input::Use, //101 is e.
         114 => Input::Drop, //114 is r.
+        108 => Input::History, //108 is l.
+        111 => Input::Explore, //111 is o.
+        116 => Input::Travel, //116 is t.
+        120 => Input::Look, //120 is x.
+        98 => Input::Spellbook, //98 is b.
+        106 => Input::Quests, //106 is j.
+        102 => Input::Order, //102 is f.
+        103 => Input::Give, //103 is g.
+        9 => Input::NextTarget, //9 is tab.
+
+        KEY_RESIZE => Input::Resize,
+        10 | KEY_ENTER => Input::Use, //10 is enter.
+
+        _ => Input::Nothing,
+    }
+}
+
+fn resolve_text_input(input: i32) -> Input {
+    match input {
+        KEY_UP => Input::MoveUp,
+        KEY_DOWN => Input::MoveDown,
+        KEY_LEFT => Input::MoveLeft,
+        KEY_RIGHT => Input::MoveRight,
+        KEY_RESIZE => Input::Resize,
+
+        10 | KEY_ENTER => Input::Use,
+        27 => Input::Quit, //27 is escape.
+        8 | 127 | KEY_BACKSPACE => Input::Backspace,
+
+        32..=126 => Input::Char(input as u8 as char),
 
         _ => Input::Nothing,
     }
@@ -510,6 +1014,9 @@ fn resolve_type(item_type: Type) -> &'static str {
         Type::Potion => {
             "Potion"
         },
+        Type::Scroll => {
+            "Scroll"
+        },
         Type::Nothing => {
             "Nothing"
         }
@@ -520,90 +1027,43 @@ fn resolve_item_cursor() -> u32 {
     '>' as u32
 }
 
-fn resolve_enemy(enemy: &Entity) -> u32 {
+
+/*fn resolve_effect() -> u32 {
+    '-' as u32
+}*/
+
+fn resolve_enemy_element(enemy: &Entity) -> Element {
     if enemy.is_death() && enemy.backpack.size() > 0 {
-        'O' as u32
+        Element::Loot
     } else if enemy.is_death() {
-        '_' as u32
+        Element::Corpse
+    } else if enemy.is_companion() {
+        Element::Companion
+    } else if enemy.is_friendly() {
+        Element::Npc
     } else {
-        match enemy.monster_type {
-            MonsterType::Unknown => {
-                '?' as u32
-            },
-            MonsterType::Zombie => {
-                match enemy.monster_difficulty {
-                    Difficulty::Easy => {
-                        'f' as u32
-                    },
-                    Difficulty::Normal => {
-                        'F' as u32
-                    },
-                    Difficulty::Hard => {
-                        'Ḟ' as u32
-                    },
-                    _ => {
-                        unreachable!();
-                    }
-                }
-            },
-            MonsterType::Crab => {
-                match enemy.monster_difficulty {
-                    Difficulty::Easy => {
-                        'm' as u32
-                    },
-                    Difficulty::Normal => {
-                        'm' as u32
-                    },
-                    Difficulty::Hard => {
-                        'Ṁ' as u32
-                    },
-                    _ => {
-                        unreachable!();
-                    }
-                }
-            },
-            MonsterType::Goblin => {
-                match enemy.monster_difficulty {
-                    Difficulty::Easy => {
-                        'x' as u32
-                    },
-                    Difficulty::Normal => {
-                        'X' as u32
-                    },
-                    Difficulty::Hard => {
-                        'Ẋ' as u32
-                    },
-                    _ => {
-                        unreachable!();
-                    }
-                }
-            }
-            _ => {
-                unreachable!();
-            }
+        match enemy.monster_difficulty {
+            Difficulty::Easy => Element::MonsterEasy,
+            Difficulty::Normal => Element::MonsterNormal,
+            Difficulty::Hard => Element::MonsterHard,
+            Difficulty::Boss => Element::MonsterBoss,
         }
     }
 }
 
-/*fn resolve_effect() -> u32 {
-    '-' as u32
-}*/
-
-fn resolve_player(player: &Entity) -> u32 {
-    if player.is_death() {
-        '_' as u32
-    } else {
-        '@' as u32
+fn resolve_rarity_element(rarity: Rarity) -> Element {
+    match rarity {
+        Rarity::Common => Element::Common,
+        Rarity::Uncommon => Element::Uncommon,
+        Rarity::Rare => Element::Rare,
+        Rarity::Legendary => Element::Legendary,
     }
 }
 
-fn resolve_tile(tile: &Tile) -> u32 {
-    match tile {
-        &Tile::Floor => '.' as u32,
-        &Tile::Wall => '#' as u32,
-        &Tile::Nothing => ' ' as u32,
-        &Tile::PlSpawn => '<' as u32,
-        &Tile::MnSpawn { .. } => '?' as u32,
-        &Tile::Next => '>' as u32,
+fn resolve_tile_element(tile: &Tile) -> Element {
+    match *tile {
+        Tile::Wall => Element::Wall,
+        Tile::PlSpawn | Tile::Next => Element::Stairs,
+        _ => Element::Floor,
     }
 }